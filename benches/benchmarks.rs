@@ -4,6 +4,7 @@ use rand_chacha::ChaCha8Rng;
 
 use reed_solomon_16::{
     engine::{DefaultEngine, Engine, GfElement, Naive, NoSimd, ShardsRefMut, GF_ORDER},
+    gf8,
     rate::{
         HighRateDecoder, HighRateEncoder, LowRateDecoder, LowRateEncoder, RateDecoder, RateEncoder,
     },
@@ -96,11 +97,15 @@ fn benchmarks_main(c: &mut Criterion) {
                 &recovery,
                 |b, recovery| {
                     b.iter(|| {
-                        for index in 0..original_provided_count {
-                            decoder.add_original_shard(index, &original[index]).unwrap();
+                        for (index, original) in
+                            original.iter().enumerate().take(original_provided_count)
+                        {
+                            decoder.add_original_shard(index, original).unwrap();
                         }
-                        for index in 0..recovery_provided_count {
-                            decoder.add_recovery_shard(index, &recovery[index]).unwrap();
+                        for (index, recovery) in
+                            recovery.iter().enumerate().take(recovery_provided_count)
+                        {
+                            decoder.add_recovery_shard(index, recovery).unwrap();
                         }
                         decoder.decode().unwrap();
                     });
@@ -112,6 +117,98 @@ fn benchmarks_main(c: &mut Criterion) {
     group.finish();
 }
 
+// ======================================================================
+// BENCHMARKS - GF8
+
+// Benchmarks encoder and decoder given as `$encoder` and `$decoder` types.
+macro_rules! bench_codec {
+    ($group:ident, $name:literal, $encoder:ty, $decoder:ty, $original_count:ident, $recovery_count:ident) => {{
+        let original = generate_shards($original_count, SHARD_BYTES, 0);
+
+        let mut encoder = <$encoder>::new($original_count, $recovery_count, SHARD_BYTES).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let recovery: Vec<Vec<u8>> = encoder
+            .encode()
+            .unwrap()
+            .recovery_iter()
+            .map(|shard| shard.to_vec())
+            .collect();
+
+        let id = format!("{}:{}", $original_count, $recovery_count);
+
+        $group.bench_with_input(
+            BenchmarkId::new(concat!($name, "Encoder"), &id),
+            &original,
+            |b, original| {
+                b.iter(|| {
+                    for original in original {
+                        encoder.add_original_shard(original).unwrap();
+                    }
+                    encoder.encode().unwrap();
+                });
+            },
+        );
+
+        // Maximum loss, i.e. as many recovery shards as possible.
+        let recovery_provided_count = std::cmp::min($original_count, $recovery_count);
+        let original_provided_count = $original_count - recovery_provided_count;
+
+        let mut decoder = <$decoder>::new($original_count, $recovery_count, SHARD_BYTES).unwrap();
+
+        $group.bench_with_input(
+            BenchmarkId::new(concat!($name, "Decoder"), &id),
+            &recovery,
+            |b, recovery| {
+                b.iter(|| {
+                    for (index, original) in
+                        original.iter().enumerate().take(original_provided_count)
+                    {
+                        decoder.add_original_shard(index, original).unwrap();
+                    }
+                    for (index, recovery) in
+                        recovery.iter().enumerate().take(recovery_provided_count)
+                    {
+                        decoder.add_recovery_shard(index, recovery).unwrap();
+                    }
+                    decoder.decode().unwrap();
+                });
+            },
+        );
+    }};
+}
+
+fn benchmarks_gf8(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gf8");
+
+    for (original_count, recovery_count) in [(10, 4), (20, 6)] {
+        group.throughput(Throughput::Bytes(
+            ((original_count + recovery_count) * SHARD_BYTES) as u64,
+        ));
+
+        bench_codec!(
+            group,
+            "ReedSolomon",
+            ReedSolomonEncoder,
+            ReedSolomonDecoder,
+            original_count,
+            recovery_count
+        );
+
+        bench_codec!(
+            group,
+            "gf8::ReedSolomon",
+            gf8::ReedSolomonEncoder,
+            gf8::ReedSolomonDecoder,
+            original_count,
+            recovery_count
+        );
+    }
+
+    group.finish();
+}
+
 // ======================================================================
 // BENCHMARKS - RATE
 
@@ -218,11 +315,15 @@ fn benchmarks_rate_one<E: Engine>(c: &mut Criterion, name: &str, engine: E) {
             &recovery,
             |b, recovery| {
                 b.iter(|| {
-                    for index in 0..original_provided_count {
-                        decoder.add_original_shard(index, &original[index]).unwrap();
+                    for (index, original) in
+                        original.iter().enumerate().take(original_provided_count)
+                    {
+                        decoder.add_original_shard(index, original).unwrap();
                     }
-                    for index in 0..recovery_provided_count {
-                        decoder.add_recovery_shard(index, &recovery[index]).unwrap();
+                    for (index, recovery) in
+                        recovery.iter().enumerate().take(recovery_provided_count)
+                    {
+                        decoder.add_recovery_shard(index, recovery).unwrap();
                     }
                     decoder.decode().unwrap();
                 });
@@ -247,11 +348,15 @@ fn benchmarks_rate_one<E: Engine>(c: &mut Criterion, name: &str, engine: E) {
             &recovery,
             |b, recovery| {
                 b.iter(|| {
-                    for index in 0..original_provided_count {
-                        decoder.add_original_shard(index, &original[index]).unwrap();
+                    for (index, original) in
+                        original.iter().enumerate().take(original_provided_count)
+                    {
+                        decoder.add_original_shard(index, original).unwrap();
                     }
-                    for index in 0..recovery_provided_count {
-                        decoder.add_recovery_shard(index, &recovery[index]).unwrap();
+                    for (index, recovery) in
+                        recovery.iter().enumerate().take(recovery_provided_count)
+                    {
+                        decoder.add_recovery_shard(index, recovery).unwrap();
                     }
                     decoder.decode().unwrap();
                 });
@@ -275,15 +380,13 @@ fn benchmarks_engine_one<E: Engine>(c: &mut Criterion, name: &str, engine: E) {
 
     // XOR MUL
 
-    let mut x = &mut generate_shards(1, SHARD_BYTES, 0)[0];
+    let x = &mut generate_shards(1, SHARD_BYTES, 0)[0];
     let y = &generate_shards(1, SHARD_BYTES, 1)[0];
 
-    group.bench_function("xor", |b| {
-        b.iter(|| E::xor(black_box(&mut x), black_box(&y)))
-    });
+    group.bench_function("xor", |b| b.iter(|| E::xor(black_box(x), black_box(y))));
 
     group.bench_function("mul", |b| {
-        b.iter(|| engine.mul(black_box(&mut x), black_box(12345)))
+        b.iter(|| engine.mul(black_box(x), black_box(12345)))
     });
 
     // XOR_WITHIN
@@ -354,6 +457,7 @@ fn benchmarks_engine_one<E: Engine>(c: &mut Criterion, name: &str, engine: E) {
 // MAIN
 
 criterion_group!(benches_main, benchmarks_main);
+criterion_group!(benches_gf8, benchmarks_gf8);
 criterion_group!(benches_rate, benchmarks_rate);
 criterion_group!(benches_engine, benchmarks_engine);
-criterion_main!(benches_main, benches_rate, benches_engine);
+criterion_main!(benches_main, benches_gf8, benches_rate, benches_engine);
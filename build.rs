@@ -19,5 +19,35 @@ fn main() -> Result<(), Box<dyn Error>> {
             Some(CRATE_NAME),
         )?,
     )?;
+
+    #[cfg(feature = "capi")]
+    generate_c_header()?;
+
+    Ok(())
+}
+
+// ======================================================================
+// FUNCTIONS
+
+/// Generates C header from `src/capi.rs` into `OUT_DIR`.
+///
+/// `tests/capi.rs` checks that `include/reed_solomon_16.h` matches it.
+#[cfg(feature = "capi")]
+fn generate_c_header() -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("REED_SOLOMON_16_H".to_string()),
+        header: Some("/* Generated with cbindgen from src/capi.rs, do not edit. */".to_string()),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        ..Default::default()
+    };
+
+    cbindgen::Builder::new()
+        .with_src("src/capi.rs")
+        .with_config(config)
+        .generate()?
+        .write_to_file(PathBuf::from(env::var("OUT_DIR")?).join("reed_solomon_16.h"));
     Ok(())
 }
@@ -111,8 +111,8 @@ fn test_reed_solomon_erasure_8(count: usize) {
     for _ in 0..count {
         decoder_shards.push(None);
     }
-    for i in 0..count {
-        decoder_shards.push(Some(recovery[i].clone()));
+    for recovery in recovery.iter().take(count) {
+        decoder_shards.push(Some(recovery.clone()));
     }
 
     // DECODE
@@ -163,8 +163,8 @@ fn test_reed_solomon_erasure_16(count: usize) {
     for _ in 0..count {
         decoder_shards.push(None);
     }
-    for i in 0..count {
-        decoder_shards.push(Some(recovery[i].clone()));
+    for recovery in recovery.iter().take(count) {
+        decoder_shards.push(Some(recovery.clone()));
     }
 
     // DECODE
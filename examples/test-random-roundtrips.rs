@@ -180,6 +180,7 @@ fn main() {
 // ======================================================================
 // FUNCTIONS
 
+#[allow(clippy::too_many_arguments)]
 fn roundtrip<E, R>(
     original: &[Vec<u8>],
     original_count: usize,
@@ -0,0 +1,278 @@
+/* Generated with cbindgen from src/capi.rs, do not edit. */
+
+#ifndef REED_SOLOMON_16_H
+#define REED_SOLOMON_16_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Success.
+ */
+#define RS16_OK 0
+
+/**
+ * Given pointer was `NULL`.
+ */
+#define RS16_ERROR_NULL_POINTER 1
+
+/**
+ * See [`Error::ChecksumMismatch`].
+ */
+#define RS16_ERROR_CHECKSUM_MISMATCH 2
+
+/**
+ * See [`Error::DifferentShardSize`].
+ */
+#define RS16_ERROR_DIFFERENT_SHARD_SIZE 3
+
+/**
+ * See [`Error::DuplicateLocalParityShardIndex`].
+ */
+#define RS16_ERROR_DUPLICATE_LOCAL_PARITY_SHARD_INDEX 4
+
+/**
+ * See [`Error::DuplicateOriginalShardIndex`].
+ */
+#define RS16_ERROR_DUPLICATE_ORIGINAL_SHARD_INDEX 5
+
+/**
+ * See [`Error::DuplicateRecoveryShardIndex`].
+ */
+#define RS16_ERROR_DUPLICATE_RECOVERY_SHARD_INDEX 6
+
+/**
+ * See [`Error::DuplicateShardPosition`].
+ */
+#define RS16_ERROR_DUPLICATE_SHARD_POSITION 7
+
+/**
+ * See [`Error::InvalidLocalParityShardIndex`].
+ */
+#define RS16_ERROR_INVALID_LOCAL_PARITY_SHARD_INDEX 8
+
+/**
+ * See [`Error::InvalidOriginalShardIndex`].
+ */
+#define RS16_ERROR_INVALID_ORIGINAL_SHARD_INDEX 9
+
+/**
+ * See [`Error::InvalidPar2`].
+ */
+#define RS16_ERROR_INVALID_PAR2 10
+
+/**
+ * See [`Error::InvalidRecoveryShardIndex`].
+ */
+#define RS16_ERROR_INVALID_RECOVERY_SHARD_INDEX 11
+
+/**
+ * See [`Error::InvalidShardPosition`].
+ */
+#define RS16_ERROR_INVALID_SHARD_POSITION 12
+
+/**
+ * See [`Error::InvalidShardSize`].
+ */
+#define RS16_ERROR_INVALID_SHARD_SIZE 13
+
+/**
+ * See [`Error::NotEnoughShards`].
+ */
+#define RS16_ERROR_NOT_ENOUGH_SHARDS 14
+
+/**
+ * See [`Error::TooFewOriginalShards`].
+ */
+#define RS16_ERROR_TOO_FEW_ORIGINAL_SHARDS 15
+
+/**
+ * See [`Error::TooManyCorruptedShards`].
+ */
+#define RS16_ERROR_TOO_MANY_CORRUPTED_SHARDS 16
+
+/**
+ * See [`Error::TooManyOriginalShards`].
+ */
+#define RS16_ERROR_TOO_MANY_ORIGINAL_SHARDS 17
+
+/**
+ * See [`Error::UnsupportedShardCount`].
+ */
+#define RS16_ERROR_UNSUPPORTED_SHARD_COUNT 18
+
+/**
+ * Opaque handle to [`ReedSolomonDecoder`].
+ */
+typedef struct RS16Decoder RS16Decoder;
+
+/**
+ * Opaque handle to [`ReedSolomonEncoder`].
+ */
+typedef struct RS16Encoder RS16Encoder;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns static NUL-terminated description of given return code.
+ */
+const char *rs16_error_message(int code);
+
+/**
+ * Returns `true` if given `original_count` / `recovery_count`
+ * combination is supported, see [`ReedSolomonEncoder::supports`].
+ */
+bool rs16_supports(size_t original_count, size_t recovery_count);
+
+/**
+ * Adds one original shard of `shard_bytes` bytes to the encoder,
+ * see [`ReedSolomonEncoder::add_original_shard`].
+ *
+ * # Safety
+ *
+ * - `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+ * - `shard` must point to `shard_bytes` readable bytes.
+ */
+int rs16_encoder_add_original_shard(struct RS16Encoder *encoder,
+                                    const uint8_t *shard,
+                                    size_t shard_bytes);
+
+/**
+ * Encodes the added original shards, copying generated
+ * recovery shards to `recovery[0 .. recovery_count]`,
+ * see [`ReedSolomonEncoder::encode`].
+ *
+ * Encoder is then ready for new round of encoding.
+ *
+ * # Safety
+ *
+ * - `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+ * - `recovery` must point to `recovery_count` pointers,
+ *   each pointing to `shard_bytes` writable bytes.
+ */
+int rs16_encoder_encode(struct RS16Encoder *encoder, uint8_t *const *recovery);
+
+/**
+ * Frees encoder. Does nothing if `encoder` is `NULL`.
+ *
+ * # Safety
+ *
+ * `encoder` must be `NULL` or from [`rs16_encoder_new`] and not yet freed.
+ */
+void rs16_encoder_free(struct RS16Encoder *encoder);
+
+/**
+ * Creates new encoder into `*encoder`, see [`ReedSolomonEncoder::new`].
+ *
+ * On error `*encoder` is set to `NULL`.
+ *
+ * # Safety
+ *
+ * `encoder` must be writable.
+ */
+int rs16_encoder_new(size_t original_count,
+                     size_t recovery_count,
+                     size_t shard_bytes,
+                     struct RS16Encoder **encoder);
+
+/**
+ * Resets encoder to given configuration, see [`ReedSolomonEncoder::reset`].
+ *
+ * # Safety
+ *
+ * `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+ */
+int rs16_encoder_reset(struct RS16Encoder *encoder,
+                       size_t original_count,
+                       size_t recovery_count,
+                       size_t shard_bytes);
+
+/**
+ * Adds one original shard of `shard_bytes` bytes to the decoder,
+ * see [`ReedSolomonDecoder::add_original_shard`].
+ *
+ * # Safety
+ *
+ * - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+ * - `shard` must point to `shard_bytes` readable bytes.
+ */
+int rs16_decoder_add_original_shard(struct RS16Decoder *decoder,
+                                    size_t index,
+                                    const uint8_t *shard,
+                                    size_t shard_bytes);
+
+/**
+ * Adds one recovery shard of `shard_bytes` bytes to the decoder,
+ * see [`ReedSolomonDecoder::add_recovery_shard`].
+ *
+ * # Safety
+ *
+ * - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+ * - `shard` must point to `shard_bytes` readable bytes.
+ */
+int rs16_decoder_add_recovery_shard(struct RS16Decoder *decoder,
+                                    size_t index,
+                                    const uint8_t *shard,
+                                    size_t shard_bytes);
+
+/**
+ * Decodes the added shards, copying each restored original shard `i`
+ * to `original[i]`, see [`ReedSolomonDecoder::decode`].
+ *
+ * - `original[i]` is only used for original shards
+ *   which weren't added and can be `NULL` for others.
+ * - Decoder is then ready for new round of decoding.
+ *
+ * # Safety
+ *
+ * - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+ * - `original` must point to `original_count` pointers and each
+ *   of those used must point to `shard_bytes` writable bytes.
+ */
+int rs16_decoder_decode(struct RS16Decoder *decoder, uint8_t *const *original);
+
+/**
+ * Frees decoder. Does nothing if `decoder` is `NULL`.
+ *
+ * # Safety
+ *
+ * `decoder` must be `NULL` or from [`rs16_decoder_new`] and not yet freed.
+ */
+void rs16_decoder_free(struct RS16Decoder *decoder);
+
+/**
+ * Creates new decoder into `*decoder`, see [`ReedSolomonDecoder::new`].
+ *
+ * On error `*decoder` is set to `NULL`.
+ *
+ * # Safety
+ *
+ * `decoder` must be writable.
+ */
+int rs16_decoder_new(size_t original_count,
+                     size_t recovery_count,
+                     size_t shard_bytes,
+                     struct RS16Decoder **decoder);
+
+/**
+ * Resets decoder to given configuration, see [`ReedSolomonDecoder::reset`].
+ *
+ * # Safety
+ *
+ * `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+ */
+int rs16_decoder_reset(struct RS16Decoder *decoder,
+                       size_t original_count,
+                       size_t recovery_count,
+                       size_t shard_bytes);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* REED_SOLOMON_16_H */
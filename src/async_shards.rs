@@ -0,0 +1,532 @@
+//! Async adapters for reading and writing shards concurrently.
+//!
+//! Requires `async` feature.
+//!
+//! - [`ShardSource`] reads one shard, e.g. from [`AsyncRead`] with
+//!   [`AsyncReadSource`], from an object store or from a local file.
+//! - [`ShardSink`] writes one shard, e.g. to [`AsyncWrite`] with [`AsyncWriteSink`].
+//! - [`decode`] reads shards concurrently from all given sources.
+//!   As soon as enough shards have arrived, the remaining reads are
+//!   cancelled and the original shards are restored if needed.
+//!   This is also known as **hedged reads**: A slow or failed source
+//!   doesn't slow down reading as long as enough other sources are fast.
+//! - [`encode`] encodes and writes shards concurrently to all given sinks.
+//!
+//! These are runtime-agnostic: Futures are driven within the calling task,
+//! so nothing is spawned. Cancelling means dropping the futures
+//! returned by [`ShardSource::read_shard`].
+//!
+//! # Example
+//!
+//! ```rust
+//! # futures_executor::block_on(async {
+//! use futures_util::io::Cursor;
+//! use reed_solomon_16::{
+//!     async_shards::{self, AsyncReadSource},
+//!     ReedSolomonDecoder, ShardKind,
+//! };
+//!
+//! let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+//! let recovery = reed_solomon_16::encode(3, 2, original)?;
+//!
+//! // Original shard 1 is lost, so read all others and use first 3.
+//! let sources = [
+//!     (ShardKind::Original, 0, Cursor::new(original[0].to_vec())),
+//!     (ShardKind::Original, 2, Cursor::new(original[2].to_vec())),
+//!     (ShardKind::Recovery, 0, Cursor::new(recovery[0].clone())),
+//!     (ShardKind::Recovery, 1, Cursor::new(recovery[1].clone())),
+//! ]
+//! .map(|(kind, index, reader)| (kind, index, AsyncReadSource::new(reader, 64)));
+//!
+//! let mut decoder = ReedSolomonDecoder::new(3, 2, 64)?;
+//! let restored = async_shards::decode(&mut decoder, sources).await?;
+//! assert_eq!(restored, original);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! # }).unwrap();
+//! ```
+
+use std::io;
+
+use futures_util::{
+    future::BoxFuture,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    stream::{FuturesUnordered, StreamExt},
+};
+
+use crate::{Error, ReedSolomonDecoder, ReedSolomonEncoder, ShardKind};
+
+// ======================================================================
+// ShardSource - PUBLIC
+
+/// Source of a single shard.
+///
+/// This trait is object safe, so sources of different types can be
+/// used together as `Box<dyn ShardSource + Send>`.
+pub trait ShardSource {
+    /// Reads the whole shard.
+    fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>>;
+}
+
+impl<T: ShardSource + ?Sized> ShardSource for Box<T> {
+    fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+        (**self).read_shard()
+    }
+}
+
+// ======================================================================
+// ShardSink - PUBLIC
+
+/// Sink of a single shard.
+///
+/// This trait is object safe, so sinks of different types can be
+/// used together as `Box<dyn ShardSink + Send>`.
+pub trait ShardSink {
+    /// Writes the whole shard.
+    fn write_shard<'a>(&'a mut self, shard: &'a [u8]) -> BoxFuture<'a, io::Result<()>>;
+}
+
+impl<T: ShardSink + ?Sized> ShardSink for Box<T> {
+    fn write_shard<'a>(&'a mut self, shard: &'a [u8]) -> BoxFuture<'a, io::Result<()>> {
+        (**self).write_shard(shard)
+    }
+}
+
+// ======================================================================
+// AsyncReadSource - PUBLIC
+
+/// [`ShardSource`] which reads `shard_bytes` bytes from [`AsyncRead`].
+pub struct AsyncReadSource<R> {
+    reader: R,
+    shard_bytes: usize,
+}
+
+impl<R> AsyncReadSource<R> {
+    /// Creates new source which reads `shard_bytes` bytes from `reader`.
+    pub fn new(reader: R, shard_bytes: usize) -> Self {
+        Self {
+            reader,
+            shard_bytes,
+        }
+    }
+
+    /// Returns the reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Send + Unpin> ShardSource for AsyncReadSource<R> {
+    fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+        Box::pin(async move {
+            let mut shard = vec![0; self.shard_bytes];
+            self.reader.read_exact(&mut shard).await?;
+            Ok(shard)
+        })
+    }
+}
+
+// ======================================================================
+// AsyncWriteSink - PUBLIC
+
+/// [`ShardSink`] which writes to [`AsyncWrite`] and then flushes it.
+pub struct AsyncWriteSink<W> {
+    writer: W,
+}
+
+impl<W> AsyncWriteSink<W> {
+    /// Creates new sink which writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: AsyncWrite + Send + Unpin> ShardSink for AsyncWriteSink<W> {
+    fn write_shard<'a>(&'a mut self, shard: &'a [u8]) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move {
+            self.writer.write_all(shard).await?;
+            self.writer.flush().await
+        })
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Reads shards concurrently from `sources` and returns all original shards.
+///
+/// - `sources` gives kind and index of the shard each source provides.
+///   Several sources may provide the same shard, e.g. replicas,
+///   in which case the first one to arrive is used.
+/// - All reads are started at once. As soon as `decoder` has enough shards,
+///   remaining reads are cancelled and missing original shards are restored.
+///   When all original shards arrive first, no decoding is done.
+/// - Failed reads are ignored, as are shards which `decoder` rejects,
+///   e.g. because of wrong size or invalid index.
+///   If all reads finish before enough shards have arrived,
+///   [`Error::NotEnoughShards`] is returned.
+///
+/// `decoder` must be configured for the shards, without any shards added.
+/// On success it is then ready for new round of decoding,
+/// on error it should be [`reset`](ReedSolomonDecoder::reset).
+pub async fn decode<S, I>(
+    decoder: &mut ReedSolomonDecoder,
+    sources: I,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+    S: ShardSource,
+    I: IntoIterator<Item = (ShardKind, usize, S)>,
+{
+    let mut original: Vec<Option<Vec<u8>>> = vec![None; decoder.original_count()];
+    let mut recovery_received = vec![false; decoder.recovery_count()];
+
+    let mut reads: FuturesUnordered<_> = sources
+        .into_iter()
+        .map(|(kind, index, mut source)| async move {
+            let shard = source.read_shard().await;
+            (kind, index, shard)
+        })
+        .collect();
+
+    while !decoder.can_decode() {
+        let (kind, index, shard) = match reads.next().await {
+            Some((kind, index, Ok(shard))) => (kind, index, shard),
+            Some((_, _, Err(_))) => continue,
+            None => break,
+        };
+
+        match kind {
+            ShardKind::Original => {
+                if original.get(index).is_some_and(Option::is_some) {
+                    continue;
+                }
+                if decoder.add_original_shard(index, &shard).is_ok() {
+                    original[index] = Some(shard);
+                }
+            }
+
+            ShardKind::Recovery => {
+                if recovery_received.get(index) == Some(&true) {
+                    continue;
+                }
+                if decoder.add_recovery_shard(index, &shard).is_ok() {
+                    recovery_received[index] = true;
+                }
+            }
+        }
+    }
+
+    // Cancel remaining reads.
+    drop(reads);
+
+    let result = decoder.decode()?;
+    Ok(original
+        .into_iter()
+        .enumerate()
+        .map(|(index, shard)| match shard {
+            Some(shard) => shard,
+            None => result.restored_original(index).unwrap().to_vec(),
+        })
+        .collect())
+}
+
+/// Encodes `original` shards and writes all shards concurrently to `sinks`.
+///
+/// - `sinks` gives kind and index of the shard each sink receives.
+///   Several sinks may receive the same shard, e.g. replicas.
+/// - All writes are started at once and all must succeed.
+///   On first failure remaining writes are cancelled and the error is returned.
+/// - Errors from `encoder` and invalid shard indexes are returned
+///   as [`io::ErrorKind::InvalidInput`] with the [`Error`] as inner error.
+///
+/// `encoder` must be configured for `original`, without any shards added.
+/// On success it is then ready for new round of encoding,
+/// on error it should be [`reset`](ReedSolomonEncoder::reset).
+pub async fn encode<K, I, T>(
+    encoder: &mut ReedSolomonEncoder,
+    original: &[T],
+    sinks: I,
+) -> io::Result<()>
+where
+    K: ShardSink,
+    I: IntoIterator<Item = (ShardKind, usize, K)>,
+    T: AsRef<[u8]>,
+{
+    let invalid_input = |err: Error| io::Error::new(io::ErrorKind::InvalidInput, err);
+
+    for original_shard in original {
+        encoder
+            .add_original_shard(original_shard)
+            .map_err(invalid_input)?;
+    }
+
+    let original_count = original.len();
+    let result = encoder.encode().map_err(invalid_input)?;
+
+    let mut sinks_with_shard = Vec::new();
+    for (kind, index, sink) in sinks {
+        let shard = match kind {
+            ShardKind::Original => {
+                original
+                    .get(index)
+                    .map(AsRef::as_ref)
+                    .ok_or(Error::InvalidOriginalShardIndex {
+                        original_count,
+                        index,
+                    })
+            }
+            ShardKind::Recovery => result
+                .recovery(index)
+                .ok_or(Error::InvalidRecoveryShardIndex {
+                    recovery_count: result.recovery_iter().count(),
+                    index,
+                }),
+        }
+        .map_err(invalid_input)?;
+        sinks_with_shard.push((sink, shard));
+    }
+
+    let mut writes: FuturesUnordered<_> = sinks_with_shard
+        .iter_mut()
+        .map(|(sink, shard)| sink.write_shard(shard))
+        .collect();
+
+    while let Some(write) = writes.next().await {
+        write?;
+    }
+
+    Ok(())
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::Poll,
+    };
+
+    use futures_executor::block_on;
+    use futures_util::{future, io::Cursor};
+
+    use super::*;
+    use crate::test_util;
+
+    // Source which yields once before returning the shard, like real I/O,
+    // so that all reads are started before any of them completes.
+    struct MemorySource(Vec<u8>);
+
+    impl ShardSource for MemorySource {
+        fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+            Box::pin(async move {
+                let mut yielded = false;
+                future::poll_fn(|cx| {
+                    if yielded {
+                        Poll::Ready(())
+                    } else {
+                        yielded = true;
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                })
+                .await;
+                Ok(self.0.clone())
+            })
+        }
+    }
+
+    // Source whose read never completes, counting
+    // how many of its reads have been started and cancelled.
+    #[derive(Clone, Default)]
+    struct NeverSource {
+        started: Arc<AtomicUsize>,
+        cancelled: Arc<AtomicUsize>,
+    }
+
+    struct DropCounter(Arc<AtomicUsize>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl ShardSource for NeverSource {
+        fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+            self.started.fetch_add(1, Ordering::SeqCst);
+            let cancelled = DropCounter(self.cancelled.clone());
+            Box::pin(async move {
+                let _cancelled = cancelled;
+                future::pending().await
+            })
+        }
+    }
+
+    struct FailSource;
+
+    impl ShardSource for FailSource {
+        fn read_shard(&mut self) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+            Box::pin(async { Err(io::ErrorKind::ConnectionReset.into()) })
+        }
+    }
+
+    fn ready(shard: &[u8]) -> Box<dyn ShardSource + Send> {
+        Box::new(MemorySource(shard.to_vec()))
+    }
+
+    #[test]
+    fn decode_cancels_slow_sources() {
+        let original = test_util::generate_original(3, 64, 0);
+        let recovery = crate::encode(3, 2, &original).unwrap();
+        let never = NeverSource::default();
+
+        let sources: Vec<(ShardKind, usize, Box<dyn ShardSource + Send>)> = vec![
+            (ShardKind::Original, 0, Box::new(never.clone())),
+            (ShardKind::Original, 1, ready(&original[1])),
+            (ShardKind::Original, 2, Box::new(FailSource)),
+            (ShardKind::Recovery, 0, ready(&recovery[0])),
+            (ShardKind::Recovery, 1, ready(&recovery[1])),
+        ];
+
+        let mut decoder = ReedSolomonDecoder::new(3, 2, 64).unwrap();
+        let restored = block_on(decode(&mut decoder, sources)).unwrap();
+
+        assert_eq!(restored, original);
+        assert_eq!(never.started.load(Ordering::SeqCst), 1);
+        assert_eq!(never.cancelled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn decode_replicas_without_decoding() {
+        let original = test_util::generate_original(2, 64, 1);
+
+        // Each original shard has two replicas, one of which is slow.
+        let never = NeverSource::default();
+        let sources: Vec<(ShardKind, usize, Box<dyn ShardSource + Send>)> = vec![
+            (ShardKind::Original, 0, ready(&original[0])),
+            (ShardKind::Original, 0, ready(&original[0])),
+            (ShardKind::Original, 1, ready(&original[1])),
+            (ShardKind::Original, 1, Box::new(never.clone())),
+        ];
+
+        let mut decoder = ReedSolomonDecoder::new(2, 1, 64).unwrap();
+        let restored = block_on(decode(&mut decoder, sources)).unwrap();
+
+        assert_eq!(restored, original);
+        assert_eq!(never.started.load(Ordering::SeqCst), 1);
+        assert_eq!(never.cancelled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn decode_not_enough_shards() {
+        let original = test_util::generate_original(2, 64, 2);
+
+        let sources: Vec<(ShardKind, usize, Box<dyn ShardSource + Send>)> = vec![
+            (ShardKind::Original, 0, ready(&original[0])),
+            (ShardKind::Original, 1, Box::new(FailSource)),
+        ];
+
+        let mut decoder = ReedSolomonDecoder::new(2, 1, 64).unwrap();
+        assert_eq!(
+            block_on(decode(&mut decoder, sources)),
+            Err(Error::NotEnoughShards {
+                original_count: 2,
+                original_received_count: 1,
+                recovery_received_count: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_skips_rejected_shards() {
+        let original = test_util::generate_original(2, 64, 5);
+        let recovery = crate::encode(2, 1, &original).unwrap();
+
+        let sources: Vec<(ShardKind, usize, Box<dyn ShardSource + Send>)> = vec![
+            (ShardKind::Original, 0, ready(&original[0][..32])),
+            (ShardKind::Original, 5, ready(&original[0])),
+            (ShardKind::Original, 1, ready(&original[1])),
+            (ShardKind::Recovery, 0, ready(&recovery[0])),
+        ];
+
+        let mut decoder = ReedSolomonDecoder::new(2, 1, 64).unwrap();
+        let restored = block_on(decode(&mut decoder, sources)).unwrap();
+
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn decode_not_enough_valid_shards() {
+        let original = test_util::generate_original(2, 64, 6);
+
+        let sources: Vec<(ShardKind, usize, Box<dyn ShardSource + Send>)> = vec![
+            (ShardKind::Original, 0, ready(&original[0])),
+            (ShardKind::Original, 1, ready(&original[1][..32])),
+        ];
+
+        let mut decoder = ReedSolomonDecoder::new(2, 1, 64).unwrap();
+        assert_eq!(
+            block_on(decode(&mut decoder, sources)),
+            Err(Error::NotEnoughShards {
+                original_count: 2,
+                original_received_count: 1,
+                recovery_received_count: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn encode_to_sinks() {
+        let original = test_util::generate_original(3, 64, 3);
+        let recovery = crate::encode(3, 2, &original).unwrap();
+
+        let mut written: Vec<Cursor<Vec<u8>>> = vec![Cursor::new(Vec::new()); 3];
+        let sinks = written
+            .iter_mut()
+            .zip([
+                (ShardKind::Original, 2),
+                (ShardKind::Recovery, 0),
+                (ShardKind::Recovery, 1),
+            ])
+            .map(|(writer, (kind, index))| (kind, index, AsyncWriteSink::new(writer)));
+
+        let mut encoder = ReedSolomonEncoder::new(3, 2, 64).unwrap();
+        block_on(encode(&mut encoder, &original, sinks)).unwrap();
+
+        assert_eq!(written[0].get_ref(), &original[2]);
+        assert_eq!(written[1].get_ref(), &recovery[0]);
+        assert_eq!(written[2].get_ref(), &recovery[1]);
+    }
+
+    #[test]
+    fn encode_invalid_index() {
+        let original = test_util::generate_original(3, 64, 4);
+
+        let sinks = [(
+            ShardKind::Recovery,
+            2,
+            AsyncWriteSink::new(Cursor::new(Vec::new())),
+        )];
+
+        let mut encoder = ReedSolomonEncoder::new(3, 2, 64).unwrap();
+        let err = block_on(encode(&mut encoder, &original, sinks)).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<Error>(),
+            Some(&Error::InvalidRecoveryShardIndex {
+                recovery_count: 2,
+                index: 2
+            })
+        );
+    }
+}
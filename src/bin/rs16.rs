@@ -0,0 +1,586 @@
+//! `rs16` - split a file into Reed-Solomon shard files and join them back.
+//!
+//! ```text
+//! rs16 encode <FILE> <ORIGINAL_COUNT> <RECOVERY_COUNT> [OUT_DIR]
+//! rs16 decode <OUTPUT> <SHARD_FILE>...
+//! ```
+//!
+//! `encode` writes `ORIGINAL_COUNT + RECOVERY_COUNT` shard files
+//! named `<FILE name>.<index>.rs16` where `index` is `0 .. ORIGINAL_COUNT`
+//! for original shards followed by recovery shards,
+//! zero-padded to five digits, e.g. `data.bin.00012.rs16`.
+//!
+//! `decode` restores the file from any sufficient subset of shard files.
+//! Shard files with mismatching checksum are skipped with a warning.
+//! Checksum covers both header and shard data, so shard files
+//! with corrupted index or length are skipped too.
+//! All shard files must have the same set ID, so shard files
+//! of different files, or of different encodings of a file, can't be mixed.
+//!
+//! # Shard file format
+//!
+//! Each shard file is a 52-byte header followed by shard data.
+//! All integers are little-endian.
+//!
+//! | Offset | Size | Field                                |
+//! | ------ | ---- | ------------------------------------ |
+//! | 0      | 4    | magic `b"RS16"`                      |
+//! | 4      | 1    | format version, currently `1`        |
+//! | 5      | 1    | shard kind, `0` original `1` recovery |
+//! | 6      | 2    | reserved, `0`                        |
+//! | 8      | 4    | `original_count`                     |
+//! | 12     | 4    | `recovery_count`                     |
+//! | 16     | 4    | shard index within its kind          |
+//! | 20     | 4    | `shard_bytes`                        |
+//! | 24     | 8    | length of the original file          |
+//! | 32     | 16   | set ID, random for each `encode`     |
+//! | 48     | 4    | CRC32C of the file, see below        |
+//!
+//! CRC32C is calculated over the whole shard file
+//! with this checksum field set to zero.
+//!
+//! The file is zero-padded to `original_count * shard_bytes` bytes
+//! and split into original shards in order.
+
+use std::{
+    collections::hash_map::RandomState,
+    env, fs,
+    hash::{BuildHasher, Hasher},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::SystemTime,
+};
+
+use reed_solomon_16::{checksum, ReedSolomonDecoder, ReedSolomonEncoder};
+
+// ======================================================================
+// CONST - PRIVATE
+
+const MAGIC: &[u8; 4] = b"RS16";
+const VERSION: u8 = 1;
+const HEADER_BYTES: usize = 52;
+const SET_ID_POS: usize = 32;
+const CHECKSUM_POS: usize = 48;
+
+const USAGE: &str = "\
+Usage:
+    rs16 encode <FILE> <ORIGINAL_COUNT> <RECOVERY_COUNT> [OUT_DIR]
+    rs16 decode <OUTPUT> <SHARD_FILE>...";
+
+// ======================================================================
+// Header - PRIVATE
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Header {
+    recovery: bool,
+    original_count: usize,
+    recovery_count: usize,
+    index: usize,
+    shard_bytes: usize,
+    file_len: u64,
+    set_id: [u8; 16],
+    checksum: u32,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() < HEADER_BYTES || &data[0..4] != MAGIC {
+            return Err("not a rs16 shard file".to_string());
+        }
+        if data[4] != VERSION {
+            return Err(format!("unsupported format version {}", data[4]));
+        }
+
+        let u32_at = |pos: usize| u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+
+        let header = Self {
+            recovery: match data[5] {
+                0 => false,
+                1 => true,
+                kind => return Err(format!("invalid shard kind {}", kind)),
+            },
+            original_count: u32_at(8) as usize,
+            recovery_count: u32_at(12) as usize,
+            index: u32_at(16) as usize,
+            shard_bytes: u32_at(20) as usize,
+            file_len: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+            set_id: data[SET_ID_POS..SET_ID_POS + 16].try_into().unwrap(),
+            checksum: u32_at(CHECKSUM_POS),
+        };
+
+        // Checked before anything is allocated based on these counts.
+        if !ReedSolomonDecoder::supports(header.original_count, header.recovery_count) {
+            return Err(format!(
+                "unsupported shard count {} original + {} recovery",
+                header.original_count, header.recovery_count
+            ));
+        }
+
+        if data.len() - HEADER_BYTES != header.shard_bytes {
+            return Err(format!(
+                "expected {} bytes of shard data, got {}",
+                header.shard_bytes,
+                data.len() - HEADER_BYTES
+            ));
+        }
+
+        Ok(header)
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.recovery as u8);
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&(self.original_count as u32).to_le_bytes());
+        out.extend_from_slice(&(self.recovery_count as u32).to_le_bytes());
+        out.extend_from_slice(&(self.index as u32).to_le_bytes());
+        out.extend_from_slice(&(self.shard_bytes as u32).to_le_bytes());
+        out.extend_from_slice(&self.file_len.to_le_bytes());
+        out.extend_from_slice(&self.set_id);
+        out.extend_from_slice(&self.checksum.to_le_bytes());
+    }
+
+    /// Returns checksum of shard file `file` which has
+    /// a complete header, see [shard file format](self#shard-file-format).
+    fn checksum(file: &[u8]) -> u32 {
+        let mut file = file.to_vec();
+        file[CHECKSUM_POS..HEADER_BYTES].fill(0);
+        checksum::crc32c(&file)
+    }
+
+    /// Returns `true` if this and `other` belong to same shard set.
+    fn same_set(&self, other: &Header) -> bool {
+        self.original_count == other.original_count
+            && self.recovery_count == other.recovery_count
+            && self.shard_bytes == other.shard_bytes
+            && self.file_len == other.file_len
+            && self.set_id == other.set_id
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE - encode/decode
+
+/// Returns contents of shard files, original shards first
+/// and then recovery shards.
+fn encode(
+    data: &[u8],
+    original_count: usize,
+    recovery_count: usize,
+) -> Result<Vec<Vec<u8>>, String> {
+    if !ReedSolomonEncoder::supports(original_count, recovery_count) {
+        return Err(format!(
+            "unsupported shard count {} original + {} recovery",
+            original_count, recovery_count
+        ));
+    }
+
+    let shard_bytes = data.len().div_ceil(original_count).div_ceil(64).max(1) * 64;
+    if shard_bytes > u32::MAX as usize {
+        return Err("file is too large for given original count".to_string());
+    }
+
+    let mut padded = data.to_vec();
+    padded.resize(original_count * shard_bytes, 0);
+
+    let mut encoder = ReedSolomonEncoder::new(original_count, recovery_count, shard_bytes)
+        .map_err(|err| err.to_string())?;
+    for original in padded.chunks_exact(shard_bytes) {
+        encoder
+            .add_original_shard(original)
+            .map_err(|err| err.to_string())?;
+    }
+    let result = encoder.encode().map_err(|err| err.to_string())?;
+
+    let set_id = random_set_id();
+    let shard_file = |recovery: bool, index: usize, shard: &[u8]| {
+        let mut out = Vec::with_capacity(HEADER_BYTES + shard_bytes);
+        Header {
+            recovery,
+            original_count,
+            recovery_count,
+            index,
+            shard_bytes,
+            file_len: data.len() as u64,
+            set_id,
+            checksum: 0,
+        }
+        .write(&mut out);
+        out.extend_from_slice(shard);
+
+        let checksum = Header::checksum(&out);
+        out[CHECKSUM_POS..HEADER_BYTES].copy_from_slice(&checksum.to_le_bytes());
+        out
+    };
+
+    let mut files: Vec<Vec<u8>> = padded
+        .chunks_exact(shard_bytes)
+        .enumerate()
+        .map(|(index, shard)| shard_file(false, index, shard))
+        .collect();
+
+    files.extend(
+        result
+            .recovery_iter()
+            .enumerate()
+            .map(|(index, shard)| shard_file(true, index, shard)),
+    );
+
+    Ok(files)
+}
+
+/// Restores file from shard files given as `(name, contents)`,
+/// returning it and a warning for each skipped shard file.
+fn decode<'a, I>(files: I) -> Result<(Vec<u8>, Vec<String>), String>
+where
+    I: IntoIterator<Item = (&'a str, &'a [u8])>,
+{
+    let mut warnings = Vec::new();
+    let mut set: Option<Header> = None;
+    let mut original: Vec<Option<&[u8]>> = Vec::new();
+    let mut recovery: Vec<Option<&[u8]>> = Vec::new();
+
+    for (name, file) in files {
+        let header = match Header::parse(file) {
+            Ok(header) => header,
+            Err(err) => {
+                warnings.push(format!("skipping {}: {}", name, err));
+                continue;
+            }
+        };
+
+        if Header::checksum(file) != header.checksum {
+            warnings.push(format!("skipping {}: checksum mismatch", name));
+            continue;
+        }
+
+        let shard = &file[HEADER_BYTES..];
+
+        match &set {
+            None => {
+                original = vec![None; header.original_count];
+                recovery = vec![None; header.recovery_count];
+                set = Some(header);
+            }
+            Some(set) if !set.same_set(&header) => {
+                return Err(format!("{} belongs to a different shard set", name));
+            }
+            Some(_) => {}
+        }
+
+        let slots = if header.recovery {
+            &mut recovery
+        } else {
+            &mut original
+        };
+
+        match slots.get_mut(header.index) {
+            Some(slot) => *slot = Some(shard),
+            None => warnings.push(format!("skipping {}: invalid shard index", name)),
+        }
+    }
+
+    let set = set.ok_or("no valid shard files given")?;
+
+    let mut decoder =
+        ReedSolomonDecoder::new(set.original_count, set.recovery_count, set.shard_bytes)
+            .map_err(|err| err.to_string())?;
+
+    for (index, shard) in original.iter().enumerate() {
+        if let Some(shard) = shard {
+            decoder
+                .add_original_shard(index, shard)
+                .map_err(|err| err.to_string())?;
+        }
+    }
+    for (index, shard) in recovery.iter().enumerate() {
+        if let Some(shard) = shard {
+            decoder
+                .add_recovery_shard(index, shard)
+                .map_err(|err| err.to_string())?;
+        }
+    }
+
+    let result = decoder.decode().map_err(|err| err.to_string())?;
+
+    let mut data = Vec::with_capacity(set.original_count * set.shard_bytes);
+    for (index, shard) in original.iter().enumerate() {
+        match shard {
+            Some(shard) => data.extend_from_slice(shard),
+            None => data.extend_from_slice(result.restored_original(index).unwrap()),
+        }
+    }
+
+    let file_len = usize::try_from(set.file_len).map_err(|err| err.to_string())?;
+    if file_len > data.len() {
+        return Err("file length in header exceeds shard data".to_string());
+    }
+    data.truncate(file_len);
+
+    Ok((data, warnings))
+}
+
+/// Returns random 128-bit ID for a new shard set.
+///
+/// Randomness comes from random keys of [`RandomState`],
+/// which differ for each instance, mixed with current time.
+fn random_set_id() -> [u8; 16] {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+
+    let mut set_id = [0; 16];
+    for chunk in set_id.chunks_exact_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(nanos);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    set_id
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE - commands
+
+fn run_encode(args: &[String]) -> Result<(), String> {
+    let (file, original_count, recovery_count, out_dir) = match args {
+        [file, original_count, recovery_count] => (file, original_count, recovery_count, None),
+        [file, original_count, recovery_count, out_dir] => {
+            (file, original_count, recovery_count, Some(out_dir))
+        }
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let original_count: usize = original_count
+        .parse()
+        .map_err(|_| format!("invalid ORIGINAL_COUNT: {}", original_count))?;
+    let recovery_count: usize = recovery_count
+        .parse()
+        .map_err(|_| format!("invalid RECOVERY_COUNT: {}", recovery_count))?;
+
+    let path = Path::new(file);
+    let data = fs::read(path).map_err(|err| format!("{}: {}", file, err))?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("{}: not a file", file))?
+        .to_string_lossy();
+    let out_dir: PathBuf = match out_dir {
+        Some(out_dir) => out_dir.into(),
+        None => path.parent().unwrap_or(Path::new("")).to_path_buf(),
+    };
+
+    for (index, shard_file) in encode(&data, original_count, recovery_count)?
+        .iter()
+        .enumerate()
+    {
+        let shard_path = out_dir.join(format!("{}.{:05}.rs16", name, index));
+        fs::write(&shard_path, shard_file)
+            .map_err(|err| format!("{}: {}", shard_path.display(), err))?;
+    }
+
+    Ok(())
+}
+
+fn run_decode(args: &[String]) -> Result<(), String> {
+    let (output, shard_paths) = match args {
+        [output, shard_paths @ ..] if !shard_paths.is_empty() => (output, shard_paths),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let mut files = Vec::with_capacity(shard_paths.len());
+    for shard_path in shard_paths {
+        match fs::read(shard_path) {
+            Ok(file) => files.push((shard_path.as_str(), file)),
+            Err(err) => eprintln!("skipping {}: {}", shard_path, err),
+        }
+    }
+
+    let (data, warnings) = decode(files.iter().map(|(name, file)| (*name, file.as_slice())))?;
+    for warning in warnings {
+        eprintln!("{}", warning);
+    }
+
+    fs::write(output, data).map_err(|err| format!("{}: {}", output, err))
+}
+
+// ======================================================================
+// MAIN
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("encode") => run_encode(&args[1..]),
+        Some("decode") => run_decode(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(files: &[Vec<u8>]) -> Vec<(&'static str, &[u8])> {
+        files
+            .iter()
+            .map(|file| ("shard", file.as_slice()))
+            .collect()
+    }
+
+    #[test]
+    fn header_roundtrip() {
+        let header = Header {
+            recovery: true,
+            original_count: 3,
+            recovery_count: 5,
+            index: 4,
+            shard_bytes: 64,
+            file_len: 150,
+            set_id: [7; 16],
+            checksum: 0x1234_5678,
+        };
+
+        let mut data = Vec::new();
+        header.write(&mut data);
+        assert_eq!(data.len(), HEADER_BYTES);
+
+        data.resize(HEADER_BYTES + 64, 0);
+        assert_eq!(Header::parse(&data), Ok(header));
+
+        data[4] = 2;
+        assert!(Header::parse(&data).is_err());
+    }
+
+    #[test]
+    fn header_unsupported_shard_count() {
+        let header = Header {
+            recovery: false,
+            original_count: u32::MAX as usize,
+            recovery_count: u32::MAX as usize,
+            index: 0,
+            shard_bytes: 64,
+            file_len: 0,
+            set_id: [0; 16],
+            checksum: 0,
+        };
+
+        let mut data = Vec::new();
+        header.write(&mut data);
+        data.resize(HEADER_BYTES + 64, 0);
+
+        assert_eq!(
+            Header::parse(&data),
+            Err(format!(
+                "unsupported shard count {} original + {} recovery",
+                u32::MAX,
+                u32::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data: Vec<u8> = (0..1000u32).map(|x| (x * 7) as u8).collect();
+
+        let files = encode(&data, 4, 3).unwrap();
+        assert_eq!(files.len(), 7);
+
+        // Any 4 shard files are enough.
+        let subset = [
+            files[1].clone(),
+            files[4].clone(),
+            files[5].clone(),
+            files[6].clone(),
+        ];
+        let (restored, warnings) = decode(named(&subset)).unwrap();
+
+        assert_eq!(restored, data);
+        assert!(warnings.is_empty());
+
+        let (restored, _) = decode(named(&files[..4])).unwrap();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn empty_file() {
+        let files = encode(&[], 2, 1).unwrap();
+        let (restored, _) = decode(named(&files[1..])).unwrap();
+        assert!(restored.is_empty());
+    }
+
+    #[test]
+    fn corrupted_shard_is_skipped() {
+        let data = vec![42u8; 500];
+        let mut files = encode(&data, 2, 2).unwrap();
+
+        let last = files[0].len() - 1;
+        files[0][last] ^= 1;
+
+        let (restored, warnings) = decode(named(&files)).unwrap();
+
+        assert_eq!(restored, data);
+        assert_eq!(warnings, ["skipping shard: checksum mismatch"]);
+    }
+
+    #[test]
+    fn corrupted_header_is_skipped() {
+        let data: Vec<u8> = (0..500u32).map(|x| x as u8).collect();
+        let mut files = encode(&data, 2, 2).unwrap();
+
+        // Swap indexes of original shards.
+        files[0][16] = 1;
+        files[1][16] = 0;
+
+        let (restored, warnings) = decode(named(&files)).unwrap();
+
+        assert_eq!(restored, data);
+        assert_eq!(
+            warnings,
+            [
+                "skipping shard: checksum mismatch",
+                "skipping shard: checksum mismatch"
+            ]
+        );
+    }
+
+    #[test]
+    fn not_enough_shards() {
+        let files = encode(&[1u8; 500], 3, 2).unwrap();
+        assert!(decode(named(&files[..2])).is_err());
+    }
+
+    #[test]
+    fn different_sets() {
+        let a = encode(&[1u8; 500], 2, 2).unwrap();
+        let b = encode(&[1u8; 600], 2, 2).unwrap();
+        assert!(decode(named(&[a[0].clone(), b[1].clone()])).is_err());
+
+        // Same size and shard counts, so only set ID differs.
+        let a = encode(&[1u8; 500], 2, 2).unwrap();
+        let b = encode(&[2u8; 500], 2, 2).unwrap();
+        assert_ne!(
+            a[0][SET_ID_POS..CHECKSUM_POS],
+            b[0][SET_ID_POS..CHECKSUM_POS]
+        );
+        assert_eq!(
+            decode(named(&[a[0].clone(), b[1].clone()])),
+            Err("shard belongs to a different shard set".to_string())
+        );
+
+        // Different encodings of the same file aren't mixed either.
+        let b = encode(&[1u8; 500], 2, 2).unwrap();
+        assert!(decode(named(&[a[0].clone(), b[1].clone()])).is_err());
+    }
+}
@@ -0,0 +1,557 @@
+//! C ABI for [`ReedSolomonEncoder`] and [`ReedSolomonDecoder`].
+//!
+//! **This module is only available with `capi` feature.**
+//!
+//! Static library `libreed_solomon_16.a` is built with
+//! `cargo rustc --release --lib --features capi --crate-type staticlib`
+//! (or `cdylib` for a shared library) and the C header
+//! `include/reed_solomon_16.h` is generated from this module with [cbindgen].
+//!
+//! - Functions mirror [`ReedSolomonEncoder`] and [`ReedSolomonDecoder`]
+//!   with `rs16_encoder_*` and `rs16_decoder_*` prefixes.
+//! - Functions which can fail return [`RS16_OK`] on success
+//!   or one of the `RS16_ERROR_*` codes which map one-to-one
+//!   to [`Error`] variants, see [`rs16_error_message`].
+//! - Instead of [`EncoderResult`] and [`DecoderResult`], results
+//!   are copied into caller-given buffers after which
+//!   the encoder/decoder is ready for a new round.
+//! - Encoders and decoders are not thread-safe,
+//!   but different ones can be used from different threads.
+//!
+//! # Example
+//!
+//! ```c
+//! #include "reed_solomon_16.h"
+//!
+//! RS16Encoder *encoder;
+//! if (rs16_encoder_new(3, 2, 64, &encoder) != RS16_OK) { /* ... */ }
+//!
+//! for (size_t i = 0; i < 3; i++) {
+//!     rs16_encoder_add_original_shard(encoder, original[i], 64);
+//! }
+//!
+//! uint8_t *recovery[2] = { recovery_0, recovery_1 };
+//! int err = rs16_encoder_encode(encoder, recovery);
+//! if (err != RS16_OK) { printf("%s\n", rs16_error_message(err)); }
+//!
+//! rs16_encoder_free(encoder);
+//! ```
+//!
+//! [cbindgen]: https://github.com/eqrion/cbindgen
+//! [`EncoderResult`]: crate::EncoderResult
+//! [`DecoderResult`]: crate::DecoderResult
+
+use std::{
+    ffi::{c_char, c_int},
+    ptr, slice,
+};
+
+use crate::{Error, ReedSolomonDecoder, ReedSolomonEncoder};
+
+// ======================================================================
+// CONST - PUBLIC
+
+/// Success.
+pub const RS16_OK: c_int = 0;
+
+/// Given pointer was `NULL`.
+pub const RS16_ERROR_NULL_POINTER: c_int = 1;
+
+/// See [`Error::ChecksumMismatch`].
+pub const RS16_ERROR_CHECKSUM_MISMATCH: c_int = 2;
+/// See [`Error::DifferentShardSize`].
+pub const RS16_ERROR_DIFFERENT_SHARD_SIZE: c_int = 3;
+/// See [`Error::DuplicateLocalParityShardIndex`].
+pub const RS16_ERROR_DUPLICATE_LOCAL_PARITY_SHARD_INDEX: c_int = 4;
+/// See [`Error::DuplicateOriginalShardIndex`].
+pub const RS16_ERROR_DUPLICATE_ORIGINAL_SHARD_INDEX: c_int = 5;
+/// See [`Error::DuplicateRecoveryShardIndex`].
+pub const RS16_ERROR_DUPLICATE_RECOVERY_SHARD_INDEX: c_int = 6;
+/// See [`Error::DuplicateShardPosition`].
+pub const RS16_ERROR_DUPLICATE_SHARD_POSITION: c_int = 7;
+/// See [`Error::InvalidLocalParityShardIndex`].
+pub const RS16_ERROR_INVALID_LOCAL_PARITY_SHARD_INDEX: c_int = 8;
+/// See [`Error::InvalidOriginalShardIndex`].
+pub const RS16_ERROR_INVALID_ORIGINAL_SHARD_INDEX: c_int = 9;
+/// See [`Error::InvalidPar2`].
+pub const RS16_ERROR_INVALID_PAR2: c_int = 10;
+/// See [`Error::InvalidRecoveryShardIndex`].
+pub const RS16_ERROR_INVALID_RECOVERY_SHARD_INDEX: c_int = 11;
+/// See [`Error::InvalidShardPosition`].
+pub const RS16_ERROR_INVALID_SHARD_POSITION: c_int = 12;
+/// See [`Error::InvalidShardSize`].
+pub const RS16_ERROR_INVALID_SHARD_SIZE: c_int = 13;
+/// See [`Error::NotEnoughShards`].
+pub const RS16_ERROR_NOT_ENOUGH_SHARDS: c_int = 14;
+/// See [`Error::TooFewOriginalShards`].
+pub const RS16_ERROR_TOO_FEW_ORIGINAL_SHARDS: c_int = 15;
+/// See [`Error::TooManyCorruptedShards`].
+pub const RS16_ERROR_TOO_MANY_CORRUPTED_SHARDS: c_int = 16;
+/// See [`Error::TooManyOriginalShards`].
+pub const RS16_ERROR_TOO_MANY_ORIGINAL_SHARDS: c_int = 17;
+/// See [`Error::UnsupportedShardCount`].
+pub const RS16_ERROR_UNSUPPORTED_SHARD_COUNT: c_int = 18;
+
+// ======================================================================
+// RS16Encoder / RS16Decoder - PUBLIC
+
+/// Opaque handle to [`ReedSolomonEncoder`].
+pub struct RS16Encoder {
+    inner: ReedSolomonEncoder,
+    recovery_count: usize,
+}
+
+/// Opaque handle to [`ReedSolomonDecoder`].
+pub struct RS16Decoder(ReedSolomonDecoder);
+
+// ======================================================================
+// FUNCTIONS - PUBLIC - misc
+
+/// Returns static NUL-terminated description of given return code.
+#[no_mangle]
+pub extern "C" fn rs16_error_message(code: c_int) -> *const c_char {
+    let message: &'static [u8] = match code {
+        RS16_OK => b"ok\0",
+        RS16_ERROR_NULL_POINTER => b"null pointer\0",
+        RS16_ERROR_CHECKSUM_MISMATCH => b"checksum mismatch\0",
+        RS16_ERROR_DIFFERENT_SHARD_SIZE => b"different shard size\0",
+        RS16_ERROR_DUPLICATE_LOCAL_PARITY_SHARD_INDEX => b"duplicate local parity shard index\0",
+        RS16_ERROR_DUPLICATE_ORIGINAL_SHARD_INDEX => b"duplicate original shard index\0",
+        RS16_ERROR_DUPLICATE_RECOVERY_SHARD_INDEX => b"duplicate recovery shard index\0",
+        RS16_ERROR_DUPLICATE_SHARD_POSITION => b"duplicate shard position\0",
+        RS16_ERROR_INVALID_LOCAL_PARITY_SHARD_INDEX => b"invalid local parity shard index\0",
+        RS16_ERROR_INVALID_ORIGINAL_SHARD_INDEX => b"invalid original shard index\0",
+        RS16_ERROR_INVALID_PAR2 => b"invalid PAR2 data\0",
+        RS16_ERROR_INVALID_RECOVERY_SHARD_INDEX => b"invalid recovery shard index\0",
+        RS16_ERROR_INVALID_SHARD_POSITION => b"invalid shard position\0",
+        RS16_ERROR_INVALID_SHARD_SIZE => b"invalid shard size\0",
+        RS16_ERROR_NOT_ENOUGH_SHARDS => b"not enough shards\0",
+        RS16_ERROR_TOO_FEW_ORIGINAL_SHARDS => b"too few original shards\0",
+        RS16_ERROR_TOO_MANY_CORRUPTED_SHARDS => b"too many corrupted shards\0",
+        RS16_ERROR_TOO_MANY_ORIGINAL_SHARDS => b"too many original shards\0",
+        RS16_ERROR_UNSUPPORTED_SHARD_COUNT => b"unsupported shard count\0",
+        _ => b"unknown error\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Returns `true` if given `original_count` / `recovery_count`
+/// combination is supported, see [`ReedSolomonEncoder::supports`].
+#[no_mangle]
+pub extern "C" fn rs16_supports(original_count: usize, recovery_count: usize) -> bool {
+    ReedSolomonEncoder::supports(original_count, recovery_count)
+}
+
+// ======================================================================
+// FUNCTIONS - PUBLIC - encoder
+
+/// Adds one original shard of `shard_bytes` bytes to the encoder,
+/// see [`ReedSolomonEncoder::add_original_shard`].
+///
+/// # Safety
+///
+/// - `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+/// - `shard` must point to `shard_bytes` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_encoder_add_original_shard(
+    encoder: *mut RS16Encoder,
+    shard: *const u8,
+    shard_bytes: usize,
+) -> c_int {
+    if encoder.is_null() || shard.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let shard = slice::from_raw_parts(shard, shard_bytes);
+    to_code((*encoder).inner.add_original_shard(shard))
+}
+
+/// Encodes the added original shards, copying generated
+/// recovery shards to `recovery[0 .. recovery_count]`,
+/// see [`ReedSolomonEncoder::encode`].
+///
+/// Encoder is then ready for new round of encoding.
+///
+/// # Safety
+///
+/// - `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+/// - `recovery` must point to `recovery_count` pointers,
+///   each pointing to `shard_bytes` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_encoder_encode(
+    encoder: *mut RS16Encoder,
+    recovery: *const *mut u8,
+) -> c_int {
+    if encoder.is_null() || recovery.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let encoder = &mut *encoder;
+
+    if (0..encoder.recovery_count).any(|index| (*recovery.add(index)).is_null()) {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let result = match encoder.inner.encode() {
+        Ok(result) => result,
+        Err(err) => return error_code(err),
+    };
+
+    for (index, shard) in result.recovery_iter().enumerate() {
+        ptr::copy_nonoverlapping(shard.as_ptr(), *recovery.add(index), shard.len());
+    }
+
+    RS16_OK
+}
+
+/// Frees encoder. Does nothing if `encoder` is `NULL`.
+///
+/// # Safety
+///
+/// `encoder` must be `NULL` or from [`rs16_encoder_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_encoder_free(encoder: *mut RS16Encoder) {
+    if !encoder.is_null() {
+        drop(Box::from_raw(encoder));
+    }
+}
+
+/// Creates new encoder into `*encoder`, see [`ReedSolomonEncoder::new`].
+///
+/// On error `*encoder` is set to `NULL`.
+///
+/// # Safety
+///
+/// `encoder` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_encoder_new(
+    original_count: usize,
+    recovery_count: usize,
+    shard_bytes: usize,
+    encoder: *mut *mut RS16Encoder,
+) -> c_int {
+    if encoder.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    match ReedSolomonEncoder::new(original_count, recovery_count, shard_bytes) {
+        Ok(inner) => {
+            *encoder = Box::into_raw(Box::new(RS16Encoder {
+                inner,
+                recovery_count,
+            }));
+            RS16_OK
+        }
+        Err(err) => {
+            *encoder = ptr::null_mut();
+            error_code(err)
+        }
+    }
+}
+
+/// Resets encoder to given configuration, see [`ReedSolomonEncoder::reset`].
+///
+/// # Safety
+///
+/// `encoder` must be from [`rs16_encoder_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_encoder_reset(
+    encoder: *mut RS16Encoder,
+    original_count: usize,
+    recovery_count: usize,
+    shard_bytes: usize,
+) -> c_int {
+    if encoder.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let encoder = &mut *encoder;
+    let result = encoder
+        .inner
+        .reset(original_count, recovery_count, shard_bytes);
+    if result.is_ok() {
+        encoder.recovery_count = recovery_count;
+    }
+    to_code(result)
+}
+
+// ======================================================================
+// FUNCTIONS - PUBLIC - decoder
+
+/// Adds one original shard of `shard_bytes` bytes to the decoder,
+/// see [`ReedSolomonDecoder::add_original_shard`].
+///
+/// # Safety
+///
+/// - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+/// - `shard` must point to `shard_bytes` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_add_original_shard(
+    decoder: *mut RS16Decoder,
+    index: usize,
+    shard: *const u8,
+    shard_bytes: usize,
+) -> c_int {
+    if decoder.is_null() || shard.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let shard = slice::from_raw_parts(shard, shard_bytes);
+    to_code((*decoder).0.add_original_shard(index, shard))
+}
+
+/// Adds one recovery shard of `shard_bytes` bytes to the decoder,
+/// see [`ReedSolomonDecoder::add_recovery_shard`].
+///
+/// # Safety
+///
+/// - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+/// - `shard` must point to `shard_bytes` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_add_recovery_shard(
+    decoder: *mut RS16Decoder,
+    index: usize,
+    shard: *const u8,
+    shard_bytes: usize,
+) -> c_int {
+    if decoder.is_null() || shard.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let shard = slice::from_raw_parts(shard, shard_bytes);
+    to_code((*decoder).0.add_recovery_shard(index, shard))
+}
+
+/// Decodes the added shards, copying each restored original shard `i`
+/// to `original[i]`, see [`ReedSolomonDecoder::decode`].
+///
+/// - `original[i]` is only used for original shards
+///   which weren't added and can be `NULL` for others.
+/// - Decoder is then ready for new round of decoding.
+///
+/// # Safety
+///
+/// - `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+/// - `original` must point to `original_count` pointers and each
+///   of those used must point to `shard_bytes` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_decode(
+    decoder: *mut RS16Decoder,
+    original: *const *mut u8,
+) -> c_int {
+    if decoder.is_null() || original.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let decoder = &mut (*decoder).0;
+
+    if decoder.can_decode()
+        && decoder
+            .missing_original_indexes()
+            .into_iter()
+            .any(|index| (*original.add(index)).is_null())
+    {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    let result = match decoder.decode() {
+        Ok(result) => result,
+        Err(err) => return error_code(err),
+    };
+
+    for (index, shard) in result.restored_original_iter() {
+        ptr::copy_nonoverlapping(shard.as_ptr(), *original.add(index), shard.len());
+    }
+
+    RS16_OK
+}
+
+/// Frees decoder. Does nothing if `decoder` is `NULL`.
+///
+/// # Safety
+///
+/// `decoder` must be `NULL` or from [`rs16_decoder_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_free(decoder: *mut RS16Decoder) {
+    if !decoder.is_null() {
+        drop(Box::from_raw(decoder));
+    }
+}
+
+/// Creates new decoder into `*decoder`, see [`ReedSolomonDecoder::new`].
+///
+/// On error `*decoder` is set to `NULL`.
+///
+/// # Safety
+///
+/// `decoder` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_new(
+    original_count: usize,
+    recovery_count: usize,
+    shard_bytes: usize,
+    decoder: *mut *mut RS16Decoder,
+) -> c_int {
+    if decoder.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    match ReedSolomonDecoder::new(original_count, recovery_count, shard_bytes) {
+        Ok(inner) => {
+            *decoder = Box::into_raw(Box::new(RS16Decoder(inner)));
+            RS16_OK
+        }
+        Err(err) => {
+            *decoder = ptr::null_mut();
+            error_code(err)
+        }
+    }
+}
+
+/// Resets decoder to given configuration, see [`ReedSolomonDecoder::reset`].
+///
+/// # Safety
+///
+/// `decoder` must be from [`rs16_decoder_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn rs16_decoder_reset(
+    decoder: *mut RS16Decoder,
+    original_count: usize,
+    recovery_count: usize,
+    shard_bytes: usize,
+) -> c_int {
+    if decoder.is_null() {
+        return RS16_ERROR_NULL_POINTER;
+    }
+
+    to_code(
+        (*decoder)
+            .0
+            .reset(original_count, recovery_count, shard_bytes),
+    )
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn error_code(err: Error) -> c_int {
+    match err {
+        Error::ChecksumMismatch { .. } => RS16_ERROR_CHECKSUM_MISMATCH,
+        Error::DifferentShardSize { .. } => RS16_ERROR_DIFFERENT_SHARD_SIZE,
+        Error::DuplicateLocalParityShardIndex { .. } => {
+            RS16_ERROR_DUPLICATE_LOCAL_PARITY_SHARD_INDEX
+        }
+        Error::DuplicateOriginalShardIndex { .. } => RS16_ERROR_DUPLICATE_ORIGINAL_SHARD_INDEX,
+        Error::DuplicateRecoveryShardIndex { .. } => RS16_ERROR_DUPLICATE_RECOVERY_SHARD_INDEX,
+        Error::DuplicateShardPosition { .. } => RS16_ERROR_DUPLICATE_SHARD_POSITION,
+        Error::InvalidLocalParityShardIndex { .. } => RS16_ERROR_INVALID_LOCAL_PARITY_SHARD_INDEX,
+        Error::InvalidOriginalShardIndex { .. } => RS16_ERROR_INVALID_ORIGINAL_SHARD_INDEX,
+        Error::InvalidPar2 { .. } => RS16_ERROR_INVALID_PAR2,
+        Error::InvalidRecoveryShardIndex { .. } => RS16_ERROR_INVALID_RECOVERY_SHARD_INDEX,
+        Error::InvalidShardPosition { .. } => RS16_ERROR_INVALID_SHARD_POSITION,
+        Error::InvalidShardSize { .. } => RS16_ERROR_INVALID_SHARD_SIZE,
+        Error::NotEnoughShards { .. } => RS16_ERROR_NOT_ENOUGH_SHARDS,
+        Error::TooFewOriginalShards { .. } => RS16_ERROR_TOO_FEW_ORIGINAL_SHARDS,
+        Error::TooManyCorruptedShards { .. } => RS16_ERROR_TOO_MANY_CORRUPTED_SHARDS,
+        Error::TooManyOriginalShards { .. } => RS16_ERROR_TOO_MANY_ORIGINAL_SHARDS,
+        Error::UnsupportedShardCount { .. } => RS16_ERROR_UNSUPPORTED_SHARD_COUNT,
+    }
+}
+
+fn to_code(result: Result<(), Error>) -> c_int {
+    match result {
+        Ok(()) => RS16_OK,
+        Err(err) => error_code(err),
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn roundtrip() {
+        let original = test_util::generate_original(3, 64, 0);
+        let mut recovery = vec![vec![0u8; 64]; 2];
+
+        unsafe {
+            let mut encoder = ptr::null_mut();
+            assert_eq!(rs16_encoder_new(3, 2, 64, &mut encoder), RS16_OK);
+            for shard in &original {
+                assert_eq!(
+                    rs16_encoder_add_original_shard(encoder, shard.as_ptr(), 64),
+                    RS16_OK
+                );
+            }
+            let outputs: Vec<_> = recovery.iter_mut().map(|x| x.as_mut_ptr()).collect();
+            assert_eq!(rs16_encoder_encode(encoder, outputs.as_ptr()), RS16_OK);
+            rs16_encoder_free(encoder);
+
+            assert_eq!(recovery, crate::encode(3, 2, &original).unwrap());
+
+            let mut decoder = ptr::null_mut();
+            assert_eq!(rs16_decoder_new(3, 2, 64, &mut decoder), RS16_OK);
+            rs16_decoder_add_original_shard(decoder, 1, original[1].as_ptr(), 64);
+            rs16_decoder_add_recovery_shard(decoder, 0, recovery[0].as_ptr(), 64);
+            rs16_decoder_add_recovery_shard(decoder, 1, recovery[1].as_ptr(), 64);
+
+            let mut restored = vec![vec![0u8; 64]; 3];
+            let mut outputs: Vec<_> = restored.iter_mut().map(|x| x.as_mut_ptr()).collect();
+            outputs[1] = ptr::null_mut();
+            assert_eq!(rs16_decoder_decode(decoder, outputs.as_ptr()), RS16_OK);
+            rs16_decoder_free(decoder);
+
+            assert_eq!(restored[0], original[0]);
+            assert_eq!(restored[2], original[2]);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        unsafe {
+            let mut encoder = ptr::null_mut();
+            assert_eq!(
+                rs16_encoder_new(3, 2, 63, &mut encoder),
+                RS16_ERROR_INVALID_SHARD_SIZE
+            );
+            assert!(encoder.is_null());
+
+            assert_eq!(
+                rs16_encoder_new(3, 2, 64, ptr::null_mut()),
+                RS16_ERROR_NULL_POINTER
+            );
+
+            let mut decoder = ptr::null_mut();
+            assert_eq!(rs16_decoder_new(3, 2, 64, &mut decoder), RS16_OK);
+            let shard = [0u8; 64];
+            assert_eq!(
+                rs16_decoder_add_original_shard(decoder, 3, shard.as_ptr(), 64),
+                RS16_ERROR_INVALID_ORIGINAL_SHARD_INDEX
+            );
+            let outputs = [ptr::null_mut(); 3];
+            assert_eq!(
+                rs16_decoder_decode(decoder, outputs.as_ptr()),
+                RS16_ERROR_NOT_ENOUGH_SHARDS
+            );
+            rs16_decoder_add_original_shard(decoder, 0, shard.as_ptr(), 64);
+            rs16_decoder_add_recovery_shard(decoder, 0, shard.as_ptr(), 64);
+            rs16_decoder_add_recovery_shard(decoder, 1, shard.as_ptr(), 64);
+            assert_eq!(
+                rs16_decoder_decode(decoder, outputs.as_ptr()),
+                RS16_ERROR_NULL_POINTER
+            );
+            rs16_decoder_free(decoder);
+        }
+
+        for code in 0..=RS16_ERROR_UNSUPPORTED_SHARD_COUNT + 1 {
+            let message = unsafe { CStr::from_ptr(rs16_error_message(code)) };
+            assert!(!message.to_bytes().is_empty());
+        }
+    }
+}
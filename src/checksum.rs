@@ -0,0 +1,159 @@
+//! Per-shard checksums for detecting corrupted shards.
+//!
+//! Decoder assumes that every shard given to it is correct,
+//! so a single corrupted shard silently corrupts restored original shards.
+//! Checksums allow rejecting such shards before decoding.
+//!
+//! - Encoder can compute checksums of original shards with
+//!   [`ReedSolomonEncoder::add_original_shard_with_checksum`]
+//!   and checksums of recovery shards with [`EncoderResult::recovery_checksum`].
+//! - Decoder verifies checksums with
+//!   [`ReedSolomonDecoder::add_original_shard_with_checksum`] and
+//!   [`ReedSolomonDecoder::add_recovery_shard_with_checksum`],
+//!   returning [`Error::ChecksumMismatch`] for corrupted shards.
+//! - Checksum is CRC32C (Castagnoli), which can also be calculated
+//!   directly with [`crc32c`].
+//!
+//! Checksums are optional and have no cost unless used.
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::{Error, ReedSolomonDecoder, ReedSolomonEncoder, ShardKind};
+//!
+//! let original = [[1u8; 64], [2u8; 64]];
+//!
+//! let mut encoder = ReedSolomonEncoder::new(2, 1, 64)?;
+//! let original_checksums = [
+//!     encoder.add_original_shard_with_checksum(original[0])?,
+//!     encoder.add_original_shard_with_checksum(original[1])?,
+//! ];
+//! let result = encoder.encode()?;
+//! let recovery = result.recovery(0).unwrap().to_vec();
+//! let recovery_checksum = result.recovery_checksum(0).unwrap();
+//!
+//! let mut decoder = ReedSolomonDecoder::new(2, 1, 64)?;
+//!
+//! // This shard has been corrupted and is rejected.
+//! let mut corrupted = original[0];
+//! corrupted[10] ^= 1;
+//! assert_eq!(
+//!     decoder.add_original_shard_with_checksum(0, corrupted, original_checksums[0]),
+//!     Err(Error::ChecksumMismatch { index: 0, kind: ShardKind::Original })
+//! );
+//!
+//! decoder.add_original_shard_with_checksum(1, original[1], original_checksums[1])?;
+//! decoder.add_recovery_shard_with_checksum(0, recovery, recovery_checksum)?;
+//!
+//! let result = decoder.decode()?;
+//! assert_eq!(result.restored_original(0).unwrap(), original[0]);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [`ReedSolomonEncoder::add_original_shard_with_checksum`]: crate::ReedSolomonEncoder::add_original_shard_with_checksum
+//! [`ReedSolomonDecoder::add_original_shard_with_checksum`]: crate::ReedSolomonDecoder::add_original_shard_with_checksum
+//! [`ReedSolomonDecoder::add_recovery_shard_with_checksum`]: crate::ReedSolomonDecoder::add_recovery_shard_with_checksum
+//! [`EncoderResult::recovery_checksum`]: crate::EncoderResult::recovery_checksum
+//! [`Error::ChecksumMismatch`]: crate::Error::ChecksumMismatch
+
+// ======================================================================
+// CONST - PRIVATE
+
+// Reversed CRC32C (Castagnoli) polynomial.
+const CRC32C_POLYNOMIAL: u32 = 0x82F6_3B78;
+
+// Tables for slicing-by-8.
+static CRC32C_TABLES: [[u32; 256]; 8] = crc32c_tables();
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Calculates CRC32C (Castagnoli) checksum of given `data`.
+///
+/// # Examples
+///
+/// ```rust
+/// use reed_solomon_16::checksum;
+///
+/// assert_eq!(checksum::crc32c(b"123456789"), 0xE306_9283);
+/// ```
+pub fn crc32c(data: &[u8]) -> u32 {
+    let t = &CRC32C_TABLES;
+    let mut crc = !0u32;
+
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let lo = crc ^ u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        let hi = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+        crc = t[7][(lo & 0xFF) as usize]
+            ^ t[6][((lo >> 8) & 0xFF) as usize]
+            ^ t[5][((lo >> 16) & 0xFF) as usize]
+            ^ t[4][(lo >> 24) as usize]
+            ^ t[3][(hi & 0xFF) as usize]
+            ^ t[2][((hi >> 8) & 0xFF) as usize]
+            ^ t[1][((hi >> 16) & 0xFF) as usize]
+            ^ t[0][(hi >> 24) as usize];
+    }
+
+    for byte in chunks.remainder() {
+        crc = t[0][((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+
+    !crc
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+const fn crc32c_tables() -> [[u32; 256]; 8] {
+    let mut tables = [[0; 256]; 8];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32C_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        tables[0][i] = crc;
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < 256 {
+        let mut slice = 1;
+        while slice < 8 {
+            let prev = tables[slice - 1][i];
+            tables[slice][i] = (prev >> 8) ^ tables[0][(prev & 0xFF) as usize];
+            slice += 1;
+        }
+        i += 1;
+    }
+
+    tables
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ============================================================
+    // crc32c
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"a"), 0xC1D0_4330);
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(&[0u8; 32]), 0x8A91_36AA);
+        assert_eq!(crc32c(&[0xFFu8; 32]), 0x62A8_AB43);
+    }
+}
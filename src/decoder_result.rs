@@ -3,10 +3,11 @@ use crate::rate::DecoderWork;
 // ======================================================================
 // DecoderResult - PUBLIC
 
-/// Result of decoding. Contains the restored original shards.
+/// Result of decoding. Contains the restored original shards
+/// and, when repairing, also the restored recovery shards.
 ///
 /// This struct is created by [`ReedSolomonDecoder::decode`]
-/// and [`RateDecoder::decode`].
+/// and [`RateDecoder::decode`] and their variants.
 ///
 /// [`RateDecoder::decode`]: crate::rate::RateDecoder::decode
 /// [`ReedSolomonDecoder::decode`]: crate::ReedSolomonDecoder::decode
@@ -15,6 +16,31 @@ pub struct DecoderResult<'a> {
 }
 
 impl<'a> DecoderResult<'a> {
+    /// Returns indexes of original shards which
+    /// [`ReedSolomonDecoder::decode_with_errors`] found to be corrupted,
+    /// in ascending order.
+    ///
+    /// These shards have been restored and are included
+    /// in [`restored_original`] and [`restored_original_iter`].
+    ///
+    /// [`ReedSolomonDecoder::decode_with_errors`]: crate::ReedSolomonDecoder::decode_with_errors
+    /// [`restored_original`]: DecoderResult::restored_original
+    /// [`restored_original_iter`]: DecoderResult::restored_original_iter
+    pub fn corrupted_original(&self) -> &[usize] {
+        self.work.corrupted_original()
+    }
+
+    /// Returns indexes of recovery shards which
+    /// [`ReedSolomonDecoder::decode_with_errors`] found to be corrupted,
+    /// in ascending order.
+    ///
+    /// These shards were ignored in decoding.
+    ///
+    /// [`ReedSolomonDecoder::decode_with_errors`]: crate::ReedSolomonDecoder::decode_with_errors
+    pub fn corrupted_recovery(&self) -> &[usize] {
+        self.work.corrupted_recovery()
+    }
+
     /// Returns restored original shard with given `index`
     /// or `None` if given `index` doesn't correspond to
     /// a missing original shard.
@@ -24,9 +50,31 @@ impl<'a> DecoderResult<'a> {
 
     /// Returns iterator over all restored original shards
     /// and their indexes, ordered by indexes.
-    pub fn restored_original_iter(&self) -> RestoredOriginal {
+    pub fn restored_original_iter(&self) -> RestoredOriginal<'_> {
         RestoredOriginal::new(self.work)
     }
+
+    /// Returns restored recovery shard with given `index`
+    /// or `None` if given `index` doesn't correspond to
+    /// a missing recovery shard.
+    ///
+    /// Recovery shards are restored only by [`ReedSolomonDecoder::repair`],
+    /// so this always returns `None` after other decoding methods.
+    ///
+    /// [`ReedSolomonDecoder::repair`]: crate::ReedSolomonDecoder::repair
+    pub fn restored_recovery(&self, index: usize) -> Option<&[u8]> {
+        self.work.restored_recovery(index)
+    }
+
+    /// Returns iterator over all restored recovery shards
+    /// and their indexes, ordered by indexes.
+    ///
+    /// See [`restored_recovery`] for when recovery shards are restored.
+    ///
+    /// [`restored_recovery`]: DecoderResult::restored_recovery
+    pub fn restored_recovery_iter(&self) -> RestoredRecovery<'_> {
+        RestoredRecovery::new(self.work)
+    }
 }
 
 // ======================================================================
@@ -95,6 +143,54 @@ impl<'a> RestoredOriginal<'a> {
     }
 }
 
+// ======================================================================
+// RestoredRecovery - PUBLIC
+
+/// Iterator over restored recovery shards and their indexes.
+///
+/// This struct is created by [`DecoderResult::restored_recovery_iter`].
+pub struct RestoredRecovery<'a> {
+    ended: bool,
+    next_index: usize,
+    work: &'a DecoderWork,
+}
+
+// ======================================================================
+// RestoredRecovery - IMPL Iterator
+
+impl<'a> Iterator for RestoredRecovery<'a> {
+    type Item = (usize, &'a [u8]);
+    fn next(&mut self) -> Option<(usize, &'a [u8])> {
+        if self.ended {
+            None
+        } else {
+            let mut index = self.next_index;
+            while index < self.work.recovery_count() {
+                if let Some(recovery) = self.work.restored_recovery(index) {
+                    self.next_index = index + 1;
+                    return Some((index, recovery));
+                }
+                index += 1
+            }
+            self.ended = true;
+            None
+        }
+    }
+}
+
+// ======================================================================
+// RestoredRecovery - CRATE
+
+impl<'a> RestoredRecovery<'a> {
+    pub(crate) fn new(work: &'a DecoderWork) -> Self {
+        Self {
+            ended: false,
+            next_index: 0,
+            work,
+        }
+    }
+}
+
 // ======================================================================
 // TESTS
 
@@ -136,5 +232,46 @@ mod tests {
         assert_eq!(iter.next(), Some((2, original[2].as_slice())));
         assert_eq!(iter.next(), None);
         assert_eq!(iter.next(), None);
+
+        assert!(result.restored_recovery(0).is_none());
+        assert!(result.restored_recovery_iter().next().is_none());
+    }
+
+    #[test]
+    // DecoderResult::restored_recovery
+    // DecoderResult::restored_recovery_iter
+    // RestoredRecovery
+    fn decoder_result_repair() {
+        let original = test_util::generate_original(3, 1024, 0);
+
+        let mut encoder = ReedSolomonEncoder::new(3, 4, 1024).unwrap();
+        let mut decoder = ReedSolomonDecoder::new(3, 4, 1024).unwrap();
+
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+
+        let result = encoder.encode().unwrap();
+        let recovery: Vec<_> = result.recovery_iter().collect();
+
+        decoder.add_original_shard(1, &original[1]).unwrap();
+        decoder.add_recovery_shard(0, recovery[0]).unwrap();
+        decoder.add_recovery_shard(2, recovery[2]).unwrap();
+
+        let result: DecoderResult = decoder.repair().unwrap();
+
+        assert_eq!(result.restored_original(0).unwrap(), original[0]);
+        assert_eq!(result.restored_original(2).unwrap(), original[2]);
+        assert!(result.restored_recovery(0).is_none());
+        assert_eq!(result.restored_recovery(1).unwrap(), recovery[1]);
+        assert!(result.restored_recovery(2).is_none());
+        assert_eq!(result.restored_recovery(3).unwrap(), recovery[3]);
+        assert!(result.restored_recovery(4).is_none());
+
+        let mut iter: RestoredRecovery = result.restored_recovery_iter();
+        assert_eq!(iter.next(), Some((1, recovery[1])));
+        assert_eq!(iter.next(), Some((3, recovery[3])));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
     }
 }
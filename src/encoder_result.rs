@@ -1,4 +1,4 @@
-use crate::rate::EncoderWork;
+use crate::{checksum, rate::EncoderWork, Error};
 
 // ======================================================================
 // EncoderResult - PUBLIC
@@ -24,11 +24,19 @@ impl<'a> EncoderResult<'a> {
         self.work.recovery(index)
     }
 
+    /// Returns checksum of recovery shard with given `index`
+    /// or `None` if `index >= recovery_count`.
+    ///
+    /// Checksum is calculated on demand, see [`checksum`] module for details.
+    pub fn recovery_checksum(&self, index: usize) -> Option<u32> {
+        self.work.recovery(index).map(checksum::crc32c)
+    }
+
     /// Returns iterator over all recovery shards ordered by their indexes.
     ///
     /// Recovery shards have indexes `0..recovery_count`
     /// and these same indexes must be used when decoding.
-    pub fn recovery_iter(&self) -> Recovery {
+    pub fn recovery_iter(&self) -> Recovery<'_> {
         Recovery::new(self.work)
     }
 }
@@ -40,6 +48,29 @@ impl<'a> EncoderResult<'a> {
     pub(crate) fn new(work: &'a mut EncoderWork) -> Self {
         Self { work }
     }
+
+    // Returns `true` if given shard matches recovery shard with given `index`.
+    pub(crate) fn matches_recovery(
+        &self,
+        index: usize,
+        recovery_shard: &[u8],
+    ) -> Result<bool, Error> {
+        if let Some(recovery) = self.work.recovery(index) {
+            if recovery_shard.len() != self.work.shard_bytes() {
+                Err(Error::DifferentShardSize {
+                    shard_bytes: self.work.shard_bytes(),
+                    got: recovery_shard.len(),
+                })
+            } else {
+                Ok(recovery == recovery_shard)
+            }
+        } else {
+            Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: self.work.recovery_count(),
+                index,
+            })
+        }
+    }
 }
 
 // ======================================================================
@@ -116,18 +147,20 @@ mod tests {
 
         let result: EncoderResult = encoder.encode().unwrap();
 
-        let mut all = Vec::new();
-        all.push(result.recovery(0).unwrap());
-        all.push(result.recovery(1).unwrap());
-        all.push(result.recovery(2).unwrap());
+        let all = vec![
+            result.recovery(0).unwrap(),
+            result.recovery(1).unwrap(),
+            result.recovery(2).unwrap(),
+        ];
         assert!(result.recovery(3).is_none());
         test_util::assert_hash(all, test_util::LOW_2_3);
 
         let mut iter: Recovery = result.recovery_iter();
-        let mut all = Vec::new();
-        all.push(iter.next().unwrap());
-        all.push(iter.next().unwrap());
-        all.push(iter.next().unwrap());
+        let all = vec![
+            iter.next().unwrap(),
+            iter.next().unwrap(),
+            iter.next().unwrap(),
+        ];
         assert!(iter.next().is_none());
         test_util::assert_hash(all, test_util::LOW_2_3);
     }
@@ -6,6 +6,8 @@
 //! - use [`rate`] module and need an [`Engine`] to use with it.
 //! - create your own [`Engine`].
 //! - understand/benchmark/test at low level.
+//! - do arithmetic in the same Galois field with [`Gf16`].
+//! - use the additive FFT for polynomials with [`poly`] module.
 //!
 //! # Engines
 //!
@@ -42,14 +44,24 @@
 //! [`ReedSolomonDecoder`]: crate::ReedSolomonDecoder
 //! [`rate`]: crate::rate
 
+#[cfg(feature = "alloc")]
 pub(crate) use self::shards::Shards;
 
-pub use self::{engine_naive::Naive, engine_nosimd::NoSimd, shards::ShardsRefMut};
+#[cfg(feature = "alloc")]
+pub use self::{engine_naive::Naive, engine_nosimd::NoSimd, gf16::Gf16, shards::ShardsRefMut};
 
+#[cfg(feature = "alloc")]
 mod engine_naive;
+#[cfg(feature = "alloc")]
 mod engine_nosimd;
+#[cfg(feature = "alloc")]
+mod gf16;
+#[cfg(feature = "alloc")]
 mod shards;
 
+#[cfg(feature = "alloc")]
+pub mod poly;
+#[cfg(feature = "alloc")]
 pub mod tables;
 
 // ======================================================================
@@ -80,6 +92,7 @@ pub const CANTOR_BASIS: [GfElement; GF_BITS] = [
 pub type GfElement = u16;
 
 /// Default [`Engine`], currently just alias to [`NoSimd`].
+#[cfg(feature = "alloc")]
 pub type DefaultEngine = NoSimd;
 
 // ======================================================================
@@ -118,13 +131,9 @@ pub fn sub_mod(x: GfElement, y: GfElement) -> GfElement {
 ///
 /// [`usize::checked_next_multiple_of`]: https://doc.rust-lang.org/std/primitive.usize.html#method.checked_next_multiple_of
 pub fn checked_next_multiple_of(a: usize, b: usize) -> Option<usize> {
-    if b == 0 {
-        None
-    } else {
-        let mut x = a / b;
-        x += if a % b != 0 { 1 } else { 0 };
-        x.checked_mul(b)
-    }
+    let mut x = a.checked_div(b)?;
+    x += if !a.is_multiple_of(b) { 1 } else { 0 };
+    x.checked_mul(b)
 }
 
 // ======================================================================
@@ -137,6 +146,7 @@ pub fn checked_next_multiple_of(a: usize, b: usize) -> Option<usize> {
 ///
 /// [`Naive`] engine is provided for those who want to
 /// study the source code to understand [`Engine`].
+#[cfg(feature = "alloc")]
 pub trait Engine: Clone
 where
     Self: Sized,
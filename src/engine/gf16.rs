@@ -0,0 +1,301 @@
+use core::{
+    fmt,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+use crate::engine::{self, tables, GfElement, GF_MODULUS};
+
+// ======================================================================
+// Gf16 - PUBLIC
+
+/// Element of the Galois field `GF(2^16)` used by this crate.
+///
+/// - Elements are in the same (Cantor basis) representation
+///   as shard data, so `Gf16(x)` multiplies exactly like
+///   [`Engine::mul`] does for 16-bit words of a shard.
+/// - Arithmetic uses the global [`Exp`] and [`Log`] tables,
+///   which are initialized on first use.
+/// - Addition and subtraction are both `XOR`.
+/// - Division by zero panics, like integer division.
+///
+/// # Examples
+///
+/// ```rust
+/// use reed_solomon_16::engine::Gf16;
+///
+/// let a = Gf16(1234);
+/// let b = Gf16(5678);
+///
+/// assert_eq!(a + b, Gf16(1234 ^ 5678));
+/// assert_eq!(a * b / b, a);
+/// assert_eq!(a * a.inverse().unwrap(), Gf16::ONE);
+/// assert_eq!(a.pow(3), a * a * a);
+/// assert_eq!(Gf16::exp(a.log().unwrap()), a);
+/// ```
+///
+/// [`Engine::mul`]: crate::engine::Engine::mul
+/// [`Exp`]: crate::engine::tables::Exp
+/// [`Log`]: crate::engine::tables::Log
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Gf16(pub GfElement);
+
+impl Gf16 {
+    /// Additive identity.
+    pub const ZERO: Gf16 = Gf16(0);
+
+    /// Multiplicative identity.
+    pub const ONE: Gf16 = Gf16(1);
+
+    /// Returns the element with given discrete logarithm.
+    ///
+    /// - `log` is taken modulo [`GF_MODULUS`],
+    ///   so `Gf16::exp(GF_MODULUS) == Gf16::ONE`.
+    pub fn exp(log: GfElement) -> Self {
+        let (exp, _) = tables::initialize_exp_log();
+        Gf16(exp[log as usize])
+    }
+
+    /// Returns multiplicative inverse, or `None` for zero.
+    pub fn inverse(self) -> Option<Self> {
+        let log = self.log()?;
+        Some(Self::exp(GF_MODULUS - log))
+    }
+
+    /// Returns `true` if this is [`Gf16::ZERO`].
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns discrete logarithm in range `0 .. GF_MODULUS`,
+    /// or `None` for zero.
+    ///
+    /// This is the `log_m` value expected by [`Engine::mul`]
+    /// and [`tables::mul`].
+    ///
+    /// [`Engine::mul`]: crate::engine::Engine::mul
+    pub fn log(self) -> Option<GfElement> {
+        if self.is_zero() {
+            None
+        } else {
+            let (_, log) = tables::initialize_exp_log();
+            Some(log[self.0 as usize])
+        }
+    }
+
+    /// Returns `self` raised to power `n`.
+    ///
+    /// `Gf16::ZERO.pow(0)` is [`Gf16::ONE`].
+    pub fn pow(self, n: u64) -> Self {
+        match self.log() {
+            None if n == 0 => Self::ONE,
+            None => Self::ZERO,
+            Some(log) => {
+                let log = (log as u64 * (n % GF_MODULUS as u64)) % GF_MODULUS as u64;
+                Self::exp(log as GfElement)
+            }
+        }
+    }
+}
+
+// ======================================================================
+// Gf16 - IMPL From
+
+impl From<GfElement> for Gf16 {
+    fn from(value: GfElement) -> Self {
+        Gf16(value)
+    }
+}
+
+impl From<Gf16> for GfElement {
+    fn from(value: Gf16) -> Self {
+        value.0
+    }
+}
+
+// ======================================================================
+// Gf16 - IMPL Display
+
+impl fmt::Display for Gf16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:04X}", self.0)
+    }
+}
+
+// ======================================================================
+// Gf16 - IMPL OPS
+
+impl Add for Gf16 {
+    type Output = Gf16;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Gf16) -> Gf16 {
+        Gf16(self.0 ^ rhs.0)
+    }
+}
+
+impl Sub for Gf16 {
+    type Output = Gf16;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Gf16) -> Gf16 {
+        Gf16(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf16 {
+    type Output = Gf16;
+
+    fn mul(self, rhs: Gf16) -> Gf16 {
+        match rhs.log() {
+            None => Gf16::ZERO,
+            Some(log_rhs) => {
+                let (exp, log) = tables::initialize_exp_log();
+                Gf16(tables::mul(self.0, log_rhs, exp, log))
+            }
+        }
+    }
+}
+
+impl Div for Gf16 {
+    type Output = Gf16;
+
+    fn div(self, rhs: Gf16) -> Gf16 {
+        let log_rhs = rhs.log().expect("division by zero in GF(2^16)");
+        match self.log() {
+            None => Gf16::ZERO,
+            Some(log_lhs) => Gf16::exp(engine::sub_mod(log_lhs, log_rhs)),
+        }
+    }
+}
+
+impl AddAssign for Gf16 {
+    fn add_assign(&mut self, rhs: Gf16) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Gf16 {
+    fn sub_assign(&mut self, rhs: Gf16) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Gf16 {
+    fn mul_assign(&mut self, rhs: Gf16) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Gf16 {
+    fn div_assign(&mut self, rhs: Gf16) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for Gf16 {
+    fn sum<I: Iterator<Item = Gf16>>(iter: I) -> Gf16 {
+        iter.fold(Gf16::ZERO, Add::add)
+    }
+}
+
+impl Product for Gf16 {
+    fn product<I: Iterator<Item = Gf16>>(iter: I) -> Gf16 {
+        iter.fold(Gf16::ONE, Mul::mul)
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Engine, NoSimd};
+
+    fn samples() -> impl Iterator<Item = Gf16> {
+        (0..=u16::MAX).step_by(997).map(Gf16)
+    }
+
+    // ==================================================
+    // arithmetic
+
+    #[test]
+    fn field_axioms() {
+        for a in samples() {
+            assert_eq!(a + Gf16::ZERO, a);
+            assert_eq!(a * Gf16::ONE, a);
+            assert_eq!(a * Gf16::ZERO, Gf16::ZERO);
+            assert_eq!(a - a, Gf16::ZERO);
+
+            for b in samples().step_by(7) {
+                assert_eq!(a * b, b * a);
+                for c in samples().step_by(11) {
+                    assert_eq!(a * (b + c), a * b + a * c);
+                    assert_eq!((a * b) * c, a * (b * c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_and_div() {
+        assert_eq!(Gf16::ZERO.inverse(), None);
+        assert_eq!(Gf16::ONE.inverse(), Some(Gf16::ONE));
+
+        for a in samples().skip(1) {
+            let inv = a.inverse().unwrap();
+            assert_eq!(a * inv, Gf16::ONE);
+            assert_eq!(Gf16::ONE / a, inv);
+            assert_eq!(Gf16::ZERO / a, Gf16::ZERO);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_by_zero() {
+        let _ = Gf16::ONE / Gf16::ZERO;
+    }
+
+    #[test]
+    fn exp_log_pow() {
+        assert_eq!(Gf16::ZERO.log(), None);
+        assert_eq!(Gf16::exp(0), Gf16::ONE);
+        assert_eq!(Gf16::exp(GF_MODULUS), Gf16::ONE);
+
+        assert_eq!(Gf16::ZERO.pow(0), Gf16::ONE);
+        assert_eq!(Gf16::ZERO.pow(5), Gf16::ZERO);
+
+        for a in samples().skip(1) {
+            assert_eq!(Gf16::exp(a.log().unwrap()), a);
+            assert_eq!(a.pow(0), Gf16::ONE);
+            assert_eq!(a.pow(1), a);
+            assert_eq!(a.pow(4), a * a * a * a);
+            assert_eq!(a.pow(GF_MODULUS as u64), Gf16::ONE);
+        }
+    }
+
+    // ==================================================
+    // consistency with Engine
+
+    #[test]
+    fn matches_engine_mul() {
+        let engine = NoSimd::new();
+        for a in samples() {
+            for b in samples().skip(1).step_by(5) {
+                let mut data = [0u8; 64];
+                data[0] = a.0 as u8;
+                data[32] = (a.0 >> 8) as u8;
+                engine.mul(&mut data, b.log().unwrap());
+                let product = Gf16(u16::from(data[0]) | (u16::from(data[32]) << 8));
+                assert_eq!(product, a * b);
+            }
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Gf16(0xACCA).to_string(), "0xACCA");
+        assert_eq!(Gf16(1).to_string(), "0x0001");
+    }
+}
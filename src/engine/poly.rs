@@ -0,0 +1,452 @@
+//! Polynomial operations in novel polynomial basis.
+//!
+//! **This is an advanced module which is not needed for [simple usage] or [basic usage].**
+//!
+//! This module exposes the additive FFT used internally by the [`Engine`]:s
+//! as functions over slices of [`Gf16`], so that same polynomial arithmetic
+//! can be used outside of Reed-Solomon encoding/decoding.
+//!
+//! # Novel polynomial basis
+//!
+//! Polynomials are represented by their coefficients in the
+//! novel polynomial basis of Lin, Chung and Han, using the Cantor basis
+//! of [`CANTOR_BASIS`]. Basis polynomial `X_j` has degree `j`
+//! and is product of subspace polynomials `W_k` for bits `k` set in `j`,
+//! where `W_k` vanishes exactly on elements `Gf16(0) .. Gf16(2^k)`
+//! and is normalized so that `W_k(Gf16(2^k)) == Gf16::ONE`.
+//!
+//! Use [`novel_to_monomial`] and [`monomial_to_novel`]
+//! to convert from/to the usual monomial basis `1, x, x^2, ...`.
+//!
+//! # Evaluation points
+//!
+//! [`fft`] and [`ifft`] work on `n = data.len()` consecutive
+//! evaluation points `Gf16(offset) .. Gf16(offset + n)`,
+//! where `n` is a power of two and `offset` is a multiple of `n`.
+//!
+//! [`HighRate`] and [`LowRate`] use exactly these transforms,
+//! with each shard being evaluation of a polynomial
+//! at one point determined by shard index.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use reed_solomon_16::engine::{poly, Gf16};
+//!
+//! // 1 + x^3 in monomial basis
+//! let mut data = [Gf16(1), Gf16(0), Gf16(0), Gf16(1)];
+//!
+//! poly::monomial_to_novel(&mut data);
+//! poly::fft(&mut data, 4);
+//!
+//! // `data` now contains evaluations at `Gf16(4) .. Gf16(8)`.
+//! for (i, y) in data.iter().enumerate() {
+//!     let x = Gf16(4 + i as u16);
+//!     assert_eq!(*y, Gf16::ONE + x.pow(3));
+//! }
+//!
+//! poly::ifft(&mut data, 4);
+//! poly::novel_to_monomial(&mut data);
+//!
+//! assert_eq!(data, [Gf16(1), Gf16(0), Gf16(0), Gf16(1)]);
+//! ```
+//!
+//! [simple usage]: crate#simple-usage
+//! [basic usage]: crate#basic-usage
+//! [`CANTOR_BASIS`]: crate::engine::CANTOR_BASIS
+//! [`Engine`]: crate::engine::Engine
+//! [`HighRate`]: crate::rate::HighRate
+//! [`LowRate`]: crate::rate::LowRate
+
+use alloc::{vec, vec::Vec};
+
+use crate::engine::{tables, Gf16, GF_BITS, GF_MODULUS, GF_ORDER};
+
+// ======================================================================
+// FUNCTIONS - PUBLIC - transforms
+
+/// In-place additive FFT, i.e. evaluation of polynomial.
+///
+/// - Before function call `data` contains coefficients
+///   of polynomial of degree `< data.len()` in novel polynomial basis.
+/// - After function call `data[i]` contains value of that polynomial
+///   at point `Gf16(offset + i)`.
+///
+/// # Panics
+///
+/// Panics unless `data.len()` is a power of two,
+/// `offset` is a multiple of `data.len()`
+/// and `offset + data.len() <= GF_ORDER`.
+pub fn fft(data: &mut [Gf16], offset: usize) {
+    check_transform(data.len(), offset);
+
+    let (exp, log) = tables::initialize_exp_log();
+    let skew = tables::initialize_skew();
+
+    let size = data.len();
+    let mut dist = size / 2;
+    while dist > 0 {
+        let mut r = 0;
+        while r < size {
+            let log_m = skew[r + dist + offset - 1];
+            for i in r..r + dist {
+                if log_m != GF_MODULUS {
+                    data[i].0 ^= tables::mul(data[i + dist].0, log_m, exp, log);
+                }
+                data[i + dist].0 ^= data[i].0;
+            }
+            r += dist * 2;
+        }
+        dist /= 2;
+    }
+}
+
+/// In-place additive IFFT, i.e. interpolation of polynomial.
+///
+/// This is inverse of [`fft`] with same `offset`:
+///
+/// - Before function call `data[i]` contains value of polynomial
+///   at point `Gf16(offset + i)`.
+/// - After function call `data` contains coefficients
+///   of that polynomial of degree `< data.len()` in novel polynomial basis.
+///
+/// # Panics
+///
+/// Panics under same conditions as [`fft`].
+pub fn ifft(data: &mut [Gf16], offset: usize) {
+    check_transform(data.len(), offset);
+
+    let (exp, log) = tables::initialize_exp_log();
+    let skew = tables::initialize_skew();
+
+    let size = data.len();
+    let mut dist = 1;
+    while dist < size {
+        let mut r = 0;
+        while r < size {
+            let log_m = skew[r + dist + offset - 1];
+            for i in r..r + dist {
+                data[i + dist].0 ^= data[i].0;
+                if log_m != GF_MODULUS {
+                    data[i].0 ^= tables::mul(data[i + dist].0, log_m, exp, log);
+                }
+            }
+            r += dist * 2;
+        }
+        dist *= 2;
+    }
+}
+
+/// In-place formal derivative of polynomial in novel polynomial basis.
+///
+/// This uses `X_j' = sum X_(j - 2^k)` over bits `k` set in `j`,
+/// which holds because every `W_k` has derivative `1` with [`CANTOR_BASIS`].
+///
+/// Note that [`Engine::formal_derivative`] instead computes `p + p'`,
+/// which is sufficient for decoding because it is only
+/// evaluated at roots of `p`.
+///
+/// # Panics
+///
+/// Panics unless `data.len()` is a power of two `<= GF_ORDER`.
+///
+/// [`CANTOR_BASIS`]: crate::engine::CANTOR_BASIS
+/// [`Engine::formal_derivative`]: crate::engine::Engine::formal_derivative
+pub fn formal_derivative(data: &mut [Gf16]) {
+    check_len(data.len());
+
+    // Coefficient `i` depends only on coefficients above `i`,
+    // so this can be done in-place in ascending order.
+    for i in 0..data.len() {
+        let mut sum = Gf16::ZERO;
+        let mut bit = 1;
+        while i + bit < data.len() {
+            if i & bit == 0 {
+                sum += data[i + bit];
+            }
+            bit <<= 1;
+        }
+        data[i] = sum;
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PUBLIC - basis conversion
+
+/// Converts polynomial coefficients in-place from
+/// monomial basis to novel polynomial basis.
+///
+/// This is inverse of [`novel_to_monomial`].
+///
+/// # Panics
+///
+/// Panics unless `data.len()` is a power of two `<= GF_ORDER`.
+pub fn monomial_to_novel(data: &mut [Gf16]) {
+    check_len(data.len());
+
+    let subspace = subspace_polynomials(data.len());
+
+    let mut half = data.len() / 2;
+    while half > 0 {
+        let w = &subspace[half.trailing_zeros() as usize];
+        let inv_lead = w[w.len() - 1].inverse().unwrap();
+
+        for block in data.chunks_exact_mut(half * 2) {
+            // Divide `block` by `W` so that quotient
+            // ends up in high half and remainder in low half.
+            for j in (half..half * 2).rev() {
+                let t = block[j] * inv_lead;
+                block[j] = t;
+                for (i, &c) in w[..w.len() - 1].iter().enumerate() {
+                    block[j - half + (1 << i)] += t * c;
+                }
+            }
+        }
+
+        half /= 2;
+    }
+}
+
+/// Converts polynomial coefficients in-place from
+/// novel polynomial basis to monomial basis.
+///
+/// This is inverse of [`monomial_to_novel`].
+///
+/// # Panics
+///
+/// Panics unless `data.len()` is a power of two `<= GF_ORDER`.
+pub fn novel_to_monomial(data: &mut [Gf16]) {
+    check_len(data.len());
+
+    let subspace = subspace_polynomials(data.len());
+
+    let mut half = 1;
+    while half < data.len() {
+        let w = &subspace[half.trailing_zeros() as usize];
+        let lead = w[w.len() - 1];
+
+        for block in data.chunks_exact_mut(half * 2) {
+            // Multiply high half by `W` and add to low half.
+            for j in half..half * 2 {
+                let t = block[j];
+                block[j] = t * lead;
+                for (i, &c) in w[..w.len() - 1].iter().enumerate() {
+                    block[j - half + (1 << i)] += t * c;
+                }
+            }
+        }
+
+        half *= 2;
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn check_len(len: usize) {
+    assert!(
+        len.is_power_of_two() && len <= GF_ORDER,
+        "length must be a power of two <= GF_ORDER"
+    );
+}
+
+fn check_transform(len: usize, offset: usize) {
+    check_len(len);
+    assert!(
+        offset.is_multiple_of(len) && offset + len <= GF_ORDER,
+        "offset must be a multiple of length and offset + length <= GF_ORDER"
+    );
+}
+
+/// Returns normalized subspace polynomials `W_k` for `2^k < len`
+/// as coefficients of linearized terms, i.e. `W_k(x) = sum c[i] * x^(2^i)`.
+fn subspace_polynomials(len: usize) -> Vec<Vec<Gf16>> {
+    let count = (len.trailing_zeros() as usize).min(GF_BITS);
+    let mut result: Vec<Vec<Gf16>> = Vec::with_capacity(count);
+
+    // Unnormalized `W_0(x) = x`
+    let mut w = vec![Gf16::ONE];
+
+    for k in 0..count {
+        let beta = Gf16(1 << k);
+        let value_at_beta = eval_linearized(&w, beta);
+
+        let inv = value_at_beta.inverse().unwrap();
+        result.push(w.iter().map(|&c| c * inv).collect());
+
+        // W_{k+1}(x) = W_k(x) * (W_k(x) + W_k(beta))
+        //            = W_k(x)^2 + W_k(beta) * W_k(x)
+        let mut next = vec![Gf16::ZERO; w.len() + 1];
+        for (i, &c) in w.iter().enumerate() {
+            next[i + 1] += c * c;
+            next[i] += value_at_beta * c;
+        }
+        w = next;
+    }
+
+    result
+}
+
+fn eval_linearized(w: &[Gf16], x: Gf16) -> Gf16 {
+    let mut result = Gf16::ZERO;
+    let mut x_pow = x;
+    for &c in w {
+        result += c * x_pow;
+        x_pow = x_pow * x_pow;
+    }
+    result
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{Engine, GfElement, Naive, NoSimd, ShardsRefMut},
+        test_util,
+    };
+
+    fn random_poly(len: usize, seed: u8) -> Vec<Gf16> {
+        test_util::generate_original(1, len * 2, seed)[0]
+            .chunks_exact(2)
+            .map(|x| Gf16(u16::from_le_bytes([x[0], x[1]])))
+            .collect()
+    }
+
+    fn eval_monomial(poly: &[Gf16], x: Gf16) -> Gf16 {
+        poly.iter().rev().fold(Gf16::ZERO, |acc, &c| acc * x + c)
+    }
+
+    // Checks that `fft` and `ifft` give same result as `Engine::fft`
+    // and `Engine::ifft`, using each of the 32 element columns
+    // of 64-byte shards as a separate polynomial.
+    fn check_engine_transforms<E: Engine>(engine: E) {
+        for (len, offset) in [(2, 0), (2, 6), (16, 0), (16, 48), (64, 65472), (256, 1024)] {
+            let polys: Vec<_> = (0..32).map(|c| random_poly(len, c)).collect();
+
+            let mut shards = vec![0u8; len * 64];
+            for (c, poly) in polys.iter().enumerate() {
+                for (i, x) in poly.iter().enumerate() {
+                    shards[i * 64 + c] = x.0 as u8;
+                    shards[i * 64 + c + 32] = (x.0 >> 8) as u8;
+                }
+            }
+
+            let column = |shards: &[u8], c: usize| -> Vec<Gf16> {
+                (0..len)
+                    .map(|i| {
+                        Gf16(u16::from_le_bytes([
+                            shards[i * 64 + c],
+                            shards[i * 64 + c + 32],
+                        ]))
+                    })
+                    .collect()
+            };
+
+            let mut work = ShardsRefMut::new(len, 64, &mut shards);
+            engine.fft(&mut work, 0, len, len, offset);
+
+            let mut evaluated = Vec::new();
+            for (c, poly) in polys.iter().enumerate() {
+                let mut data = poly.clone();
+                fft(&mut data, offset);
+                assert_eq!(column(&shards, c), data);
+                evaluated.push(data);
+            }
+
+            let mut work = ShardsRefMut::new(len, 64, &mut shards);
+            engine.ifft(&mut work, 0, len, len, offset);
+
+            for (c, (poly, mut data)) in polys.iter().zip(evaluated).enumerate() {
+                ifft(&mut data, offset);
+                assert_eq!(&data, poly);
+                assert_eq!(column(&shards, c), data);
+            }
+        }
+    }
+
+    // ==================================================
+    // fft / ifft
+
+    #[test]
+    fn fft_evaluates_at_offset() {
+        for (len, offset) in [(1, 0), (1, 7), (2, 0), (16, 0), (16, 48), (64, 65472)] {
+            let monomial = random_poly(len, len as u8);
+
+            let mut data = monomial.clone();
+            monomial_to_novel(&mut data);
+            fft(&mut data, offset);
+
+            for (i, &y) in data.iter().enumerate() {
+                let x = Gf16((offset + i) as GfElement);
+                assert_eq!(y, eval_monomial(&monomial, x));
+            }
+
+            ifft(&mut data, offset);
+            novel_to_monomial(&mut data);
+            assert_eq!(data, monomial);
+        }
+    }
+
+    #[test]
+    fn fft_ifft_match_engine() {
+        check_engine_transforms(Naive::new());
+        check_engine_transforms(NoSimd::new());
+    }
+
+    #[test]
+    #[ignore]
+    fn fft_full_size() {
+        let original = random_poly(GF_ORDER, 123);
+        let mut data = original.clone();
+        fft(&mut data, 0);
+        ifft(&mut data, 0);
+        assert_eq!(data, original);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fft_bad_offset() {
+        fft(&mut [Gf16::ZERO; 4], 2);
+    }
+
+    // ==================================================
+    // formal_derivative
+
+    #[test]
+    fn formal_derivative_matches_monomial() {
+        let len = 32;
+        let monomial = random_poly(len, 45);
+
+        let mut data = monomial.clone();
+        monomial_to_novel(&mut data);
+        formal_derivative(&mut data);
+        novel_to_monomial(&mut data);
+
+        // In characteristic 2 derivative of `x^i` is `x^(i-1)` for odd `i`
+        // and zero for even `i`.
+        let mut expected = vec![Gf16::ZERO; len];
+        for i in (1..len).step_by(2) {
+            expected[i - 1] = monomial[i];
+        }
+
+        assert_eq!(data, expected);
+    }
+
+    // ==================================================
+    // basis conversion
+
+    #[test]
+    fn novel_basis_degrees() {
+        // `X_j` has degree exactly `j`.
+        for j in 0..16 {
+            let mut data = vec![Gf16::ZERO; 16];
+            data[j] = Gf16::ONE;
+            novel_to_monomial(&mut data);
+            assert!(!data[j].is_zero());
+            assert!(data[j + 1..].iter().all(|c| c.is_zero()));
+        }
+    }
+}
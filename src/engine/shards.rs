@@ -1,4 +1,5 @@
-use std::ops::{Bound, Index, IndexMut, RangeBounds};
+use alloc::vec::Vec;
+use core::ops::{Bound, Index, IndexMut, RangeBounds};
 
 // ======================================================================
 // Shards - CRATE
@@ -12,7 +13,7 @@ pub(crate) struct Shards {
 }
 
 impl Shards {
-    pub(crate) fn as_ref_mut(&mut self) -> ShardsRefMut {
+    pub(crate) fn as_ref_mut(&mut self) -> ShardsRefMut<'_> {
         ShardsRefMut::new(self.shard_count, self.shard_bytes, self.data.as_mut())
     }
 
@@ -24,6 +25,10 @@ impl Shards {
         }
     }
 
+    pub(crate) fn shard_count(&self) -> usize {
+        self.shard_count
+    }
+
     pub(crate) fn resize(&mut self, shard_count: usize, shard_bytes: usize) {
         assert!(shard_bytes > 0 && shard_bytes & 63 == 0);
 
@@ -139,7 +144,7 @@ impl<'a> ShardsRefMut<'a> {
 
     /// Splits this [`ShardsRefMut`] into two so that
     /// first includes shards `0..mid` and second includes shards `mid..`.
-    pub fn split_at_mut(&mut self, mid: usize) -> (ShardsRefMut, ShardsRefMut) {
+    pub fn split_at_mut(&mut self, mid: usize) -> (ShardsRefMut<'_>, ShardsRefMut<'_>) {
         let (a, b) = self.data.split_at_mut(mid * self.shard_bytes);
         (
             ShardsRefMut::new(mid, self.shard_bytes, a),
@@ -1,6 +1,10 @@
 //! Lookup-tables used by [`Engine`]:s.
 //!
-//! All tables are global and each is initialized at most once.
+//! All tables are global and each is initialized once on first use.
+//! With `std` feature threads racing on first use wait
+//! for one of them to calculate a table.
+//! Without `std` each of those threads may calculate a table,
+//! but only one of those is kept.
 //!
 //! # Tables
 //!
@@ -13,13 +17,29 @@
 //! | [`Skew`]     | 128 kiB | yes              | yes              | all        |
 //!
 //! [`NoSimd`]: crate::engine::NoSimd
+//!
+//! # PAR2 tables
+//!
+//! [`initialize_par2_exp_log`] returns separate [`Exp`] and [`Log`] tables
+//! for the field used by [PAR2], which are only used by [`par2`] module.
+//!
+//! [PAR2]: https://parchive.github.io/doc/Parity%20Volume%20Set%20Specification%20v2.0.html
+//! [`par2`]: crate::par2
 
-use once_cell::sync::OnceCell;
+use alloc::{boxed::Box, vec};
 
 use crate::engine::{
     self, Engine, GfElement, CANTOR_BASIS, GF_BITS, GF_MODULUS, GF_ORDER, GF_POLYNOMIAL,
 };
 
+// ======================================================================
+// CONST - PUBLIC
+
+/// Polynomial used by [PAR2] to generate its `GF(2^16)`.
+///
+/// [PAR2]: https://parchive.github.io/doc/Parity%20Volume%20Set%20Specification%20v2.0.html
+pub const PAR2_POLYNOMIAL: usize = 0x1100B;
+
 // ======================================================================
 // TYPE ALIASES - PUBLIC
 
@@ -46,6 +66,16 @@ pub type Mul16 = [[[GfElement; 16]; 4]; GF_ORDER];
 /// Used by all [`Engine`]:s for FFT and IFFT.
 pub type Skew = [GfElement; GF_MODULUS as usize];
 
+// ======================================================================
+// TYPE ALIASES - CRATE
+
+// Table which is initialized once with `get_or_init`
+// from a boxed value, dereferencing to the table.
+#[cfg(feature = "std")]
+pub(crate) type OnceTable<T> = once_cell::sync::OnceCell<Box<T>>;
+#[cfg(not(feature = "std"))]
+pub(crate) type OnceTable<T> = once_cell::race::OnceBox<T>;
+
 // ======================================================================
 // ExpLog - PRIVATE
 
@@ -57,10 +87,11 @@ struct ExpLog {
 // ======================================================================
 // STATIC - PRIVATE
 
-static EXP_LOG: OnceCell<ExpLog> = OnceCell::new();
-static LOG_WALSH: OnceCell<Box<LogWalsh>> = OnceCell::new();
-static MUL16: OnceCell<Box<Mul16>> = OnceCell::new();
-static SKEW: OnceCell<Box<Skew>> = OnceCell::new();
+static EXP_LOG: OnceTable<ExpLog> = OnceTable::new();
+static LOG_WALSH: OnceTable<LogWalsh> = OnceTable::new();
+static PAR2_EXP_LOG: OnceTable<ExpLog> = OnceTable::new();
+static MUL16: OnceTable<Mul16> = OnceTable::new();
+static SKEW: OnceTable<Skew> = OnceTable::new();
 
 // ======================================================================
 // FUNCTIONS - PUBLIC - math
@@ -117,7 +148,40 @@ pub fn initialize_exp_log() -> (&'static Exp, &'static Log) {
 
         exp[GF_MODULUS as usize] = exp[0];
 
-        ExpLog { exp, log }
+        Box::new(ExpLog { exp, log })
+    });
+
+    (&exp_log.exp, &exp_log.log)
+}
+
+/// Initializes and returns [`Exp`] and [`Log`] tables of [PAR2].
+///
+/// - Field is generated by [`PAR2_POLYNOMIAL`] in plain polynomial basis
+///   with generator `2`, so elements are **not** compatible with
+///   tables from [`initialize_exp_log`] and must not be given to [`Engine`]:s.
+/// - Table layout is same as with [`initialize_exp_log`],
+///   so [`mul`] works with these tables too.
+///
+/// [PAR2]: https://parchive.github.io/doc/Parity%20Volume%20Set%20Specification%20v2.0.html
+pub fn initialize_par2_exp_log() -> (&'static Exp, &'static Log) {
+    let exp_log = PAR2_EXP_LOG.get_or_init(|| {
+        let mut exp = Box::new([0; GF_ORDER]);
+        let mut log = Box::new([0; GF_ORDER]);
+
+        let mut state = 1;
+        for i in 0..GF_MODULUS {
+            exp[i as usize] = state as GfElement;
+            log[state] = i;
+            state <<= 1;
+            if state >= GF_ORDER {
+                state ^= PAR2_POLYNOMIAL;
+            }
+        }
+
+        exp[GF_MODULUS as usize] = exp[0];
+        log[0] = GF_MODULUS;
+
+        Box::new(ExpLog { exp, log })
     });
 
     (&exp_log.exp, &exp_log.log)
@@ -139,6 +203,7 @@ pub fn initialize_log_walsh<E: Engine>() -> &'static LogWalsh {
 }
 
 /// Initializes and returns [`Mul16`] table.
+#[allow(clippy::needless_range_loop)]
 pub fn initialize_mul16() -> &'static Mul16 {
     MUL16.get_or_init(|| {
         let (exp, log) = initialize_exp_log();
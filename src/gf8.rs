@@ -0,0 +1,1158 @@
+//! Reed-Solomon `GF(2^8)` erasure coding for small shard counts.
+//!
+//! This module is a sibling of the main `GF(2^16)` codec,
+//! using same FFT-based algorithm but over `GF(2^8)`,
+//! similar to FF8 variant of [Leopard-RS].
+//!
+//! - Any combination of 1 - 128 original shards with 1 - 128 recovery shards.
+//! - Up to 255 original or recovery shards with some limitations,
+//!   see [algorithm] with `65536` replaced by `256`.
+//! - Shard size must be non-zero multiple of 64 bytes.
+//! - Lookup-tables take 64 kiB instead of 384 kiB - 8.5 MiB
+//!   and arithmetic is done with bytes instead of 16-bit words.
+//!
+//! [`Gf8Rate`] implements [`Rate`] so it can be used like the rates
+//! in [`rate`] module, and [`ReedSolomonEncoder`] / [`ReedSolomonDecoder`]
+//! here are wrappers for it like [`crate::ReedSolomonEncoder`] /
+//! [`crate::ReedSolomonDecoder`] are for [`DefaultRate`].
+//! However the codes are different, i.e. shards encoded with one
+//! can't be decoded with the other.
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::gf8::{ReedSolomonDecoder, ReedSolomonEncoder};
+//!
+//! let original: Vec<[u8; 64]> = (0..10u8).map(|i| [i; 64]).collect();
+//!
+//! let mut encoder = ReedSolomonEncoder::new(10, 4, 64)?;
+//! for shard in &original {
+//!     encoder.add_original_shard(shard)?;
+//! }
+//! let result = encoder.encode()?;
+//! let recovery: Vec<_> = (0..4).map(|i| result.recovery(i).unwrap().to_vec()).collect();
+//!
+//! // Lose original shards 0, 3, 5 and 9.
+//! let mut decoder = ReedSolomonDecoder::new(10, 4, 64)?;
+//! for index in [1, 2, 4, 6, 7, 8] {
+//!     decoder.add_original_shard(index, &original[index])?;
+//! }
+//! for (index, recovery) in recovery.iter().enumerate() {
+//!     decoder.add_recovery_shard(index, recovery)?;
+//! }
+//!
+//! let result = decoder.decode()?;
+//! for index in [0, 3, 5, 9] {
+//!     assert_eq!(result.restored_original(index).unwrap(), original[index]);
+//! }
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [Leopard-RS]: https://github.com/catid/leopard
+//! [algorithm]: crate::algorithm
+//! [`rate`]: crate::rate
+//! [`DefaultRate`]: crate::rate::DefaultRate
+
+use alloc::vec::Vec;
+use core::{cmp::Ordering, marker::PhantomData};
+
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    engine::{checked_next_multiple_of, DefaultEngine, Engine, ShardsRefMut},
+    rate::{self, DecoderWork, EncoderWork, Field, Rate, RateDecoder, RateEncoder},
+    DecoderResult, EncoderResult, Error,
+};
+
+use self::engine::Tables;
+
+mod engine;
+
+// ======================================================================
+// CONST - PUBLIC
+
+/// Size of `GF(2^8)` field element in bits.
+pub const GF_BITS: usize = 8;
+
+/// Galois field order, i.e. number of elements.
+pub const GF_ORDER: usize = 256;
+
+/// `GF_ORDER - 1`
+pub const GF_MODULUS: u8 = 255;
+
+/// Galois field polynomial.
+pub const GF_POLYNOMIAL: usize = 0x11D;
+
+/// Cantor basis of the field, same as in [Leopard-RS].
+///
+/// [Leopard-RS]: https://github.com/catid/leopard
+pub const CANTOR_BASIS: [u8; GF_BITS] = [1, 214, 152, 146, 86, 200, 88, 230];
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Counterpart of `rate::use_high_rate` with `GF_ORDER = 256`.
+fn use_high_rate(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
+    let unsupported = Err(Error::UnsupportedShardCount {
+        original_count,
+        recovery_count,
+    });
+
+    if original_count == 0
+        || recovery_count == 0
+        || original_count >= GF_ORDER
+        || recovery_count >= GF_ORDER
+    {
+        return unsupported;
+    }
+
+    let original_count_pow2 = original_count.next_power_of_two();
+    let recovery_count_pow2 = recovery_count.next_power_of_two();
+
+    let smaller_pow2 = core::cmp::min(original_count_pow2, recovery_count_pow2);
+    let larger = core::cmp::max(original_count, recovery_count);
+
+    if smaller_pow2 + larger > GF_ORDER {
+        return unsupported;
+    }
+
+    match original_count_pow2.cmp(&recovery_count_pow2) {
+        Ordering::Less => Ok(false),
+        Ordering::Greater => Ok(true),
+        // Using the "wrong" rate on purpose, see `rate::use_high_rate`.
+        Ordering::Equal => Ok(original_count <= recovery_count),
+    }
+}
+
+// ======================================================================
+// Gf8Rate - PUBLIC
+
+/// Reed-Solomon `GF(2^8)` encoder/decoder generator.
+///
+/// This chooses between high and low rate like [`DefaultRate`].
+/// Field arithmetic is done with `GF(2^8)` tables of this module
+/// while the given [`Engine`] is used only for operations
+/// which don't depend on the field, like `xor`.
+///
+/// [`Rate::generator_matrix`] returns `GF(2^8)` coefficients
+/// as [`Gf16`] values whose high byte is zero.
+///
+/// [`DefaultRate`]: crate::rate::DefaultRate
+/// [`Gf16`]: crate::engine::Gf16
+pub struct Gf8Rate<E: Engine>(PhantomData<E>);
+
+impl<E: Engine> Rate<E> for Gf8Rate<E> {
+    type RateEncoder = Gf8RateEncoder<E>;
+    type RateDecoder = Gf8RateDecoder<E>;
+
+    fn encode_one<T>(
+        original_count: usize,
+        recovery_count: usize,
+        recovery_index: usize,
+        original: T,
+        _engine: &E,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: IntoIterator,
+        T::Item: AsRef<[u8]>,
+    {
+        // Same coefficients as in `HighRate::encode_one`
+        // and `LowRate::encode_one`, but in `GF(2^8)`.
+        let high_rate = use_high_rate(original_count, recovery_count)?;
+        let tables = engine::tables();
+        rate::encode_one_with::<Self, _, _, _, _>(
+            original_count,
+            recovery_count,
+            recovery_index,
+            original,
+            tables,
+            |j| {
+                let (x, y) =
+                    rate::coefficient(high_rate, original_count, recovery_count, j, recovery_index);
+                tables.div_element(x, y)
+            },
+        )
+    }
+
+    fn supports(original_count: usize, recovery_count: usize) -> bool {
+        use_high_rate(original_count, recovery_count).is_ok()
+    }
+}
+
+// ======================================================================
+// Gf8RateEncoder - PUBLIC
+
+/// Reed-Solomon `GF(2^8)` encoder.
+pub struct Gf8RateEncoder<E: Engine> {
+    engine: E,
+    high_rate: bool,
+    tables: &'static Tables,
+    work: EncoderWork,
+}
+
+impl<E: Engine> RateEncoder<E> for Gf8RateEncoder<E> {
+    type Rate = Gf8Rate<E>;
+
+    fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error> {
+        self.work.add_original_shard(original_shard)
+    }
+
+    fn encode(&mut self) -> Result<EncoderResult<'_>, Error> {
+        let (mut work, original_count, recovery_count) = self.work.encode_begin()?;
+
+        if self.high_rate {
+            Self::encode_high(self.tables, &mut work, original_count, recovery_count);
+        } else {
+            Self::encode_low(self.tables, &mut work, original_count, recovery_count);
+        }
+
+        Ok(EncoderResult::new(&mut self.work))
+    }
+
+    fn into_parts(self) -> (E, EncoderWork) {
+        (self.engine, self.work)
+    }
+
+    fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        engine: E,
+        work: Option<EncoderWork>,
+    ) -> Result<Self, Error> {
+        let mut work = work.unwrap_or_default();
+        let high_rate = Self::reset_work(original_count, recovery_count, shard_bytes, &mut work)?;
+        Ok(Self {
+            engine,
+            high_rate,
+            tables: engine::tables(),
+            work,
+        })
+    }
+
+    fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        self.high_rate =
+            Self::reset_work(original_count, recovery_count, shard_bytes, &mut self.work)?;
+        Ok(())
+    }
+}
+
+// ======================================================================
+// Gf8RateEncoder - PRIVATE
+
+impl<E: Engine> Gf8RateEncoder<E> {
+    fn encode_high(
+        tables: &Tables,
+        work: &mut ShardsRefMut,
+        original_count: usize,
+        recovery_count: usize,
+    ) {
+        let chunk_size = recovery_count.next_power_of_two();
+
+        // FIRST CHUNK
+
+        let first_count = core::cmp::min(original_count, chunk_size);
+
+        work.zero(first_count..chunk_size);
+        tables.ifft::<E>(work, 0, chunk_size, first_count, chunk_size);
+
+        if original_count > chunk_size {
+            // FULL CHUNKS
+
+            let mut chunk_start = chunk_size;
+            while chunk_start + chunk_size <= original_count {
+                tables.ifft::<E>(
+                    work,
+                    chunk_start,
+                    chunk_size,
+                    chunk_size,
+                    chunk_start + chunk_size,
+                );
+                E::xor_within(work, 0, chunk_start, chunk_size);
+                chunk_start += chunk_size;
+            }
+
+            // FINAL PARTIAL CHUNK
+
+            let last_count = original_count % chunk_size;
+            if last_count > 0 {
+                work.zero(chunk_start + last_count..);
+                tables.ifft::<E>(
+                    work,
+                    chunk_start,
+                    chunk_size,
+                    last_count,
+                    chunk_start + chunk_size,
+                );
+                E::xor_within(work, 0, chunk_start, chunk_size);
+            }
+        }
+
+        // FFT
+
+        tables.fft::<E>(work, 0, chunk_size, recovery_count, 0);
+    }
+
+    fn encode_low(
+        tables: &Tables,
+        work: &mut ShardsRefMut,
+        original_count: usize,
+        recovery_count: usize,
+    ) {
+        let chunk_size = original_count.next_power_of_two();
+
+        // ZEROPAD ORIGINAL
+
+        work.zero(original_count..chunk_size);
+
+        // IFFT - ORIGINAL
+
+        tables.ifft::<E>(work, 0, chunk_size, original_count, 0);
+
+        // COPY IFFT RESULT TO OTHER CHUNKS
+
+        let mut chunk_start = chunk_size;
+        while chunk_start < recovery_count {
+            work.copy_within(0, chunk_start, chunk_size);
+            chunk_start += chunk_size;
+        }
+
+        // FFT - FULL CHUNKS
+
+        let mut chunk_start = 0;
+        while chunk_start + chunk_size <= recovery_count {
+            tables.fft::<E>(
+                work,
+                chunk_start,
+                chunk_size,
+                chunk_size,
+                chunk_start + chunk_size,
+            );
+            chunk_start += chunk_size;
+        }
+
+        // FFT - FINAL PARTIAL CHUNK
+
+        let last_count = recovery_count % chunk_size;
+        if last_count > 0 {
+            tables.fft::<E>(
+                work,
+                chunk_start,
+                chunk_size,
+                last_count,
+                chunk_start + chunk_size,
+            );
+        }
+    }
+
+    // Returns `true` if high rate is used.
+    fn reset_work(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        work: &mut EncoderWork,
+    ) -> Result<bool, Error> {
+        Self::validate(original_count, recovery_count, shard_bytes)?;
+        let high_rate = use_high_rate(original_count, recovery_count)?;
+
+        let work_count = if high_rate {
+            checked_next_multiple_of(original_count, recovery_count.next_power_of_two())
+        } else {
+            checked_next_multiple_of(recovery_count, original_count.next_power_of_two())
+        };
+
+        work.reset(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            work_count.unwrap(),
+        );
+        Ok(high_rate)
+    }
+}
+
+// ======================================================================
+// Gf8RateDecoder - PUBLIC
+
+/// Reed-Solomon `GF(2^8)` decoder.
+pub struct Gf8RateDecoder<E: Engine> {
+    engine: E,
+    high_rate: bool,
+    tables: &'static Tables,
+    work: DecoderWork,
+}
+
+impl<E: Engine> RateDecoder<E> for Gf8RateDecoder<E> {
+    type Rate = Gf8Rate<E>;
+
+    fn add_original_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+    ) -> Result<(), Error> {
+        self.work.add_original_shard(index, original_shard)
+    }
+
+    fn add_recovery_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+    ) -> Result<(), Error> {
+        self.work.add_recovery_shard(index, recovery_shard)
+    }
+
+    fn decode(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.decode_private(false)
+    }
+
+    fn decode_with_errors(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.work.correct_errors_in::<E, _>(self.tables)?;
+        self.decode()
+    }
+
+    fn into_parts(self) -> (E, DecoderWork) {
+        (self.engine, self.work)
+    }
+
+    fn missing_original_indexes(&self) -> Vec<usize> {
+        self.work.missing_original_indexes()
+    }
+
+    fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        engine: E,
+        work: Option<DecoderWork>,
+    ) -> Result<Self, Error> {
+        let mut work = work.unwrap_or_default();
+        let high_rate = Self::reset_work(original_count, recovery_count, shard_bytes, &mut work)?;
+        Ok(Self {
+            engine,
+            high_rate,
+            tables: engine::tables(),
+            work,
+        })
+    }
+
+    fn original_count(&self) -> usize {
+        self.work.original_count()
+    }
+
+    fn original_received_count(&self) -> usize {
+        self.work.original_received_count()
+    }
+
+    fn recovery_count(&self) -> usize {
+        self.work.recovery_count()
+    }
+
+    fn recovery_received_count(&self) -> usize {
+        self.work.recovery_received_count()
+    }
+
+    fn repair(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.decode_private(true)
+    }
+
+    fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        self.high_rate =
+            Self::reset_work(original_count, recovery_count, shard_bytes, &mut self.work)?;
+        Ok(())
+    }
+}
+
+// ======================================================================
+// Gf8RateDecoder - PRIVATE
+
+impl<E: Engine> Gf8RateDecoder<E> {
+    fn decode_private(&mut self, restore_recovery: bool) -> Result<DecoderResult<'_>, Error> {
+        let (mut work, original_count, recovery_count, received) =
+            if let Some(stuff) = self.work.decode_begin(restore_recovery)? {
+                stuff
+            } else {
+                // Nothing to do, all requested shards are complete.
+                return Ok(DecoderResult::new(&mut self.work));
+            };
+
+        if self.high_rate {
+            Self::decode_high(
+                self.tables,
+                &mut work,
+                original_count,
+                recovery_count,
+                received,
+                restore_recovery,
+            );
+        } else {
+            Self::decode_low(
+                self.tables,
+                &mut work,
+                original_count,
+                recovery_count,
+                received,
+                restore_recovery,
+            );
+        }
+
+        Ok(DecoderResult::new(&mut self.work))
+    }
+
+    fn decode_high(
+        tables: &Tables,
+        work: &mut ShardsRefMut,
+        original_count: usize,
+        recovery_count: usize,
+        received: &FixedBitSet,
+        restore_recovery: bool,
+    ) {
+        let chunk_size = recovery_count.next_power_of_two();
+        let original_end = chunk_size + original_count;
+        let work_count = work.len();
+
+        // ERASURE LOCATIONS
+
+        let mut erasures = [0; GF_ORDER];
+
+        for i in (0..recovery_count).chain(chunk_size..original_end) {
+            if !received[i] {
+                erasures[i] = 1;
+            }
+        }
+
+        erasures[recovery_count..chunk_size].fill(1);
+
+        // EVALUATE POLYNOMIAL
+
+        tables.eval_poly(&mut erasures, original_end);
+
+        // MULTIPLY SHARDS
+
+        for i in (0..recovery_count).chain(chunk_size..original_end) {
+            if received[i] {
+                tables.mul(&mut work[i], erasures[i]);
+            } else {
+                work[i].fill(0);
+            }
+        }
+
+        work.zero(recovery_count..chunk_size);
+        work.zero(original_end..);
+
+        // IFFT / FORMAL DERIVATIVE / FFT
+
+        tables.ifft::<E>(work, 0, work_count, original_end, 0);
+        E::formal_derivative(work);
+        tables.fft::<E>(work, 0, work_count, original_end, 0);
+
+        // REVEAL ERASURES
+
+        for i in chunk_size..original_end {
+            if !received[i] {
+                tables.mul(&mut work[i], GF_MODULUS - erasures[i]);
+            }
+        }
+
+        if restore_recovery {
+            for i in 0..recovery_count {
+                if !received[i] {
+                    tables.mul(&mut work[i], GF_MODULUS - erasures[i]);
+                }
+            }
+        }
+    }
+
+    fn decode_low(
+        tables: &Tables,
+        work: &mut ShardsRefMut,
+        original_count: usize,
+        recovery_count: usize,
+        received: &FixedBitSet,
+        restore_recovery: bool,
+    ) {
+        let chunk_size = original_count.next_power_of_two();
+        let recovery_end = chunk_size + recovery_count;
+        let work_count = work.len();
+
+        // ERASURE LOCATIONS
+
+        let mut erasures = [0; GF_ORDER];
+
+        for i in (0..original_count).chain(chunk_size..recovery_end) {
+            if !received[i] {
+                erasures[i] = 1;
+            }
+        }
+
+        erasures[recovery_end..].fill(1);
+
+        // EVALUATE POLYNOMIAL
+
+        tables.eval_poly(&mut erasures, GF_ORDER);
+
+        // MULTIPLY SHARDS
+
+        for i in (0..original_count).chain(chunk_size..recovery_end) {
+            if received[i] {
+                tables.mul(&mut work[i], erasures[i]);
+            } else {
+                work[i].fill(0);
+            }
+        }
+
+        work.zero(original_count..chunk_size);
+        work.zero(recovery_end..);
+
+        // IFFT / FORMAL DERIVATIVE / FFT
+
+        tables.ifft::<E>(work, 0, work_count, recovery_end, 0);
+        E::formal_derivative(work);
+        tables.fft::<E>(work, 0, work_count, recovery_end, 0);
+
+        // REVEAL ERASURES
+
+        for i in 0..original_count {
+            if !received[i] {
+                tables.mul(&mut work[i], GF_MODULUS - erasures[i]);
+            }
+        }
+
+        if restore_recovery {
+            for i in chunk_size..recovery_end {
+                if !received[i] {
+                    tables.mul(&mut work[i], GF_MODULUS - erasures[i]);
+                }
+            }
+        }
+    }
+
+    // Returns `true` if high rate is used.
+    fn reset_work(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        work: &mut DecoderWork,
+    ) -> Result<bool, Error> {
+        Self::validate(original_count, recovery_count, shard_bytes)?;
+        let high_rate = use_high_rate(original_count, recovery_count)?;
+
+        // high rate: work[..recovery_count] = recovery, work[recovery_count_pow2..] = original
+        // low rate:  work[..original_count] = original, work[original_count_pow2..] = recovery
+        let (original_base_pos, recovery_base_pos, work_count) = if high_rate {
+            let chunk_size = recovery_count.next_power_of_two();
+            (
+                chunk_size,
+                0,
+                (chunk_size + original_count).next_power_of_two(),
+            )
+        } else {
+            let chunk_size = original_count.next_power_of_two();
+            (
+                0,
+                chunk_size,
+                (chunk_size + recovery_count).next_power_of_two(),
+            )
+        };
+
+        work.reset(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            original_base_pos,
+            recovery_base_pos,
+            work_count,
+        );
+        Ok(high_rate)
+    }
+}
+
+// ======================================================================
+// ReedSolomonEncoder - PUBLIC
+
+/// Reed-Solomon `GF(2^8)` encoder using [`DefaultEngine`] and [`Gf8Rate`].
+///
+/// See [module documentation](crate::gf8) for details.
+///
+/// [`DefaultEngine`]: crate::engine::DefaultEngine
+pub struct ReedSolomonEncoder(Gf8RateEncoder<DefaultEngine>);
+
+impl ReedSolomonEncoder {
+    /// Adds one original shard to the encoder.
+    ///
+    /// Original shards have indexes `0..original_count` corresponding to the order
+    /// in which they are added and these same indexes must be used when decoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error> {
+        self.0.add_original_shard(original_shard)
+    }
+
+    /// Encodes the added original shards returning [`EncoderResult`]
+    /// which contains the generated recovery shards.
+    ///
+    /// Encoder is reset and ready for new round of encoding
+    /// when returned [`EncoderResult`] is dropped.
+    pub fn encode(&mut self) -> Result<EncoderResult<'_>, Error> {
+        self.0.encode()
+    }
+
+    /// Creates new encoder with given configuration
+    /// and allocates required working space.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self(Gf8RateEncoder::new(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            DefaultEngine::new(),
+            None,
+        )?))
+    }
+
+    /// Resets encoder to given configuration.
+    ///
+    /// - Added original shards are forgotten.
+    /// - Existing working space is re-used if it's large enough
+    ///   or re-allocated otherwise.
+    pub fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        self.0.reset(original_count, recovery_count, shard_bytes)
+    }
+
+    /// Returns `true` if given `original_count` / `recovery_count`
+    /// combination is supported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::gf8::ReedSolomonEncoder;
+    ///
+    /// assert_eq!(ReedSolomonEncoder::supports(240, 16), true);
+    /// assert_eq!(ReedSolomonEncoder::supports(240, 17), false);
+    /// ```
+    pub fn supports(original_count: usize, recovery_count: usize) -> bool {
+        Gf8Rate::<DefaultEngine>::supports(original_count, recovery_count)
+    }
+}
+
+// ======================================================================
+// ReedSolomonDecoder - PUBLIC
+
+/// Reed-Solomon `GF(2^8)` decoder using [`DefaultEngine`] and [`Gf8Rate`].
+///
+/// See [module documentation](crate::gf8) for details.
+///
+/// [`DefaultEngine`]: crate::engine::DefaultEngine
+pub struct ReedSolomonDecoder(Gf8RateDecoder<DefaultEngine>);
+
+impl ReedSolomonDecoder {
+    /// Adds one original shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+    ) -> Result<(), Error> {
+        self.0.add_original_shard(index, original_shard)
+    }
+
+    /// Adds one recovery shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_recovery_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+    ) -> Result<(), Error> {
+        self.0.add_recovery_shard(index, recovery_shard)
+    }
+
+    /// Decodes the added shards returning [`DecoderResult`]
+    /// which contains the restored original shards.
+    ///
+    /// When returned [`DecoderResult`] is dropped the decoder is
+    /// automatically reset and ready for new round of decoding.
+    pub fn decode(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.0.decode()
+    }
+
+    /// Creates new decoder with given configuration
+    /// and allocates required working space.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self(Gf8RateDecoder::new(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            DefaultEngine::new(),
+            None,
+        )?))
+    }
+
+    /// Resets decoder to given configuration.
+    ///
+    /// - Added shards are forgotten.
+    /// - Existing working space is re-used if it's large enough
+    ///   or re-allocated otherwise.
+    pub fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        self.0.reset(original_count, recovery_count, shard_bytes)
+    }
+
+    /// Returns `true` if given `original_count` / `recovery_count`
+    /// combination is supported.
+    pub fn supports(original_count: usize, recovery_count: usize) -> bool {
+        Gf8Rate::<DefaultEngine>::supports(original_count, recovery_count)
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::{engine::NoSimd, test_util};
+
+    // Evaluates at point `x` the polynomial which has
+    // `values[k]` at point `points[k]`, by Lagrange interpolation.
+    fn interpolate(points: &[usize], values: &[u8], x: usize) -> usize {
+        let tables = engine::tables();
+        let mut sum = 0;
+        for (j, (&point_j, &value_j)) in points.iter().zip(values).enumerate() {
+            let mut term = value_j as usize;
+            for (k, &point_k) in points.iter().enumerate() {
+                if k != j {
+                    term = tables.mul_element(term, x ^ point_k);
+                    term = tables.div_element(term, point_j ^ point_k);
+                }
+            }
+            sum ^= term;
+        }
+        sum
+    }
+
+    // Calculates recovery shards from the definition of the code,
+    // see [algorithm](crate::algorithm), without FFT.
+    fn encode_by_interpolation(
+        original_count: usize,
+        recovery_count: usize,
+        original: &[Vec<u8>],
+    ) -> Vec<Vec<u8>> {
+        let shard_bytes = original[0].len();
+        let mut recovery = vec![vec![0u8; shard_bytes]; recovery_count];
+
+        if super::use_high_rate(original_count, recovery_count).unwrap() {
+            // Each chunk of original shards is interpolated separately
+            // and the results are summed at points `0..recovery_count`.
+            let chunk_size = recovery_count.next_power_of_two();
+            for chunk_start in (0..original_count).step_by(chunk_size) {
+                let points: Vec<_> = (0..chunk_size)
+                    .map(|k| chunk_size + chunk_start + k)
+                    .collect();
+                for byte in 0..shard_bytes {
+                    let values: Vec<_> = (chunk_start..chunk_start + chunk_size)
+                        .map(|j| original.get(j).map_or(0, |shard| shard[byte]))
+                        .collect();
+                    for (i, recovery) in recovery.iter_mut().enumerate() {
+                        recovery[byte] ^= interpolate(&points, &values, i) as u8;
+                    }
+                }
+            }
+        } else {
+            // Original shards are interpolated at points `0..chunk_size`
+            // and evaluated at points `chunk_size..chunk_size + recovery_count`.
+            let chunk_size = original_count.next_power_of_two();
+            let points: Vec<_> = (0..chunk_size).collect();
+            for byte in 0..shard_bytes {
+                let values: Vec<_> = (0..chunk_size)
+                    .map(|j| original.get(j).map_or(0, |shard| shard[byte]))
+                    .collect();
+                for (i, recovery) in recovery.iter_mut().enumerate() {
+                    recovery[byte] = interpolate(&points, &values, chunk_size + i) as u8;
+                }
+            }
+        }
+
+        recovery
+    }
+
+    // ============================================================
+    // FIELD
+
+    #[test]
+    fn field() {
+        let tables = engine::tables();
+        for x in 0..GF_ORDER {
+            assert_eq!(tables.mul_element(x, 1), x);
+            assert_eq!(tables.mul_element(x, 0), 0);
+            if x != 0 {
+                assert_eq!(tables.mul_element(tables.div_element(1, x), x), 1);
+            }
+            for y in 0..GF_ORDER {
+                assert_eq!(tables.mul_element(x, y), tables.mul_element(y, x));
+                for z in [1, 2, 77, 255] {
+                    assert_eq!(
+                        tables.mul_element(x, y ^ z),
+                        tables.mul_element(x, y) ^ tables.mul_element(x, z)
+                    );
+                }
+            }
+        }
+    }
+
+    // ============================================================
+    // KNOWN ANSWERS
+
+    #[test]
+    fn encode_matches_interpolation() {
+        for (original_count, recovery_count) in [
+            (1, 1),
+            (3, 5),
+            (5, 3),
+            (4, 4),
+            (10, 4),
+            (20, 6),
+            (3, 10),
+            (6, 20),
+        ] {
+            let original = test_util::generate_original(original_count, 64, original_count as u8);
+
+            let mut encoder =
+                Gf8Rate::encoder(original_count, recovery_count, 64, NoSimd::new(), None).unwrap();
+            for original in &original {
+                encoder.add_original_shard(original).unwrap();
+            }
+            let result = encoder.encode().unwrap();
+            let recovery: Vec<_> = result.recovery_iter().collect();
+
+            assert_eq!(
+                recovery,
+                encode_by_interpolation(original_count, recovery_count, &original)
+            );
+        }
+    }
+
+    #[test]
+    fn encode_one() {
+        for (original_count, recovery_count) in [(3, 5), (5, 3), (10, 4), (6, 20)] {
+            let original = test_util::generate_original(original_count, 64, 0);
+            let expected = encode_by_interpolation(original_count, recovery_count, &original);
+
+            for (i, expected) in expected.iter().enumerate() {
+                assert_eq!(
+                    &Gf8Rate::encode_one(
+                        original_count,
+                        recovery_count,
+                        i,
+                        &original,
+                        &NoSimd::new()
+                    )
+                    .unwrap(),
+                    expected
+                );
+            }
+        }
+    }
+
+    // ============================================================
+    // ROUNDTRIPS
+
+    #[test]
+    fn roundtrip_high_rate() {
+        roundtrip_single!(
+            Gf8Rate,
+            10,
+            4,
+            128,
+            GF8_HIGH_10_4,
+            &[1..3, 4..5, 6..9],
+            &[0..4],
+            104
+        );
+        roundtrip_single!(
+            Gf8Rate,
+            240,
+            16,
+            64,
+            GF8_HIGH_240_16,
+            &[16..240],
+            &[0..16],
+            240
+        );
+    }
+
+    #[test]
+    fn roundtrip_low_rate() {
+        roundtrip_single!(Gf8Rate, 3, 5, 128, GF8_LOW_3_5, &[1], &[1, 4], 35);
+        roundtrip_single!(Gf8Rate, 16, 240, 64, GF8_LOW_16_240, &[], &[224..240], 16);
+    }
+
+    #[test]
+    fn roundtrip_nothing_lost() {
+        roundtrip_single!(Gf8Rate, 10, 4, 128, GF8_HIGH_10_4, &[0..10], &[], 104);
+    }
+
+    // ============================================================
+    // use_high_rate
+
+    #[test]
+    fn use_high_rate() {
+        fn err(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
+            Err(Error::UnsupportedShardCount {
+                original_count,
+                recovery_count,
+            })
+        }
+
+        for (original_count, recovery_count, expected) in [
+            (0, 1, err(0, 1)),
+            (1, 0, err(1, 0)),
+            (3, 3, Ok(true)),
+            (3, 5, Ok(false)),
+            (5, 3, Ok(true)),
+            (128, 128, Ok(true)),
+            (16, 240, Ok(false)),
+            (16, 241, err(16, 241)),
+            (240, 16, Ok(true)),
+            (241, 16, err(241, 16)),
+            (256, 1, err(256, 1)),
+        ] {
+            assert_eq!(
+                super::use_high_rate(original_count, recovery_count),
+                expected
+            );
+        }
+    }
+
+    // ============================================================
+    // RECOVERY HASHES
+
+    // SHA256 hashes of recovery shards, see `test_util`.
+
+    const GF8_HIGH_10_4: &str = "eb4ebef65133d85a83dcfae8f1181cc5b4a17c359464a5009303fa08c3cbf117";
+    const GF8_HIGH_240_16: &str =
+        "3dded19ff7fe490c0ed907c55f1b510219630272628ee327faad50da5aed7f0b";
+    const GF8_LOW_3_5: &str = "0e430c7c41cc1f07ed859ea17822ee37221fb9222c6a050ff5484499d9f5ca5a";
+    const GF8_LOW_16_240: &str = "063c4f5494e8789dd3bde724a151266d003aa2ce8a8f3bf486d3bce4905f91a5";
+
+    // ============================================================
+    // Gf8Rate
+
+    mod gf8_rate {
+        use crate::{
+            engine::NoSimd,
+            gf8::Gf8Rate,
+            rate::{Rate, RateDecoder},
+            Error,
+        };
+
+        test_rate_decoder_error_correction! {Gf8Rate}
+        test_rate_decoder_repair! {Gf8Rate}
+
+        #[test]
+        fn decoder_with_unsupported_shard_count() {
+            assert_eq!(
+                Gf8Rate::<NoSimd>::decoder(16, 241, 64, NoSimd::new(), None).err(),
+                Some(Error::UnsupportedShardCount {
+                    original_count: 16,
+                    recovery_count: 241,
+                })
+            );
+        }
+
+        #[test]
+        fn decoder_reset_switches_rate() {
+            let mut decoder = Gf8Rate::decoder(3, 5, 64, NoSimd::new(), None).unwrap();
+            decoder.reset(5, 3, 64).unwrap();
+            assert_eq!(decoder.original_count(), 5);
+            assert_eq!(decoder.recovery_count(), 3);
+        }
+    }
+
+    // ============================================================
+    // Gf8RateEncoder
+
+    mod gf8_rate_encoder {
+        use crate::{engine::NoSimd, gf8::Gf8RateEncoder, rate::RateEncoder, Error};
+
+        test_rate_encoder_errors! {Gf8RateEncoder}
+    }
+
+    // ============================================================
+    // Gf8RateDecoder
+
+    mod gf8_rate_decoder {
+        use crate::{engine::NoSimd, gf8::Gf8RateDecoder, rate::RateDecoder, Error};
+
+        test_rate_decoder_errors! {Gf8RateDecoder}
+    }
+
+    // ============================================================
+    // ReedSolomonEncoder / ReedSolomonDecoder
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ReedSolomonEncoder::new(2, 2, 100).err(),
+            Some(Error::InvalidShardSize { shard_bytes: 100 })
+        );
+
+        let mut encoder = ReedSolomonEncoder::new(2, 2, 64).unwrap();
+        assert_eq!(
+            encoder.encode().err(),
+            Some(Error::TooFewOriginalShards {
+                original_count: 2,
+                original_received_count: 0,
+            })
+        );
+
+        let mut decoder = ReedSolomonDecoder::new(2, 2, 64).unwrap();
+        assert_eq!(
+            decoder.add_original_shard(2, [0; 64]),
+            Err(Error::InvalidOriginalShardIndex {
+                original_count: 2,
+                index: 2,
+            })
+        );
+        decoder.add_recovery_shard(1, [0; 64]).unwrap();
+        assert_eq!(
+            decoder.add_recovery_shard(1, [0; 64]),
+            Err(Error::DuplicateRecoveryShardIndex { index: 1 })
+        );
+        assert_eq!(
+            decoder.decode().err(),
+            Some(Error::NotEnoughShards {
+                original_count: 2,
+                original_received_count: 0,
+                recovery_received_count: 1,
+            })
+        );
+    }
+}
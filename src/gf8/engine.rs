@@ -0,0 +1,308 @@
+// Lookup-tables and low-level algorithms for `GF(2^8)`.
+//
+// These are direct 8-bit counterparts of `engine::tables`
+// and `engine::Naive`, see those for details.
+// Operations which don't depend on the field, like `xor`,
+// are done with the given `Engine`.
+
+use alloc::boxed::Box;
+
+use crate::{
+    engine::{tables::OnceTable, Engine, ShardsRefMut},
+    gf8::{CANTOR_BASIS, GF_BITS, GF_MODULUS, GF_ORDER, GF_POLYNOMIAL},
+    rate::Field,
+};
+
+// ======================================================================
+// Tables - CRATE
+
+pub(crate) struct Tables {
+    exp: [u8; GF_ORDER],
+    log: [u8; GF_ORDER],
+    log_walsh: [u8; GF_ORDER],
+    // `mul[log_m][x] == x * log_m`
+    mul: Box<[[u8; GF_ORDER]; GF_ORDER]>,
+    skew: [u8; GF_MODULUS as usize],
+}
+
+// ======================================================================
+// STATIC - PRIVATE
+
+static TABLES: OnceTable<Tables> = OnceTable::new();
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+// Initializes and returns all tables.
+pub(crate) fn tables() -> &'static Tables {
+    TABLES.get_or_init(|| {
+        let (exp, log) = initialize_exp_log();
+
+        let mut mul = Box::new([[0; GF_ORDER]; GF_ORDER]);
+        for (log_m, lut) in mul.iter_mut().enumerate() {
+            for (x, product) in lut.iter_mut().enumerate() {
+                *product = mul_log(x as u8, log_m as u8, &exp, &log);
+            }
+        }
+
+        let skew = initialize_skew(&exp, &log);
+
+        let mut log_walsh = log;
+        log_walsh[0] = 0;
+        fwht(&mut log_walsh, GF_ORDER);
+
+        Box::new(Tables {
+            exp,
+            log,
+            log_walsh,
+            mul,
+            skew,
+        })
+    })
+}
+
+#[inline(always)]
+pub(crate) fn add_mod(x: u8, y: u8) -> u8 {
+    let sum: usize = (x as usize) + (y as usize);
+    (sum + (sum >> GF_BITS)) as u8
+}
+
+#[inline(always)]
+pub(crate) fn sub_mod(x: u8, y: u8) -> u8 {
+    let dif: usize = (x as usize).wrapping_sub(y as usize);
+    dif.wrapping_add(dif >> GF_BITS) as u8
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+#[inline(always)]
+fn mul_log(x: u8, log_m: u8, exp: &[u8; GF_ORDER], log: &[u8; GF_ORDER]) -> u8 {
+    if x == 0 {
+        0
+    } else {
+        exp[add_mod(log[x as usize], log_m) as usize]
+    }
+}
+
+fn initialize_exp_log() -> ([u8; GF_ORDER], [u8; GF_ORDER]) {
+    let mut exp = [0; GF_ORDER];
+    let mut log = [0; GF_ORDER];
+
+    // GENERATE LFSR TABLE
+
+    let mut state = 1;
+    for i in 0..GF_MODULUS {
+        exp[state] = i;
+        state <<= 1;
+        if state >= GF_ORDER {
+            state ^= GF_POLYNOMIAL;
+        }
+    }
+    exp[0] = GF_MODULUS;
+
+    // CONVERT TO CANTOR BASIS
+
+    log[0] = 0;
+    for (i, basis) in CANTOR_BASIS.iter().enumerate() {
+        let width = 1usize << i;
+        for j in 0..width {
+            log[j + width] = log[j] ^ basis;
+        }
+    }
+
+    for i in 0..GF_ORDER {
+        log[i] = exp[log[i] as usize];
+    }
+
+    for i in 0..GF_ORDER {
+        exp[log[i] as usize] = i as u8;
+    }
+
+    exp[GF_MODULUS as usize] = exp[0];
+
+    (exp, log)
+}
+
+#[allow(clippy::needless_range_loop)]
+fn initialize_skew(exp: &[u8; GF_ORDER], log: &[u8; GF_ORDER]) -> [u8; GF_MODULUS as usize] {
+    let mut skew = [0; GF_MODULUS as usize];
+
+    let mut temp = [0u8; GF_BITS - 1];
+
+    for i in 1..GF_BITS {
+        temp[i - 1] = 1 << i;
+    }
+
+    for m in 0..GF_BITS - 1 {
+        let step: usize = 1 << (m + 1);
+
+        skew[(1 << m) - 1] = 0;
+
+        for i in m..GF_BITS - 1 {
+            let s: usize = 1 << (i + 1);
+            let mut j = (1 << m) - 1;
+            while j < s {
+                skew[j + s] = skew[j] ^ temp[i];
+                j += step;
+            }
+        }
+
+        temp[m] =
+            GF_MODULUS - log[mul_log(temp[m], log[(temp[m] ^ 1) as usize], exp, log) as usize];
+
+        for i in m + 1..GF_BITS - 1 {
+            let sum = add_mod(log[(temp[i] ^ 1) as usize], temp[m]);
+            temp[i] = mul_log(temp[i], sum, exp, log);
+        }
+    }
+
+    for value in skew.iter_mut() {
+        *value = log[*value as usize];
+    }
+
+    skew
+}
+
+fn fwht(data: &mut [u8; GF_ORDER], truncated_size: usize) {
+    let mut dist = 1;
+    while dist < GF_ORDER {
+        let mut r = 0;
+        while r < truncated_size {
+            for i in r..r + dist {
+                let sum = add_mod(data[i], data[i + dist]);
+                let dif = sub_mod(data[i], data[i + dist]);
+                data[i] = sum;
+                data[i + dist] = dif;
+            }
+            r += dist * 2;
+        }
+        dist *= 2;
+    }
+}
+
+// ======================================================================
+// Tables - CRATE
+
+impl Tables {
+    // Evaluate polynomial.
+    pub(crate) fn eval_poly(&self, erasures: &mut [u8; GF_ORDER], truncated_size: usize) {
+        fwht(erasures, truncated_size);
+
+        for (erasure, log_walsh) in erasures.iter_mut().zip(self.log_walsh.iter()) {
+            *erasure =
+                (((*erasure as usize) * (*log_walsh as usize)) % (GF_MODULUS as usize)) as u8;
+        }
+
+        fwht(erasures, GF_ORDER);
+    }
+
+    // In-place decimation-in-time FFT.
+    pub(crate) fn fft<E: Engine>(
+        &self,
+        work: &mut ShardsRefMut,
+        pos: usize,
+        size: usize,
+        truncated_size: usize,
+        skew_delta: usize,
+    ) {
+        debug_assert!(size.is_power_of_two());
+        debug_assert!(truncated_size <= size);
+
+        let mut dist = size / 2;
+        while dist > 0 {
+            let mut r = 0;
+            while r < truncated_size {
+                let log_m = self.skew[r + dist + skew_delta - 1];
+                for i in r..r + dist {
+                    let (a, b) = work.dist2_mut(pos + i, dist);
+                    if log_m != GF_MODULUS {
+                        self.mul_add(a, b, log_m);
+                    }
+                    E::xor(b, a);
+                }
+                r += dist * 2;
+            }
+            dist /= 2;
+        }
+    }
+
+    // In-place decimation-in-time IFFT.
+    pub(crate) fn ifft<E: Engine>(
+        &self,
+        work: &mut ShardsRefMut,
+        pos: usize,
+        size: usize,
+        truncated_size: usize,
+        skew_delta: usize,
+    ) {
+        debug_assert!(size.is_power_of_two());
+        debug_assert!(truncated_size <= size);
+
+        let mut dist = 1;
+        while dist < size {
+            let mut r = 0;
+            while r < truncated_size {
+                let log_m = self.skew[r + dist + skew_delta - 1];
+                for i in r..r + dist {
+                    let (a, b) = work.dist2_mut(pos + i, dist);
+                    E::xor(b, a);
+                    if log_m != GF_MODULUS {
+                        self.mul_add(a, b, log_m);
+                    }
+                }
+                r += dist * 2;
+            }
+            dist *= 2;
+        }
+    }
+
+    // `x[] *= log_m`
+    pub(crate) fn mul(&self, x: &mut [u8], log_m: u8) {
+        let lut = &self.mul[log_m as usize];
+        for x in x.iter_mut() {
+            *x = lut[*x as usize];
+        }
+    }
+
+    // `x[] ^= y[] * log_m`
+    fn mul_add(&self, x: &mut [u8], y: &[u8], log_m: u8) {
+        let lut = &self.mul[log_m as usize];
+        for (x, y) in x.iter_mut().zip(y.iter()) {
+            *x ^= lut[*y as usize];
+        }
+    }
+}
+
+// ======================================================================
+// Tables - IMPL Field
+
+impl Field for Tables {
+    fn div_element(&self, x: usize, y: usize) -> usize {
+        if x == 0 {
+            0
+        } else {
+            self.exp[sub_mod(self.log[x], self.log[y]) as usize] as usize
+        }
+    }
+
+    fn mul_element(&self, x: usize, y: usize) -> usize {
+        if y == 0 {
+            0
+        } else {
+            mul_log(x as u8, self.log[y], &self.exp, &self.log) as usize
+        }
+    }
+
+    fn mul_shard(&self, x: &mut [u8], y: usize) {
+        self.mul(x, self.log[y]);
+    }
+
+    fn symbol(shard: &[u8], index: usize) -> usize {
+        shard[index] as usize
+    }
+
+    fn symbol_count(shard_bytes: usize) -> usize {
+        shard_bytes
+    }
+}
@@ -0,0 +1,608 @@
+//! Fixed-capacity encoder and decoder which don't allocate.
+//!
+//! [`HeaplessEncoder`] and [`HeaplessDecoder`] produce and consume
+//! the same shards as [`crate::ReedSolomonEncoder`] and
+//! [`crate::ReedSolomonDecoder`], but work entirely in caller-provided
+//! buffers, so they are suitable for embedded targets without allocator.
+//!
+//! - Shard counts are limited by const generic capacities
+//!   `ORIGINAL_MAX` and `RECOVERY_MAX`, which also set the size
+//!   of the encoder/decoder itself.
+//! - Shards are added as references and restored into `&mut [u8]` buffers,
+//!   e.g. slices of statically allocated arrays.
+//! - Lookup-tables take 256 bytes of static data instead of
+//!   384 kiB - 8.5 MiB, and each recovery shard or restored original shard
+//!   is calculated directly as a weighted sum of the received shards.
+//!   Calculating a single shard takes `O(original_count * shard_bytes)` time,
+//!   so this is meant for small shard counts only.
+//!
+//! Decoding needs a `RECOVERY_MAX * RECOVERY_MAX` matrix of 16-bit elements
+//! on the stack in [`HeaplessDecoder::restored_original`], i.e. `RECOVERY_MAX`
+//! should be kept small on targets with small stack.
+//!
+//! This module is available without `alloc` feature, in which case
+//! this crate doesn't link `alloc` and no global allocator is needed:
+//!
+//! ```toml
+//! reed-solomon-16 = { version = "0.2", default-features = false }
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::heapless::{HeaplessDecoder, HeaplessEncoder};
+//!
+//! let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+//! let mut recovery = [[0u8; 64]; 2];
+//!
+//! // Capacity of 4 original shards and 2 recovery shards.
+//! let mut encoder = HeaplessEncoder::<4, 2>::new(3, 2, 64)?;
+//! for shard in &original {
+//!     encoder.add_original_shard(shard)?;
+//! }
+//! for (index, shard) in recovery.iter_mut().enumerate() {
+//!     encoder.recovery(index, shard)?;
+//! }
+//!
+//! // Lose original shards 0 and 2.
+//! let mut decoder = HeaplessDecoder::<4, 2>::new(3, 2, 64)?;
+//! decoder.add_original_shard(1, &original[1])?;
+//! decoder.add_recovery_shard(0, &recovery[0])?;
+//! decoder.add_recovery_shard(1, &recovery[1])?;
+//!
+//! let mut restored = [0u8; 64];
+//! decoder.restored_original(0, &mut restored)?;
+//! assert_eq!(restored, original[0]);
+//! decoder.restored_original(2, &mut restored)?;
+//! assert_eq!(restored, original[2]);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+
+use crate::{engine::GfElement, rate, Error};
+
+use field::MulTable;
+
+mod field;
+
+// ======================================================================
+// Config - PRIVATE
+
+#[derive(Clone, Copy)]
+struct Config {
+    original_count: usize,
+    recovery_count: usize,
+    shard_bytes: usize,
+    high_rate: bool,
+}
+
+impl Config {
+    fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        original_max: usize,
+        recovery_max: usize,
+    ) -> Result<Self, Error> {
+        if original_count > original_max || recovery_count > recovery_max {
+            return Err(Error::UnsupportedShardCount {
+                original_count,
+                recovery_count,
+            });
+        }
+
+        let high_rate = rate::use_high_rate(original_count, recovery_count)?;
+
+        if shard_bytes == 0 || shard_bytes & 63 != 0 {
+            return Err(Error::InvalidShardSize { shard_bytes });
+        }
+
+        Ok(Self {
+            original_count,
+            recovery_count,
+            shard_bytes,
+            high_rate,
+        })
+    }
+
+    fn check_shard_size(&self, shard: &[u8]) -> Result<(), Error> {
+        if shard.len() == self.shard_bytes {
+            Ok(())
+        } else {
+            Err(Error::DifferentShardSize {
+                shard_bytes: self.shard_bytes,
+                got: shard.len(),
+            })
+        }
+    }
+
+    // Returns weight of original shard `original_index`
+    // in recovery shard `recovery_index`.
+    fn coefficient(&self, recovery_index: usize, original_index: usize) -> GfElement {
+        let (x, y) = rate::coefficient(
+            self.high_rate,
+            self.original_count,
+            self.recovery_count,
+            original_index,
+            recovery_index,
+        );
+        field::div(x as GfElement, y as GfElement)
+    }
+}
+
+// ======================================================================
+// HeaplessEncoder - PUBLIC
+
+/// Fixed-capacity encoder which doesn't allocate.
+///
+/// Holds references to added original shards and calculates
+/// recovery shards into caller-provided buffers.
+///
+/// See [module documentation](crate::heapless) for details and an example.
+pub struct HeaplessEncoder<'a, const ORIGINAL_MAX: usize, const RECOVERY_MAX: usize> {
+    config: Config,
+    original: [&'a [u8]; ORIGINAL_MAX],
+    original_received_count: usize,
+}
+
+impl<'a, const ORIGINAL_MAX: usize, const RECOVERY_MAX: usize>
+    HeaplessEncoder<'a, ORIGINAL_MAX, RECOVERY_MAX>
+{
+    /// Adds one original shard to the encoder.
+    ///
+    /// Original shards have indexes `0..original_count` corresponding
+    /// to the order in which they are added.
+    pub fn add_original_shard(&mut self, original_shard: &'a [u8]) -> Result<(), Error> {
+        if self.original_received_count == self.config.original_count {
+            return Err(Error::TooManyOriginalShards {
+                original_count: self.config.original_count,
+            });
+        }
+
+        self.config.check_shard_size(original_shard)?;
+
+        self.original[self.original_received_count] = original_shard;
+        self.original_received_count += 1;
+        Ok(())
+    }
+
+    /// Creates new encoder with given configuration.
+    ///
+    /// Returns [`Error::UnsupportedShardCount`] if `original_count`
+    /// or `recovery_count` exceeds capacity.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            config: Config::new(
+                original_count,
+                recovery_count,
+                shard_bytes,
+                ORIGINAL_MAX,
+                RECOVERY_MAX,
+            )?,
+            original: [&[]; ORIGINAL_MAX],
+            original_received_count: 0,
+        })
+    }
+
+    /// Calculates recovery shard `index` into `recovery_shard`.
+    ///
+    /// Result is same as `recovery(index)` of [`crate::EncoderResult`]
+    /// with [`crate::ReedSolomonEncoder`] given the same original shards.
+    pub fn recovery(&self, index: usize, recovery_shard: &mut [u8]) -> Result<(), Error> {
+        let config = &self.config;
+
+        if self.original_received_count < config.original_count {
+            return Err(Error::TooFewOriginalShards {
+                original_count: config.original_count,
+                original_received_count: self.original_received_count,
+            });
+        }
+
+        if index >= config.recovery_count {
+            return Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: config.recovery_count,
+                index,
+            });
+        }
+
+        config.check_shard_size(recovery_shard)?;
+
+        recovery_shard.fill(0);
+        for (j, original_shard) in self.original[..config.original_count].iter().enumerate() {
+            MulTable::new(config.coefficient(index, j)).mul_add(recovery_shard, original_shard);
+        }
+
+        Ok(())
+    }
+
+    /// Resets encoder to given configuration.
+    ///
+    /// - Added original shards are forgotten.
+    pub fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        *self = Self::new(original_count, recovery_count, shard_bytes)?;
+        Ok(())
+    }
+}
+
+// ======================================================================
+// HeaplessDecoder - PUBLIC
+
+/// Fixed-capacity decoder which doesn't allocate.
+///
+/// Holds references to added shards and restores
+/// original shards into caller-provided buffers.
+///
+/// See [module documentation](crate::heapless) for details and an example.
+pub struct HeaplessDecoder<'a, const ORIGINAL_MAX: usize, const RECOVERY_MAX: usize> {
+    config: Config,
+    original: [Option<&'a [u8]>; ORIGINAL_MAX],
+    original_received_count: usize,
+    recovery: [Option<&'a [u8]>; RECOVERY_MAX],
+    recovery_received_count: usize,
+}
+
+impl<'a, const ORIGINAL_MAX: usize, const RECOVERY_MAX: usize>
+    HeaplessDecoder<'a, ORIGINAL_MAX, RECOVERY_MAX>
+{
+    /// Adds original shard with given `index`.
+    pub fn add_original_shard(
+        &mut self,
+        index: usize,
+        original_shard: &'a [u8],
+    ) -> Result<(), Error> {
+        if index >= self.config.original_count {
+            return Err(Error::InvalidOriginalShardIndex {
+                original_count: self.config.original_count,
+                index,
+            });
+        }
+
+        if self.original[index].is_some() {
+            return Err(Error::DuplicateOriginalShardIndex { index });
+        }
+
+        self.config.check_shard_size(original_shard)?;
+
+        self.original[index] = Some(original_shard);
+        self.original_received_count += 1;
+        Ok(())
+    }
+
+    /// Adds recovery shard with given `index`.
+    pub fn add_recovery_shard(
+        &mut self,
+        index: usize,
+        recovery_shard: &'a [u8],
+    ) -> Result<(), Error> {
+        if index >= self.config.recovery_count {
+            return Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: self.config.recovery_count,
+                index,
+            });
+        }
+
+        if self.recovery[index].is_some() {
+            return Err(Error::DuplicateRecoveryShardIndex { index });
+        }
+
+        self.config.check_shard_size(recovery_shard)?;
+
+        self.recovery[index] = Some(recovery_shard);
+        self.recovery_received_count += 1;
+        Ok(())
+    }
+
+    /// Returns `true` if enough shards have been added
+    /// to restore all original shards.
+    pub fn can_decode(&self) -> bool {
+        self.original_received_count + self.recovery_received_count >= self.config.original_count
+    }
+
+    /// Creates new decoder with given configuration.
+    ///
+    /// Returns [`Error::UnsupportedShardCount`] if `original_count`
+    /// or `recovery_count` exceeds capacity.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            config: Config::new(
+                original_count,
+                recovery_count,
+                shard_bytes,
+                ORIGINAL_MAX,
+                RECOVERY_MAX,
+            )?,
+            original: [None; ORIGINAL_MAX],
+            original_received_count: 0,
+            recovery: [None; RECOVERY_MAX],
+            recovery_received_count: 0,
+        })
+    }
+
+    /// Resets decoder to given configuration.
+    ///
+    /// - Added shards are forgotten.
+    pub fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        *self = Self::new(original_count, recovery_count, shard_bytes)?;
+        Ok(())
+    }
+
+    /// Restores original shard `index` into `original_shard`.
+    ///
+    /// - If original shard `index` was added, it is just copied.
+    /// - Otherwise it is calculated as a weighted sum of added original
+    ///   shards and as many added recovery shards as there are missing
+    ///   original shards. Weights are solved with Gaussian elimination
+    ///   which takes `O(missing^3)` time, where `missing` is the number
+    ///   of missing original shards.
+    pub fn restored_original(&self, index: usize, original_shard: &mut [u8]) -> Result<(), Error> {
+        let config = &self.config;
+
+        if index >= config.original_count {
+            return Err(Error::InvalidOriginalShardIndex {
+                original_count: config.original_count,
+                index,
+            });
+        }
+
+        config.check_shard_size(original_shard)?;
+
+        if let Some(shard) = self.original[index] {
+            original_shard.copy_from_slice(shard);
+            return Ok(());
+        }
+
+        if !self.can_decode() {
+            return Err(Error::NotEnoughShards {
+                original_count: config.original_count,
+                original_received_count: self.original_received_count,
+                recovery_received_count: self.recovery_received_count,
+            });
+        }
+
+        // Missing original shards and the recovery shards used instead.
+        // There are fewer missing original shards than received
+        // recovery shards, so both fit in `RECOVERY_MAX`.
+
+        let missing_count = config.original_count - self.original_received_count;
+
+        let mut missing = [0; RECOVERY_MAX];
+        let mut target = 0;
+        for (i, j) in (0..config.original_count)
+            .filter(|j| self.original[*j].is_none())
+            .enumerate()
+        {
+            missing[i] = j;
+            if j == index {
+                target = i;
+            }
+        }
+
+        let mut used = [0; RECOVERY_MAX];
+        for (i, r) in (0..config.recovery_count)
+            .filter(|r| self.recovery[*r].is_some())
+            .take(missing_count)
+            .enumerate()
+        {
+            used[i] = r;
+        }
+
+        let missing = &missing[..missing_count];
+        let used = &used[..missing_count];
+
+        // With `A[r][m] = coefficient(used[r], missing[m])`,
+        // missing original shards are `A^-1 * (recovery - known)`.
+        // Row `target` of `A^-1` is solved from `A^T * x = e(target)`.
+
+        let mut matrix = [[0; RECOVERY_MAX]; RECOVERY_MAX];
+        let mut x = [0; RECOVERY_MAX];
+        for (m, row) in matrix[..missing_count].iter_mut().enumerate() {
+            for (r, value) in row[..missing_count].iter_mut().enumerate() {
+                *value = config.coefficient(used[r], missing[m]);
+            }
+        }
+        x[target] = 1;
+
+        solve(&mut matrix[..missing_count], &mut x[..missing_count]);
+        let x = &x[..missing_count];
+
+        // WEIGHTED SUM
+
+        original_shard.fill(0);
+
+        for (r, weight) in used.iter().zip(x.iter()) {
+            let recovery_shard = self.recovery[*r].unwrap();
+            MulTable::new(*weight).mul_add(original_shard, recovery_shard);
+        }
+
+        for (j, shard) in self.original[..config.original_count].iter().enumerate() {
+            if let Some(shard) = shard {
+                let mut weight = 0;
+                for (r, x) in used.iter().zip(x.iter()) {
+                    weight ^= field::mul(*x, config.coefficient(*r, j));
+                }
+                MulTable::new(weight).mul_add(original_shard, shard);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Solves `matrix * x = b` in-place with Gauss-Jordan elimination,
+// replacing `b` with `x`. Only first `b.len()` columns of `matrix` are used.
+//
+// Any square submatrix of coefficients is invertible because the code is MDS.
+fn solve<const N: usize>(matrix: &mut [[GfElement; N]], b: &mut [GfElement]) {
+    let n = b.len();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|row| matrix[*row][col] != 0)
+            .expect("coefficient matrix is invertible");
+        matrix.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let scale = field::div(1, matrix[col][col]);
+        for value in matrix[col][col..n].iter_mut() {
+            *value = field::mul(*value, scale);
+        }
+        b[col] = field::mul(b[col], scale);
+
+        let pivot_row = matrix[col];
+        for row in 0..n {
+            let factor = matrix[row][col];
+            if row != col && factor != 0 {
+                for (value, pivot) in matrix[row][col..n].iter_mut().zip(&pivot_row[col..n]) {
+                    *value ^= field::mul(factor, *pivot);
+                }
+                b[row] ^= field::mul(factor, b[col]);
+            }
+        }
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn roundtrip(original_count: usize, recovery_count: usize, lost: &[usize]) {
+        let original = test_util::generate_original(original_count, 128, 0);
+        let expected = crate::encode(original_count, recovery_count, &original).unwrap();
+
+        let mut encoder =
+            HeaplessEncoder::<8, 8>::new(original_count, recovery_count, 128).unwrap();
+        for shard in &original {
+            encoder.add_original_shard(shard).unwrap();
+        }
+
+        let mut recovery = [[0u8; 128]; 8];
+        for (index, shard) in recovery[..recovery_count].iter_mut().enumerate() {
+            encoder.recovery(index, shard).unwrap();
+            assert_eq!(shard[..], expected[index][..]);
+        }
+
+        // Use the last recovery shards to exercise
+        // non-trivial choices of recovery indexes.
+        let mut decoder =
+            HeaplessDecoder::<8, 8>::new(original_count, recovery_count, 128).unwrap();
+        for (index, shard) in original.iter().enumerate() {
+            if !lost.contains(&index) {
+                decoder.add_original_shard(index, shard).unwrap();
+            }
+        }
+        let first = recovery_count - lost.len();
+        for (index, shard) in recovery[..recovery_count].iter().enumerate().skip(first) {
+            decoder.add_recovery_shard(index, shard).unwrap();
+        }
+
+        let mut restored = [0u8; 128];
+        for (index, shard) in original.iter().enumerate() {
+            decoder.restored_original(index, &mut restored).unwrap();
+            assert_eq!(restored[..], shard[..]);
+        }
+    }
+
+    #[test]
+    fn roundtrip_high_rate() {
+        roundtrip(3, 3, &[0, 2]);
+        roundtrip(7, 2, &[1, 6]);
+        roundtrip(8, 3, &[0, 4, 7]);
+    }
+
+    #[test]
+    fn roundtrip_low_rate() {
+        roundtrip(3, 5, &[0, 1, 2]);
+        roundtrip(2, 8, &[1]);
+        roundtrip(1, 6, &[0]);
+    }
+
+    #[test]
+    fn capacity() {
+        assert!(matches!(
+            HeaplessEncoder::<2, 2>::new(3, 2, 64),
+            Err(Error::UnsupportedShardCount { .. })
+        ));
+        assert!(matches!(
+            HeaplessDecoder::<2, 2>::new(2, 3, 64),
+            Err(Error::UnsupportedShardCount { .. })
+        ));
+        assert!(matches!(
+            HeaplessEncoder::<2, 2>::new(2, 2, 32),
+            Err(Error::InvalidShardSize { shard_bytes: 32 })
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let shard = [0u8; 64];
+        let mut out = [0u8; 64];
+
+        let mut encoder = HeaplessEncoder::<2, 2>::new(2, 1, 64).unwrap();
+        assert_eq!(
+            encoder.recovery(0, &mut out),
+            Err(Error::TooFewOriginalShards {
+                original_count: 2,
+                original_received_count: 0,
+            })
+        );
+        assert_eq!(
+            encoder.add_original_shard(&shard[..32]),
+            Err(Error::DifferentShardSize {
+                shard_bytes: 64,
+                got: 32
+            })
+        );
+        encoder.add_original_shard(&shard).unwrap();
+        encoder.add_original_shard(&shard).unwrap();
+        assert_eq!(
+            encoder.add_original_shard(&shard),
+            Err(Error::TooManyOriginalShards { original_count: 2 })
+        );
+        assert_eq!(
+            encoder.recovery(1, &mut out),
+            Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: 1,
+                index: 1
+            })
+        );
+
+        let mut decoder = HeaplessDecoder::<2, 2>::new(2, 1, 64).unwrap();
+        decoder.add_original_shard(0, &shard).unwrap();
+        assert_eq!(
+            decoder.add_original_shard(0, &shard),
+            Err(Error::DuplicateOriginalShardIndex { index: 0 })
+        );
+        assert_eq!(
+            decoder.restored_original(1, &mut out),
+            Err(Error::NotEnoughShards {
+                original_count: 2,
+                original_received_count: 1,
+                recovery_received_count: 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Table-light `GF(2^16)` arithmetic in Cantor basis.
+//!
+//! Multiplication converts both factors to polynomial basis,
+//! does carry-less multiplication modulo [`GF_POLYNOMIAL`]
+//! and converts the product back. Basis conversions are linear,
+//! so they are done with four 16-entry tables, one per nibble.
+//! Together the conversion tables take 256 bytes.
+//!
+//! Multiplying shards by a constant uses [`MulTable`] which is likewise
+//! built from four 16-entry tables and takes 128 bytes of stack.
+
+use crate::engine::{GfElement, CANTOR_BASIS, GF_BITS, GF_POLYNOMIAL};
+
+// ======================================================================
+// STATIC - PRIVATE
+
+// `TO_POLY[n][x]` is nibble `n` with value `x` converted to polynomial basis.
+static TO_POLY: [[GfElement; 16]; 4] = nibble_tables(CANTOR_BASIS);
+
+// `FROM_POLY[n][x]` is nibble `n` with value `x` converted to Cantor basis.
+static FROM_POLY: [[GfElement; 16]; 4] = nibble_tables(cantor_basis_inverse());
+
+// ======================================================================
+// MulTable - CRATE
+
+/// Lookup-table for multiplying by a constant.
+pub(crate) struct MulTable([[GfElement; 16]; 4]);
+
+impl MulTable {
+    /// Creates table for multiplying by `m`.
+    pub(crate) fn new(m: GfElement) -> Self {
+        let mut table = [[0; 16]; 4];
+        for (nibble, lut) in table.iter_mut().enumerate() {
+            for (x, product) in lut.iter_mut().enumerate() {
+                *product = mul(m, (x as GfElement) << (4 * nibble));
+            }
+        }
+        MulTable(table)
+    }
+
+    /// Returns `x * m`.
+    #[inline(always)]
+    pub(crate) fn mul(&self, x: GfElement) -> GfElement {
+        self.0[0][(x & 15) as usize]
+            ^ self.0[1][((x >> 4) & 15) as usize]
+            ^ self.0[2][((x >> 8) & 15) as usize]
+            ^ self.0[3][(x >> 12) as usize]
+    }
+
+    /// `x[] ^= y[] * m` for shards stored as 64-byte blocks
+    /// of 32 low bytes followed by 32 high bytes.
+    pub(crate) fn mul_add(&self, x: &mut [u8], y: &[u8]) {
+        debug_assert_eq!(x.len(), y.len());
+        for (x, y) in x.chunks_exact_mut(64).zip(y.chunks_exact(64)) {
+            let (x_lo, x_hi) = x.split_at_mut(32);
+            let (y_lo, y_hi) = y.split_at(32);
+            for i in 0..32 {
+                let product = self.mul(GfElement::from(y_lo[i]) | (GfElement::from(y_hi[i]) << 8));
+                x_lo[i] ^= product as u8;
+                x_hi[i] ^= (product >> 8) as u8;
+            }
+        }
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+/// Returns `x * y`.
+pub(crate) fn mul(x: GfElement, y: GfElement) -> GfElement {
+    from_poly(mul_poly(to_poly(x), to_poly(y)))
+}
+
+/// Returns `x / y`, `y` must be non-zero.
+pub(crate) fn div(x: GfElement, y: GfElement) -> GfElement {
+    debug_assert_ne!(y, 0);
+
+    // y^-1 = y^(2^16 - 2)
+    let y = to_poly(y);
+    let mut inverse = 1;
+    let mut square = y;
+    for _ in 1..GF_BITS {
+        square = mul_poly(square, square);
+        inverse = mul_poly(inverse, square);
+    }
+
+    from_poly(mul_poly(to_poly(x), inverse))
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn to_poly(x: GfElement) -> GfElement {
+    convert(&TO_POLY, x)
+}
+
+fn from_poly(x: GfElement) -> GfElement {
+    convert(&FROM_POLY, x)
+}
+
+#[inline(always)]
+fn convert(table: &[[GfElement; 16]; 4], x: GfElement) -> GfElement {
+    table[0][(x & 15) as usize]
+        ^ table[1][((x >> 4) & 15) as usize]
+        ^ table[2][((x >> 8) & 15) as usize]
+        ^ table[3][(x >> 12) as usize]
+}
+
+// Carry-less multiplication modulo `GF_POLYNOMIAL` in polynomial basis.
+fn mul_poly(x: GfElement, y: GfElement) -> GfElement {
+    let mut product: u32 = 0;
+    for bit in 0..GF_BITS {
+        if y & (1 << bit) != 0 {
+            product ^= u32::from(x) << bit;
+        }
+    }
+
+    for bit in (GF_BITS..2 * GF_BITS - 1).rev() {
+        if product & (1 << bit) != 0 {
+            product ^= (GF_POLYNOMIAL as u32) << (bit - GF_BITS);
+        }
+    }
+
+    product as GfElement
+}
+
+// Returns `table` where `table[n][x]` is XOR of `basis[4 * n + b]`
+// for each set bit `b` of `x`.
+const fn nibble_tables(basis: [GfElement; GF_BITS]) -> [[GfElement; 16]; 4] {
+    let mut table = [[0; 16]; 4];
+    let mut nibble = 0;
+    while nibble < 4 {
+        let mut x = 0;
+        while x < 16 {
+            let mut bit = 0;
+            while bit < 4 {
+                if x & (1 << bit) != 0 {
+                    table[nibble][x] ^= basis[4 * nibble + bit];
+                }
+                bit += 1;
+            }
+            x += 1;
+        }
+        nibble += 1;
+    }
+    table
+}
+
+// Returns Cantor basis representation of each polynomial basis vector,
+// using Gauss-Jordan elimination over `GF(2)`.
+const fn cantor_basis_inverse() -> [GfElement; GF_BITS] {
+    // `poly[i]` in polynomial basis equals `cantor[i]` in Cantor basis.
+    let mut poly = CANTOR_BASIS;
+    let mut cantor = [0; GF_BITS];
+    let mut i = 0;
+    while i < GF_BITS {
+        cantor[i] = 1 << i;
+        i += 1;
+    }
+
+    let mut bit = 0;
+    while bit < GF_BITS {
+        let mut pivot = bit;
+        while poly[pivot] & (1 << bit) == 0 {
+            pivot += 1;
+        }
+
+        let (p, c) = (poly[pivot], cantor[pivot]);
+        poly[pivot] = poly[bit];
+        cantor[pivot] = cantor[bit];
+        poly[bit] = p;
+        cantor[bit] = c;
+
+        let mut row = 0;
+        while row < GF_BITS {
+            if row != bit && poly[row] & (1 << bit) != 0 {
+                poly[row] ^= p;
+                cantor[row] ^= c;
+            }
+            row += 1;
+        }
+
+        bit += 1;
+    }
+
+    cantor
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Gf16;
+
+    #[test]
+    fn basis_roundtrip() {
+        for x in 0..=GfElement::MAX {
+            assert_eq!(from_poly(to_poly(x)), x);
+        }
+    }
+
+    #[test]
+    fn mul_div_match_gf16() {
+        for (x, y) in [
+            (0, 5),
+            (1, 1),
+            (2, 3),
+            (0x1234, 0xABCD),
+            (0xFFFF, 0xFFFF),
+            (77, 40000),
+        ] {
+            assert_eq!(mul(x, y), (Gf16(x) * Gf16(y)).0);
+            if y != 0 {
+                assert_eq!(div(x, y), (Gf16(x) / Gf16(y)).0);
+            }
+        }
+
+        for x in (1..=GfElement::MAX).step_by(251) {
+            assert_eq!(mul(x, div(1, x)), 1);
+        }
+    }
+
+    #[test]
+    fn mul_table() {
+        let table = MulTable::new(0x5A5A);
+        for x in (0..=GfElement::MAX).step_by(97) {
+            assert_eq!(table.mul(x), mul(x, 0x5A5A));
+        }
+    }
+}
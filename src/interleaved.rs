@@ -0,0 +1,545 @@
+//! Encoding/decoding of more than 65536 shards in total
+//! by interleaving several Reed-Solomon codewords.
+//!
+//! A single Reed-Solomon codeword over `GF(2^16)` is limited to
+//! at most 65536 shards in total (see [algorithm > Rate]).
+//! [`InterleavedEncoder`] and [`InterleavedDecoder`] lift this limit
+//! by splitting shards into several independent codewords:
+//!
+//! - Number of codewords is the smallest number for which every codeword
+//!   is supported by [`ReedSolomonEncoder`], see [`codeword_count`].
+//! - Original shard `index` belongs to codeword `index % codeword_count`,
+//!   and so does recovery shard `index`.
+//! - Shards are given and returned with their global indexes,
+//!   so the partitioning is invisible to the user.
+//!
+//! # Loss tolerance
+//!
+//! Each codeword is decodable independently of other codewords,
+//! so shard loss is tolerated per codeword:
+//! Codeword can be decoded if at most as many of its shards are lost
+//! as it has recovery shards.
+//!
+//! Any `recovery_count / codeword_count` lost shards are always tolerated.
+//! More shards can be lost if losses are spread evenly among codewords.
+//!
+//! With a single codeword recovery shards are same as those of
+//! [`ReedSolomonEncoder`] and [`ReedSolomonDecoder`],
+//! but with multiple codewords they are **not** compatible.
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::interleaved::{self, InterleavedDecoder, InterleavedEncoder};
+//!
+//! // 70000 shards in total don't fit into a single codeword.
+//! assert_eq!(interleaved::codeword_count(40000, 30000)?, 2);
+//!
+//! let mut encoder = InterleavedEncoder::new(40000, 30000, 64)?;
+//! for index in 0..40000u32 {
+//!     let mut shard = [0u8; 64];
+//!     shard[..4].copy_from_slice(&index.to_le_bytes());
+//!     encoder.add_original_shard(shard)?;
+//! }
+//! let result = encoder.encode()?;
+//!
+//! // Lose original shards 0 and 1, which belong to different codewords.
+//! let mut decoder = InterleavedDecoder::new(40000, 30000, 64)?;
+//! for index in 2..40000u32 {
+//!     let mut shard = [0u8; 64];
+//!     shard[..4].copy_from_slice(&index.to_le_bytes());
+//!     decoder.add_original_shard(index as usize, shard)?;
+//! }
+//! decoder.add_recovery_shard(0, result.recovery(0).unwrap())?;
+//! decoder.add_recovery_shard(1, result.recovery(1).unwrap())?;
+//!
+//! let restored = decoder.decode()?;
+//! assert_eq!(restored.restored_original(1).unwrap()[..4], 1u32.to_le_bytes());
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [algorithm > Rate]: crate::algorithm#rate
+//! [`ReedSolomonDecoder`]: crate::ReedSolomonDecoder
+//! [`ReedSolomonEncoder`]: crate::ReedSolomonEncoder
+
+use alloc::vec::Vec;
+
+use crate::{DecoderResult, EncoderResult, Error, ReedSolomonDecoder, ReedSolomonEncoder};
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Returns number of codewords used with given shard counts.
+///
+/// This is the smallest number of codewords for which every codeword
+/// is supported by [`ReedSolomonEncoder`].
+///
+/// Returns [`Error::UnsupportedShardCount`] if there is no such number,
+/// i.e. if either count is zero or if there are so few original or recovery
+/// shards that some codeword would be left without them.
+///
+/// [`ReedSolomonEncoder`]: crate::ReedSolomonEncoder
+pub fn codeword_count(original_count: usize, recovery_count: usize) -> Result<usize, Error> {
+    // Codeword sizes only decrease when `codeword_count` increases,
+    // so supported counts form a range which is found with binary search.
+
+    let mut low = 1;
+    let mut high = core::cmp::min(original_count, recovery_count);
+
+    if high == 0 || !supports_codeword_count(original_count, recovery_count, high) {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if supports_codeword_count(original_count, recovery_count, mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Ok(low)
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Returns number of shards in codeword `codeword`
+// when `count` shards are split into `codeword_count` codewords.
+fn codeword_shard_count(count: usize, codeword_count: usize, codeword: usize) -> usize {
+    count / codeword_count + usize::from(codeword < count % codeword_count)
+}
+
+fn supports_codeword_count(
+    original_count: usize,
+    recovery_count: usize,
+    codeword_count: usize,
+) -> bool {
+    // Largest codeword has most original and most recovery shards.
+    ReedSolomonEncoder::supports(
+        original_count.div_ceil(codeword_count),
+        recovery_count.div_ceil(codeword_count),
+    )
+}
+
+// ======================================================================
+// InterleavedEncoder - PUBLIC
+
+/// Reed-Solomon encoder which interleaves several codewords.
+///
+/// See [module documentation](crate::interleaved) for details.
+pub struct InterleavedEncoder {
+    encoders: Vec<ReedSolomonEncoder>,
+    original_count: usize,
+    original_received_count: usize,
+    recovery_count: usize,
+}
+
+impl InterleavedEncoder {
+    /// Adds one original shard to the encoder.
+    ///
+    /// Original shards have indexes `0..original_count` corresponding to the order
+    /// in which they are added and these same indexes must be used when decoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error> {
+        if self.original_received_count == self.original_count {
+            return Err(Error::TooManyOriginalShards {
+                original_count: self.original_count,
+            });
+        }
+
+        let codeword = self.original_received_count % self.encoders.len();
+        self.encoders[codeword].add_original_shard(original_shard)?;
+        self.original_received_count += 1;
+        Ok(())
+    }
+
+    /// Returns number of codewords used by this encoder.
+    pub fn codeword_count(&self) -> usize {
+        self.encoders.len()
+    }
+
+    /// Encodes the added original shards returning [`InterleavedEncoderResult`]
+    /// which contains the generated recovery shards.
+    ///
+    /// When returned [`InterleavedEncoderResult`] is dropped the encoder is
+    /// automatically reset and ready for new round of encoding.
+    pub fn encode(&mut self) -> Result<InterleavedEncoderResult<'_>, Error> {
+        if self.original_received_count < self.original_count {
+            return Err(Error::TooFewOriginalShards {
+                original_count: self.original_count,
+                original_received_count: self.original_received_count,
+            });
+        }
+
+        self.original_received_count = 0;
+
+        let results = self
+            .encoders
+            .iter_mut()
+            .map(|encoder| encoder.encode())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(InterleavedEncoderResult {
+            recovery_count: self.recovery_count,
+            results,
+        })
+    }
+
+    /// Creates new encoder with given configuration
+    /// and allocates required working space.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        let codeword_count = codeword_count(original_count, recovery_count)?;
+
+        let encoders = (0..codeword_count)
+            .map(|codeword| {
+                ReedSolomonEncoder::new(
+                    codeword_shard_count(original_count, codeword_count, codeword),
+                    codeword_shard_count(recovery_count, codeword_count, codeword),
+                    shard_bytes,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            encoders,
+            original_count,
+            original_received_count: 0,
+            recovery_count,
+        })
+    }
+}
+
+// ======================================================================
+// InterleavedEncoderResult - PUBLIC
+
+/// Result of interleaved encoding. Contains the generated recovery shards.
+///
+/// This struct is created by [`InterleavedEncoder::encode`].
+pub struct InterleavedEncoderResult<'a> {
+    recovery_count: usize,
+    results: Vec<EncoderResult<'a>>,
+}
+
+impl<'a> InterleavedEncoderResult<'a> {
+    /// Returns recovery shard with given `index`
+    /// or `None` if `index >= recovery_count`.
+    ///
+    /// Recovery shards have indexes `0..recovery_count`
+    /// and these same indexes must be used when decoding.
+    pub fn recovery(&self, index: usize) -> Option<&[u8]> {
+        if index < self.recovery_count {
+            let codeword_count = self.results.len();
+            self.results[index % codeword_count].recovery(index / codeword_count)
+        } else {
+            None
+        }
+    }
+}
+
+// ======================================================================
+// InterleavedDecoder - PUBLIC
+
+/// Reed-Solomon decoder which interleaves several codewords.
+///
+/// See [module documentation](crate::interleaved) for details.
+pub struct InterleavedDecoder {
+    decoders: Vec<ReedSolomonDecoder>,
+    original_count: usize,
+    recovery_count: usize,
+}
+
+impl InterleavedDecoder {
+    /// Adds one original shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+    ) -> Result<(), Error> {
+        if index >= self.original_count {
+            return Err(Error::InvalidOriginalShardIndex {
+                original_count: self.original_count,
+                index,
+            });
+        }
+
+        let codeword_count = self.decoders.len();
+        self.decoders[index % codeword_count]
+            .add_original_shard(index / codeword_count, original_shard)
+            .map_err(|error| match error {
+                Error::DuplicateOriginalShardIndex { .. } => {
+                    Error::DuplicateOriginalShardIndex { index }
+                }
+                _ => error,
+            })
+    }
+
+    /// Adds one recovery shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_recovery_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+    ) -> Result<(), Error> {
+        if index >= self.recovery_count {
+            return Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: self.recovery_count,
+                index,
+            });
+        }
+
+        let codeword_count = self.decoders.len();
+        self.decoders[index % codeword_count]
+            .add_recovery_shard(index / codeword_count, recovery_shard)
+            .map_err(|error| match error {
+                Error::DuplicateRecoveryShardIndex { .. } => {
+                    Error::DuplicateRecoveryShardIndex { index }
+                }
+                _ => error,
+            })
+    }
+
+    /// Returns `true` if enough shards have been added for decoding,
+    /// i.e. if every codeword has enough shards.
+    pub fn can_decode(&self) -> bool {
+        self.decoders.iter().all(|decoder| decoder.can_decode())
+    }
+
+    /// Returns number of codewords used by this decoder.
+    pub fn codeword_count(&self) -> usize {
+        self.decoders.len()
+    }
+
+    /// Decodes the added shards returning [`InterleavedDecoderResult`]
+    /// which contains the restored original shards.
+    ///
+    /// When returned [`InterleavedDecoderResult`] is dropped the decoder is
+    /// automatically reset and ready for new round of decoding.
+    ///
+    /// Returns [`Error::NotEnoughShards`] if some codeword doesn't have
+    /// enough shards, in which case counts in the error are those of
+    /// the first such codeword. Added shards are kept in that case.
+    pub fn decode(&mut self) -> Result<InterleavedDecoderResult<'_>, Error> {
+        // Check all codewords first so that a failure
+        // doesn't reset some of the decoders.
+        if let Some(decoder) = self.decoders.iter().find(|decoder| !decoder.can_decode()) {
+            return Err(Error::NotEnoughShards {
+                original_count: decoder.original_count(),
+                original_received_count: decoder.original_received_count(),
+                recovery_received_count: decoder.recovery_received_count(),
+            });
+        }
+
+        let results = self
+            .decoders
+            .iter_mut()
+            .map(|decoder| decoder.decode())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(InterleavedDecoderResult {
+            original_count: self.original_count,
+            results,
+        })
+    }
+
+    /// Creates new decoder with given configuration
+    /// and allocates required working space.
+    pub fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        let codeword_count = codeword_count(original_count, recovery_count)?;
+
+        let decoders = (0..codeword_count)
+            .map(|codeword| {
+                ReedSolomonDecoder::new(
+                    codeword_shard_count(original_count, codeword_count, codeword),
+                    codeword_shard_count(recovery_count, codeword_count, codeword),
+                    shard_bytes,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            decoders,
+            original_count,
+            recovery_count,
+        })
+    }
+}
+
+// ======================================================================
+// InterleavedDecoderResult - PUBLIC
+
+/// Result of interleaved decoding. Contains the restored original shards.
+///
+/// This struct is created by [`InterleavedDecoder::decode`].
+pub struct InterleavedDecoderResult<'a> {
+    original_count: usize,
+    results: Vec<DecoderResult<'a>>,
+}
+
+impl<'a> InterleavedDecoderResult<'a> {
+    /// Returns restored original shard with given `index`
+    /// or `None` if given `index` doesn't correspond to
+    /// a missing original shard.
+    pub fn restored_original(&self, index: usize) -> Option<&[u8]> {
+        if index < self.original_count {
+            let codeword_count = self.results.len();
+            self.results[index % codeword_count].restored_original(index / codeword_count)
+        } else {
+            None
+        }
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    // ============================================================
+    // HELPERS
+
+    fn roundtrip(
+        original_count: usize,
+        recovery_count: usize,
+        original_lost: &[usize],
+        recovery_used: &[usize],
+    ) {
+        let original = test_util::generate_original(original_count, 64, 0);
+
+        let mut encoder = InterleavedEncoder::new(original_count, recovery_count, 64).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let encoded = encoder.encode().unwrap();
+
+        let mut decoder = InterleavedDecoder::new(original_count, recovery_count, 64).unwrap();
+        for (index, original) in original.iter().enumerate() {
+            if !original_lost.contains(&index) {
+                decoder.add_original_shard(index, original).unwrap();
+            }
+        }
+        for &index in recovery_used {
+            decoder
+                .add_recovery_shard(index, encoded.recovery(index).unwrap())
+                .unwrap();
+        }
+
+        let result = decoder.decode().unwrap();
+        for &index in original_lost {
+            assert_eq!(result.restored_original(index).unwrap(), original[index]);
+        }
+    }
+
+    // ============================================================
+    // codeword_count
+
+    #[test]
+    fn codeword_count() {
+        fn err(original_count: usize, recovery_count: usize) -> Result<usize, Error> {
+            Err(Error::UnsupportedShardCount {
+                original_count,
+                recovery_count,
+            })
+        }
+
+        for (original_count, recovery_count, expected) in [
+            (0, 1, err(0, 1)),
+            (1, 0, err(1, 0)),
+            (3, 5, Ok(1)),
+            (32768, 32768, Ok(1)),
+            (32769, 32768, Ok(2)),
+            (61440, 4096, Ok(1)),
+            (61441, 4096, Ok(2)),
+            (40000, 30000, Ok(2)),
+            (100_000, 1, err(100_000, 1)),
+            (100_000, 2, Ok(2)),
+            (1_000_000, 100_000, Ok(18)),
+        ] {
+            assert_eq!(
+                super::codeword_count(original_count, recovery_count),
+                expected
+            );
+        }
+    }
+
+    // ============================================================
+    // ROUNDTRIPS
+
+    #[test]
+    fn roundtrip_single_codeword() {
+        roundtrip(3, 5, &[0, 2], &[1, 4]);
+    }
+
+    #[test]
+    fn roundtrip_two_codewords() {
+        // Each codeword has 2 recovery shards so it can lose 2 shards.
+        roundtrip(65534, 4, &[0, 1, 2, 3], &[0, 1, 2, 3]);
+    }
+
+    // ============================================================
+    // ERRORS
+
+    #[test]
+    fn errors() {
+        let mut decoder = InterleavedDecoder::new(65534, 4, 64).unwrap();
+        assert_eq!(decoder.codeword_count(), 2);
+
+        assert_eq!(
+            decoder.add_original_shard(65534, [0; 64]),
+            Err(Error::InvalidOriginalShardIndex {
+                original_count: 65534,
+                index: 65534,
+            })
+        );
+
+        decoder.add_original_shard(5, [0; 64]).unwrap();
+        assert_eq!(
+            decoder.add_original_shard(5, [0; 64]),
+            Err(Error::DuplicateOriginalShardIndex { index: 5 })
+        );
+
+        decoder.add_recovery_shard(3, [0; 64]).unwrap();
+        assert_eq!(
+            decoder.add_recovery_shard(3, [0; 64]),
+            Err(Error::DuplicateRecoveryShardIndex { index: 3 })
+        );
+
+        assert!(!decoder.can_decode());
+        assert_eq!(
+            decoder.decode().err(),
+            Some(Error::NotEnoughShards {
+                original_count: 32767,
+                original_received_count: 0,
+                recovery_received_count: 0,
+            })
+        );
+
+        let mut encoder = InterleavedEncoder::new(3, 5, 64).unwrap();
+        for _ in 0..3 {
+            encoder.add_original_shard([0; 64]).unwrap();
+        }
+        assert_eq!(
+            encoder.add_original_shard([0; 64]),
+            Err(Error::TooManyOriginalShards { original_count: 3 })
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Encoding/decoding compatible with [Leopard-RS] `GF(2^16)` codec.
+//!
+//! **This is an advanced module which is not needed for [simple usage] or [basic usage].**
+//!
+//! This crate is a port of [Leopard-RS] and uses the same field,
+//! [`CANTOR_BASIS`], shard layout and FFT, so shards are interchangeable
+//! with `leo_encode` / `leo_decode` whenever Leopard uses its `GF(2^16)` codec.
+//! This module pins down what that requires:
+//!
+//! - **Rate**
+//!     - `leo_encode` always uses high rate, i.e. chunks of
+//!       `recovery_count.next_power_of_two()` original shards
+//!       which are combined into single chunk of recovery shards.
+//!     - [`DefaultRate`] may use low rate, so [`encode`] and [`decode`]
+//!       of this module always use [`HighRate`] instead.
+//! - **Shard layout**
+//!     - `buffer_bytes` must be a multiple of 64 and each 64-byte block
+//!       contains low bytes of 32 elements followed by their high bytes,
+//!       as described in [algorithm > Shard](crate::algorithm#shard).
+//! - **Shard indexes**
+//!     - Recovery shard `i` is `work_data[i]` of `leo_encode`
+//!       and `recovery_data[i]` of `leo_decode`.
+//!     - Original shard `i` is `original_data[i]` in both.
+//! - **Field**
+//!     - Leopard uses its `GF(2^8)` codec instead when
+//!       `(recovery_count.next_power_of_two() + original_count).next_power_of_two() <= 256`
+//!       and it was compiled with `GF(2^8)` support, which is the default.
+//!       Those shard counts are not supported here, see [`supports`].
+//!
+//! TODO: Fixtures from `tests/fixtures/leopard/generate.c` still need to be
+//!       generated with Leopard and checked in. Until then the tests
+//!       comparing against them are ignored and compatibility is verified
+//!       only against this crate's own [`HighRate`].
+//!
+//! [Leopard-RS]: https://github.com/catid/leopard
+//! [simple usage]: crate#simple-usage
+//! [basic usage]: crate#basic-usage
+//! [`CANTOR_BASIS`]: crate::engine::CANTOR_BASIS
+//! [`DefaultRate`]: crate::rate::DefaultRate
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "std")]
+use crate::rate::{HighRateDecoder, RateDecoder};
+use crate::{
+    engine::DefaultEngine,
+    rate::{HighRate, HighRateEncoder, Rate, RateEncoder},
+    Error,
+};
+
+// ======================================================================
+// CONST - PRIVATE
+
+// Largest `n` for which Leopard uses its `GF(2^8)` codec.
+const LEOPARD_FF8_ORDER: usize = 256;
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Returns `true` if given `original_count` / `recovery_count`
+/// combination is supported by [`HighRate`] and Leopard
+/// uses its `GF(2^16)` codec for it.
+///
+/// # Examples
+///
+/// ```rust
+/// use reed_solomon_16::leopard;
+///
+/// assert!(!leopard::supports(100, 100));
+/// assert!(leopard::supports(200, 100));
+/// assert!(leopard::supports(1000, 1));
+/// ```
+pub fn supports(original_count: usize, recovery_count: usize) -> bool {
+    HighRate::<DefaultEngine>::supports(original_count, recovery_count)
+        && (recovery_count.next_power_of_two() + original_count).next_power_of_two()
+            > LEOPARD_FF8_ORDER
+}
+
+/// Encodes in one go like `leo_encode`, returning generated recovery shards.
+///
+/// This is like [`reed_solomon_16::encode`] except that
+/// high rate is always used and shard counts must be [supported].
+///
+/// [`reed_solomon_16::encode`]: crate::encode
+/// [supported]: supports
+pub fn encode<T>(
+    original_count: usize,
+    recovery_count: usize,
+    original: T,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+    T: IntoIterator,
+    T::Item: AsRef<[u8]>,
+{
+    if !supports(original_count, recovery_count) {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    let mut original = original.into_iter();
+
+    let (shard_bytes, first) = if let Some(first) = original.next() {
+        (first.as_ref().len(), first)
+    } else {
+        return Err(Error::TooFewOriginalShards {
+            original_count,
+            original_received_count: 0,
+        });
+    };
+
+    let mut encoder = HighRateEncoder::new(
+        original_count,
+        recovery_count,
+        shard_bytes,
+        DefaultEngine::new(),
+        None,
+    )?;
+
+    encoder.add_original_shard(first)?;
+    for original in original {
+        encoder.add_original_shard(original)?;
+    }
+
+    let result = encoder.encode()?;
+
+    Ok(result.recovery_iter().map(|s| s.to_vec()).collect())
+}
+
+/// Decodes in one go like `leo_decode`,
+/// returning restored original shards with their indexes.
+///
+/// This is like [`reed_solomon_16::decode`] except that
+/// high rate is always used and shard counts must be [supported].
+///
+/// Requires `std` feature because of the returned [`HashMap`].
+///
+/// [`reed_solomon_16::decode`]: crate::decode
+/// [supported]: supports
+#[cfg(feature = "std")]
+pub fn decode<O, R, OT, RT>(
+    original_count: usize,
+    recovery_count: usize,
+    original: O,
+    recovery: R,
+) -> Result<HashMap<usize, Vec<u8>>, Error>
+where
+    O: IntoIterator<Item = (usize, OT)>,
+    R: IntoIterator<Item = (usize, RT)>,
+    OT: AsRef<[u8]>,
+    RT: AsRef<[u8]>,
+{
+    if !supports(original_count, recovery_count) {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    let original = original.into_iter();
+    let mut recovery = recovery.into_iter();
+
+    let (shard_bytes, first_recovery) = if let Some(first_recovery) = recovery.next() {
+        (first_recovery.1.as_ref().len(), first_recovery)
+    } else {
+        // NO RECOVERY SHARDS
+
+        let original_received_count = original.count();
+        if original_received_count == original_count {
+            // Nothing to do, original data is complete.
+            return Ok(HashMap::new());
+        } else {
+            return Err(Error::NotEnoughShards {
+                original_count,
+                original_received_count,
+                recovery_received_count: 0,
+            });
+        }
+    };
+
+    let mut decoder = HighRateDecoder::new(
+        original_count,
+        recovery_count,
+        shard_bytes,
+        DefaultEngine::new(),
+        None,
+    )?;
+
+    for (index, original) in original {
+        decoder.add_original_shard(index, original)?;
+    }
+
+    decoder.add_recovery_shard(first_recovery.0, first_recovery.1)?;
+    for (index, recovery) in recovery {
+        decoder.add_recovery_shard(index, recovery)?;
+    }
+
+    let mut result = HashMap::new();
+    for (index, original) in decoder.decode()?.restored_original_iter() {
+        result.insert(index, original.to_vec());
+    }
+
+    Ok(result)
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    // ============================================================
+    // supports
+
+    #[test]
+    fn supports() {
+        // n = next_pow2(m + original_count) <= 256 uses GF(2^8) in Leopard
+        assert!(!super::supports(128, 128));
+        assert!(!super::supports(192, 64));
+        assert!(super::supports(193, 64));
+        assert!(super::supports(128, 129));
+        assert!(!super::supports(255, 1));
+        assert!(super::supports(256, 1));
+
+        assert!(super::supports(65535, 1));
+        assert!(!super::supports(65535, 2));
+        assert!(!super::supports(0, 1000));
+    }
+
+    // ============================================================
+    // encode / decode
+
+    #[test]
+    fn roundtrip() {
+        let original = test_util::generate_original(300, 128, 0);
+
+        let recovery = encode(300, 10, &original).unwrap();
+
+        let restored = decode(
+            300,
+            10,
+            original.iter().enumerate().skip(10),
+            recovery.iter().enumerate(),
+        )
+        .unwrap();
+
+        assert_eq!(restored.len(), 10);
+        for i in 0..10 {
+            assert_eq!(restored[&i], original[i]);
+        }
+    }
+
+    #[test]
+    fn roundtrip_ff8_boundary() {
+        // Smallest supported shard counts just above `GF(2^8)` boundary.
+        for (original_count, recovery_count) in [(129, 128), (64, 193), (1, 256)] {
+            let original = test_util::generate_original(original_count, 64, 2);
+
+            let recovery = encode(original_count, recovery_count, &original).unwrap();
+            assert_eq!(recovery.len(), recovery_count);
+
+            let lost = original_count.min(recovery_count);
+            let restored = decode(
+                original_count,
+                recovery_count,
+                original.iter().enumerate().skip(lost),
+                recovery.iter().enumerate().take(lost),
+            )
+            .unwrap();
+
+            assert_eq!(restored.len(), lost);
+            for i in 0..lost {
+                assert_eq!(restored[&i], original[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn uses_high_rate() {
+        // `DefaultRate` would use low rate here.
+        let original = test_util::generate_original(300, 64, 1);
+
+        let mut encoder = HighRateEncoder::new(300, 600, 64, DefaultEngine::new(), None).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let result = encoder.encode().unwrap();
+        let expected: Vec<_> = result.recovery_iter().collect();
+
+        assert_eq!(encode(300, 600, &original).unwrap(), expected);
+        assert_ne!(crate::encode(300, 600, &original).unwrap(), expected);
+    }
+
+    // ============================================================
+    // Leopard fixtures
+
+    // `(original_count, recovery_count, shard_bytes)` of fixtures
+    // generated by `tests/fixtures/leopard/generate.c`.
+    const FIXTURES: &[(usize, usize, usize)] = &[
+        (129, 128, 64),
+        (1, 256, 64),
+        (300, 10, 128),
+        (1000, 24, 64),
+        (3000, 1000, 192),
+        (64, 193, 128),
+    ];
+
+    // Returns original and recovery shards of given fixture.
+    fn read_fixture(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let path = format!(
+            "{}/tests/fixtures/leopard/{}_{}_{}.bin",
+            env!("CARGO_MANIFEST_DIR"),
+            original_count,
+            recovery_count,
+            shard_bytes
+        );
+        let data = std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+        assert_eq!(data.len(), (original_count + recovery_count) * shard_bytes);
+
+        let mut shards = data.chunks_exact(shard_bytes).map(<[u8]>::to_vec);
+        let original = shards.by_ref().take(original_count).collect();
+        let recovery = shards.collect();
+        (original, recovery)
+    }
+
+    // Requires fixtures generated with Leopard, see `read_fixture`.
+    #[test]
+    #[ignore]
+    fn encode_matches_leopard() {
+        for &(original_count, recovery_count, shard_bytes) in FIXTURES {
+            let (original, recovery) = read_fixture(original_count, recovery_count, shard_bytes);
+
+            assert!(
+                encode(original_count, recovery_count, &original).unwrap() == recovery,
+                "{} : {}",
+                original_count,
+                recovery_count
+            );
+        }
+    }
+
+    // Requires fixtures generated with Leopard, see `read_fixture`.
+    #[test]
+    #[ignore]
+    fn decode_leopard_recovery() {
+        for &(original_count, recovery_count, shard_bytes) in FIXTURES {
+            let (original, recovery) = read_fixture(original_count, recovery_count, shard_bytes);
+            let lost = original_count.min(recovery_count);
+
+            // Recovery shards both in ascending and descending index order.
+            let ascending: Vec<_> = recovery.iter().enumerate().take(lost).collect();
+            let descending: Vec<_> = recovery.iter().enumerate().rev().take(lost).collect();
+
+            for received in [ascending, descending] {
+                let restored = decode(
+                    original_count,
+                    recovery_count,
+                    original.iter().enumerate().skip(lost),
+                    received,
+                )
+                .unwrap();
+
+                assert_eq!(restored.len(), lost);
+                for i in 0..lost {
+                    assert_eq!(restored[&i], original[i]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            encode(100, 100, [[0u8; 64]]),
+            Err(Error::UnsupportedShardCount {
+                original_count: 100,
+                recovery_count: 100,
+            })
+        );
+    }
+}
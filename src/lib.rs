@@ -1,10 +1,25 @@
 #![doc = include_str!(concat!(env!("OUT_DIR"), "/README-rustdocified.md"))]
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::{collections::HashMap, fmt};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use crate::{
+    engine::DefaultEngine,
+    rate::{DefaultRate, Rate},
+};
+
+#[cfg(feature = "alloc")]
 pub use crate::{
-    decoder_result::{DecoderResult, RestoredOriginal},
+    decoder_result::{DecoderResult, RestoredOriginal, RestoredRecovery},
     encoder_result::{EncoderResult, Recovery},
     reed_solomon::{ReedSolomonDecoder, ReedSolomonEncoder},
 };
@@ -13,15 +28,42 @@ pub use crate::{
 #[macro_use]
 mod test_util;
 
+#[cfg(feature = "alloc")]
 mod decoder_result;
+#[cfg(feature = "alloc")]
 mod encoder_result;
+#[cfg(feature = "alloc")]
 mod reed_solomon;
 
 pub mod algorithm {
     #![doc = include_str!("algorithm.md")]
 }
+#[cfg(feature = "async")]
+pub mod async_shards;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod checksum;
 pub mod engine;
+#[cfg(feature = "alloc")]
+pub mod gf8;
+pub mod heapless;
+#[cfg(feature = "alloc")]
+pub mod interleaved;
+#[cfg(feature = "alloc")]
+pub mod leopard;
+#[cfg(feature = "alloc")]
+pub mod lrc;
+#[cfg(feature = "alloc")]
+pub mod novelpoly;
+#[cfg(feature = "std")]
+pub mod par2;
+#[cfg(feature = "alloc")]
+pub mod product;
+#[cfg(feature = "python")]
+pub mod python;
 pub mod rate;
+#[cfg(feature = "std")]
+pub mod stream;
 
 // ======================================================================
 // Error - PUBLIC
@@ -29,6 +71,17 @@ pub mod rate;
 /// Represents all possible errors that can occur in this library.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
+    /// Checksum of given shard doesn't match the given checksum,
+    /// i.e. the shard is corrupted.
+    ///
+    /// See [`checksum`] module for details.
+    ChecksumMismatch {
+        /// Index of the corrupted shard.
+        index: usize,
+        /// Kind of the corrupted shard.
+        kind: ShardKind,
+    },
+
     /// Given shard has different size than given or inferred shard size.
     ///
     /// - Shard size is given explicitly to encoders/decoders
@@ -44,6 +97,14 @@ pub enum Error {
         got: usize,
     },
 
+    /// Decoder was given two local parity shards with same index.
+    ///
+    /// See [`lrc`] module for details.
+    DuplicateLocalParityShardIndex {
+        /// Given duplicate index.
+        index: usize,
+    },
+
     /// Decoder was given two original shards with same index.
     DuplicateOriginalShardIndex {
         /// Given duplicate index.
@@ -56,6 +117,27 @@ pub enum Error {
         index: usize,
     },
 
+    /// Decoder was given two shards with same position.
+    ///
+    /// See [`product`] module for details.
+    DuplicateShardPosition {
+        /// Row of the given duplicate position.
+        row: usize,
+        /// Column of the given duplicate position.
+        column: usize,
+    },
+
+    /// Decoder was given local parity shard with invalid index,
+    /// i.e. `index >= group_count`.
+    ///
+    /// See [`lrc`] module for details.
+    InvalidLocalParityShardIndex {
+        /// Configured number of local groups.
+        group_count: usize,
+        /// Given invalid index.
+        index: usize,
+    },
+
     /// Decoder was given original shard with invalid index,
     /// i.e. `index >= original_count`.
     InvalidOriginalShardIndex {
@@ -65,6 +147,14 @@ pub enum Error {
         index: usize,
     },
 
+    /// Given PAR2 data is malformed or PAR2 parameters are invalid.
+    ///
+    /// See [`par2`] module for details.
+    InvalidPar2 {
+        /// Description of the problem.
+        reason: &'static str,
+    },
+
     /// Decoder was given recovery shard with invalid index,
     /// i.e. `index >= recovery_count`.
     InvalidRecoveryShardIndex {
@@ -74,6 +164,21 @@ pub enum Error {
         index: usize,
     },
 
+    /// Decoder was given shard with invalid position,
+    /// i.e. `row >= rows` or `column >= columns`.
+    ///
+    /// See [`product`] module for details.
+    InvalidShardPosition {
+        /// Number of rows in the grid, including recovery rows.
+        rows: usize,
+        /// Number of columns in the grid, including recovery columns.
+        columns: usize,
+        /// Row of the given invalid position.
+        row: usize,
+        /// Column of the given invalid position.
+        column: usize,
+    },
+
     /// Given or inferred shard size is invalid:
     /// Size must be non-zero and multiple of 64 bytes.
     ///
@@ -109,6 +214,21 @@ pub enum Error {
         original_received_count: usize,
     },
 
+    /// Decoder found more corrupted shards than it can correct.
+    ///
+    /// Given `original_count + n` shards in total,
+    /// up to `n / 2` corrupted shards can be corrected.
+    ///
+    /// See [`ReedSolomonDecoder::decode_with_errors`].
+    TooManyCorruptedShards {
+        /// Configured number of original shards.
+        original_count: usize,
+        /// Number of original shards given to decoder.
+        original_received_count: usize,
+        /// Number of recovery shards given to decoder.
+        recovery_received_count: usize,
+    },
+
     /// Encoder was given more than `original_count` original shards.
     TooManyOriginalShards {
         /// Configured number of original shards.
@@ -130,6 +250,10 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::ChecksumMismatch { index, kind } => {
+                write!(f, "checksum mismatch: {} shard {}", kind, index)
+            }
+
             Error::DifferentShardSize { shard_bytes, got } => {
                 write!(
                     f,
@@ -138,6 +262,10 @@ impl fmt::Display for Error {
                 )
             }
 
+            Error::DuplicateLocalParityShardIndex { index } => {
+                write!(f, "duplicate local parity shard index: {}", index)
+            }
+
             Error::DuplicateOriginalShardIndex { index } => {
                 write!(f, "duplicate original shard index: {}", index)
             }
@@ -146,6 +274,18 @@ impl fmt::Display for Error {
                 write!(f, "duplicate recovery shard index: {}", index)
             }
 
+            Error::DuplicateShardPosition { row, column } => {
+                write!(f, "duplicate shard position: ({}, {})", row, column)
+            }
+
+            Error::InvalidLocalParityShardIndex { group_count, index } => {
+                write!(
+                    f,
+                    "invalid local parity shard index: {} >= group_count {}",
+                    index, group_count,
+                )
+            }
+
             Error::InvalidOriginalShardIndex {
                 original_count,
                 index,
@@ -157,6 +297,10 @@ impl fmt::Display for Error {
                 )
             }
 
+            Error::InvalidPar2 { reason } => {
+                write!(f, "invalid PAR2 data: {}", reason)
+            }
+
             Error::InvalidRecoveryShardIndex {
                 recovery_count,
                 index,
@@ -168,6 +312,19 @@ impl fmt::Display for Error {
                 )
             }
 
+            Error::InvalidShardPosition {
+                rows,
+                columns,
+                row,
+                column,
+            } => {
+                write!(
+                    f,
+                    "invalid shard position: ({}, {}) is outside of {} x {} grid",
+                    row, column, rows, columns,
+                )
+            }
+
             Error::InvalidShardSize { shard_bytes } => {
                 write!(
                     f,
@@ -199,6 +356,18 @@ impl fmt::Display for Error {
                 )
             }
 
+            Error::TooManyCorruptedShards {
+                original_count,
+                original_received_count,
+                recovery_received_count,
+            } => {
+                write!(
+                    f,
+                    "too many corrupted shards: can't correct with {} original + {} recovery shards and original_count {}",
+                    original_received_count, recovery_received_count, original_count,
+                )
+            }
+
             Error::TooManyOriginalShards { original_count } => {
                 write!(
                     f,
@@ -224,8 +393,33 @@ impl fmt::Display for Error {
 // ======================================================================
 // Error - IMPL ERROR
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+// ======================================================================
+// ShardKind - PUBLIC
+
+/// Kind of a shard, i.e. original or recovery.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShardKind {
+    /// Original shard.
+    Original,
+    /// Recovery shard.
+    Recovery,
+}
+
+// ======================================================================
+// ShardKind - IMPL DISPLAY
+
+impl fmt::Display for ShardKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShardKind::Original => write!(f, "original"),
+            ShardKind::Recovery => write!(f, "recovery"),
+        }
+    }
+}
+
 // ======================================================================
 // FUNCTIONS - PUBLIC
 
@@ -239,6 +433,7 @@ impl std::error::Error for Error {}
 /// - These same indexes must be used when decoding.
 ///
 /// See [simple usage](crate#simple-usage) for an example.
+#[cfg(feature = "alloc")]
 pub fn encode<T>(
     original_count: usize,
     recovery_count: usize,
@@ -278,12 +473,58 @@ where
     Ok(result.recovery_iter().map(|s| s.to_vec()).collect())
 }
 
+/// Calculates only recovery shard `recovery_index`
+/// as a weighted sum of original shards.
+///
+/// - Result is same as `encode(original_count, recovery_count, original)?[recovery_index]`.
+/// - This takes `O(original_count)` time and needs memory only for two shards,
+///   so it is useful when each recovery shard is calculated by a different party.
+///   When all recovery shards are needed, [`encode`] is much faster.
+///
+/// See [`Rate::encode_one`] for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+/// let recovery = reed_solomon_16::encode(3, 5, original)?;
+///
+/// let recovery_2 = reed_solomon_16::encode_one(3, 5, 2, original)?;
+/// assert_eq!(recovery_2, recovery[2]);
+/// # Ok::<(), reed_solomon_16::Error>(())
+/// ```
+///
+/// [`Rate::encode_one`]: crate::rate::Rate::encode_one
+#[cfg(feature = "alloc")]
+pub fn encode_one<T>(
+    original_count: usize,
+    recovery_count: usize,
+    recovery_index: usize,
+    original: T,
+) -> Result<Vec<u8>, Error>
+where
+    T: IntoIterator,
+    T::Item: AsRef<[u8]>,
+{
+    DefaultRate::encode_one(
+        original_count,
+        recovery_count,
+        recovery_index,
+        original,
+        &DefaultEngine::new(),
+    )
+}
+
 /// Decodes in one go using [`ReedSolomonDecoder`],
 /// returning restored original shards with their indexes.
 ///
 /// - Given shard indexes must be the same that were used in encoding.
 ///
 /// See [simple usage](crate#simple-usage) for an example and more details.
+///
+/// Requires `std` feature because of the returned [`HashMap`].
+/// Without `std` use [`ReedSolomonDecoder`] instead.
+#[cfg(feature = "std")]
 pub fn decode<O, R, OT, RT>(
     original_count: usize,
     recovery_count: usize,
@@ -412,7 +653,7 @@ mod tests {
         #[test]
         fn too_many_original_shards() {
             assert_eq!(
-                encode(1, 1, &[[0u8; 64], [0u8; 64]]),
+                encode(1, 1, [[0u8; 64], [0u8; 64]]),
                 Err(Error::TooManyOriginalShards { original_count: 1 })
             );
         }
@@ -431,7 +672,7 @@ mod tests {
         #[test]
         fn unsupported_shard_count_with_zero_recovery_count() {
             assert_eq!(
-                encode(1, 0, &[[0u8; 64]]),
+                encode(1, 0, [[0u8; 64]]),
                 Err(Error::UnsupportedShardCount {
                     original_count: 1,
                     recovery_count: 0,
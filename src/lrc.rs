@@ -0,0 +1,553 @@
+//! Locally repairable code (LRC) built on top of [`ReedSolomonEncoder`]
+//! and [`ReedSolomonDecoder`].
+//!
+//! Repairing a single lost shard with plain Reed-Solomon decoding
+//! requires `original_count` shards. LRC reduces this by adding
+//! local parity to small groups of original shards:
+//!
+//! - Original shards are split into **local groups** of `group_size`
+//!   consecutive shards, except that last group may be smaller.
+//!   Group `g` contains original shards [`group_range(g)`].
+//! - Each local group has one **local parity shard**,
+//!   which is XOR of the original shards of the group.
+//!   Local parity shards have indexes `0..group_count`.
+//! - **Global recovery shards** are regular recovery shards generated
+//!   by [`ReedSolomonEncoder`] from all original shards.
+//!
+//! When decoding, [`LrcDecoder`] prefers local repair:
+//! Group which has lost a single original shard and has its local parity
+//! shard is repaired with `group_size` shards only. Global recovery shards
+//! are used only if some group can't be repaired locally.
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::lrc::{LrcDecoder, LrcEncoder};
+//!
+//! let original: Vec<[u8; 64]> = (0..6u8).map(|i| [i; 64]).collect();
+//!
+//! // 6 original shards in groups of 3 with 2 global recovery shards.
+//! let mut encoder = LrcEncoder::new(6, 3, 2, 64)?;
+//! for shard in &original {
+//!     encoder.add_original_shard(shard)?;
+//! }
+//! let result = encoder.encode()?;
+//! let local_parity = result.local_parity(0).unwrap().to_vec();
+//!
+//! // Lose original shard 1 and repair it from its group only.
+//! let mut decoder = LrcDecoder::new(6, 3, 2, 64)?;
+//! assert_eq!(decoder.group_range(0), Some(0..3));
+//! for index in [0, 2, 3, 4, 5] {
+//!     decoder.add_original_shard(index, &original[index])?;
+//! }
+//! decoder.add_local_parity_shard(0, &local_parity)?;
+//!
+//! let result = decoder.decode()?;
+//! assert!(!result.used_global_recovery());
+//! assert_eq!(result.restored_original(1).unwrap(), original[1]);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [`group_range(g)`]: LrcDecoder::group_range
+//! [`ReedSolomonDecoder`]: crate::ReedSolomonDecoder
+//! [`ReedSolomonEncoder`]: crate::ReedSolomonEncoder
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::{
+    engine::{DefaultEngine, Engine},
+    DecoderResult, EncoderResult, Error, ReedSolomonDecoder, ReedSolomonEncoder,
+};
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn group_range(original_count: usize, group_size: usize, group: usize) -> Option<Range<usize>> {
+    let start = group.checked_mul(group_size)?;
+    if start < original_count {
+        Some(start..core::cmp::min(start + group_size, original_count))
+    } else {
+        None
+    }
+}
+
+fn check_group_size(
+    original_count: usize,
+    recovery_count: usize,
+    group_size: usize,
+) -> Result<(), Error> {
+    if group_size == 0 {
+        Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// ======================================================================
+// LrcEncoder - PUBLIC
+
+/// Encoder generating local parity shards and global recovery shards.
+///
+/// See [module documentation](crate::lrc) for details.
+pub struct LrcEncoder {
+    encoder: ReedSolomonEncoder,
+    group_size: usize,
+    local_parity: Vec<Vec<u8>>,
+    original_received_count: usize,
+}
+
+impl LrcEncoder {
+    /// Adds one original shard to the encoder.
+    ///
+    /// Original shards have indexes `0..original_count` corresponding to the order
+    /// in which they are added and these same indexes must be used when decoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error> {
+        let original_shard = original_shard.as_ref();
+        self.encoder.add_original_shard(original_shard)?;
+
+        let index = self.original_received_count;
+        let local_parity = &mut self.local_parity[index / self.group_size];
+        if index.is_multiple_of(self.group_size) {
+            local_parity.copy_from_slice(original_shard);
+        } else {
+            DefaultEngine::xor(local_parity, original_shard);
+        }
+
+        self.original_received_count += 1;
+        Ok(())
+    }
+
+    /// Encodes the added original shards returning [`LrcEncoderResult`]
+    /// which contains the generated local parity shards and global recovery shards.
+    ///
+    /// When returned [`LrcEncoderResult`] is dropped the encoder is
+    /// automatically reset and ready for new round of encoding.
+    pub fn encode(&mut self) -> Result<LrcEncoderResult<'_>, Error> {
+        let result = self.encoder.encode()?;
+        self.original_received_count = 0;
+
+        Ok(LrcEncoderResult {
+            local_parity: &self.local_parity,
+            result,
+        })
+    }
+
+    /// Returns number of local groups.
+    pub fn group_count(&self) -> usize {
+        self.local_parity.len()
+    }
+
+    /// Creates new encoder with given configuration
+    /// and allocates required working space.
+    ///
+    /// Returns [`Error::UnsupportedShardCount`] if `group_size` is zero.
+    pub fn new(
+        original_count: usize,
+        group_size: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        check_group_size(original_count, recovery_count, group_size)?;
+        let encoder = ReedSolomonEncoder::new(original_count, recovery_count, shard_bytes)?;
+        let group_count = original_count.div_ceil(group_size);
+
+        Ok(Self {
+            encoder,
+            group_size,
+            local_parity: vec![vec![0; shard_bytes]; group_count],
+            original_received_count: 0,
+        })
+    }
+}
+
+// ======================================================================
+// LrcEncoderResult - PUBLIC
+
+/// Result of LRC encoding.
+/// Contains the generated local parity shards and global recovery shards.
+///
+/// This struct is created by [`LrcEncoder::encode`].
+pub struct LrcEncoderResult<'a> {
+    local_parity: &'a [Vec<u8>],
+    result: EncoderResult<'a>,
+}
+
+impl<'a> LrcEncoderResult<'a> {
+    /// Returns local parity shard of group with given `index`
+    /// or `None` if `index >= group_count`.
+    pub fn local_parity(&self, index: usize) -> Option<&[u8]> {
+        self.local_parity.get(index).map(Vec::as_slice)
+    }
+
+    /// Returns global recovery shard with given `index`
+    /// or `None` if `index >= recovery_count`.
+    pub fn recovery(&self, index: usize) -> Option<&[u8]> {
+        self.result.recovery(index)
+    }
+}
+
+// ======================================================================
+// Group - PRIVATE
+
+// Decoding state of a local group.
+struct Group {
+    // XOR of received original shards and local parity shard.
+    xor: Vec<u8>,
+    local_parity_received: bool,
+    original_received_count: usize,
+}
+
+// ======================================================================
+// LrcDecoder - PUBLIC
+
+/// Decoder which prefers local repair over global decoding.
+///
+/// See [module documentation](crate::lrc) for details.
+pub struct LrcDecoder {
+    decoder: ReedSolomonDecoder,
+    groups: Vec<Group>,
+    group_size: usize,
+    original_count: usize,
+    original_received: Vec<bool>,
+    recovery_count: usize,
+    shard_bytes: usize,
+}
+
+impl LrcDecoder {
+    /// Adds one local parity shard to the decoder.
+    ///
+    /// Index of local parity shard is the index of its group.
+    pub fn add_local_parity_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        local_parity_shard: T,
+    ) -> Result<(), Error> {
+        let local_parity_shard = local_parity_shard.as_ref();
+
+        if index >= self.groups.len() {
+            Err(Error::InvalidLocalParityShardIndex {
+                group_count: self.groups.len(),
+                index,
+            })
+        } else if self.groups[index].local_parity_received {
+            Err(Error::DuplicateLocalParityShardIndex { index })
+        } else if local_parity_shard.len() != self.shard_bytes {
+            Err(Error::DifferentShardSize {
+                shard_bytes: self.shard_bytes,
+                got: local_parity_shard.len(),
+            })
+        } else {
+            let group = &mut self.groups[index];
+            DefaultEngine::xor(&mut group.xor, local_parity_shard);
+            group.local_parity_received = true;
+            Ok(())
+        }
+    }
+
+    /// Adds one original shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_original_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+    ) -> Result<(), Error> {
+        let original_shard = original_shard.as_ref();
+        self.decoder.add_original_shard(index, original_shard)?;
+
+        let group = &mut self.groups[index / self.group_size];
+        DefaultEngine::xor(&mut group.xor, original_shard);
+        group.original_received_count += 1;
+        self.original_received[index] = true;
+        Ok(())
+    }
+
+    /// Adds one global recovery shard to the decoder.
+    ///
+    /// - Shards can be added in any order.
+    /// - Index must be the same that was used in encoding.
+    pub fn add_recovery_shard<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+    ) -> Result<(), Error> {
+        self.decoder.add_recovery_shard(index, recovery_shard)
+    }
+
+    /// Decodes the added shards returning [`LrcDecoderResult`]
+    /// which contains the restored original shards.
+    ///
+    /// - Every group which has lost a single original shard
+    ///   and has its local parity shard is repaired locally.
+    /// - If any original shards remain missing after that,
+    ///   they are restored with global decoding, which requires
+    ///   as many shards as there were original shards in total,
+    ///   counting locally repaired shards.
+    ///
+    /// When returned [`LrcDecoderResult`] is dropped the decoder is
+    /// automatically reset and ready for new round of decoding.
+    pub fn decode(&mut self) -> Result<LrcDecoderResult<'_>, Error> {
+        let mut local = Vec::new();
+        let mut global_missing_count = 0;
+
+        for (group_index, group) in self.groups.iter().enumerate() {
+            let range = group_range(self.original_count, self.group_size, group_index).unwrap();
+            let missing_count = range.len() - group.original_received_count;
+
+            if missing_count == 1 && group.local_parity_received {
+                let index = range.clone().find(|&i| !self.original_received[i]).unwrap();
+                local.push((index, group.xor.clone()));
+            } else {
+                global_missing_count += missing_count;
+            }
+        }
+
+        if global_missing_count == 0 {
+            self.reset_local();
+            self.decoder
+                .reset(self.original_count, self.recovery_count, self.shard_bytes)?;
+            return Ok(LrcDecoderResult {
+                global: None,
+                local,
+            });
+        }
+
+        let original_received_count = self.decoder.original_received_count() + local.len();
+        let recovery_received_count = self.decoder.recovery_received_count();
+        if original_received_count + recovery_received_count < self.original_count {
+            return Err(Error::NotEnoughShards {
+                original_count: self.original_count,
+                original_received_count,
+                recovery_received_count,
+            });
+        }
+
+        for (index, shard) in &local {
+            self.decoder.add_original_shard(*index, shard)?;
+        }
+
+        self.reset_local();
+
+        Ok(LrcDecoderResult {
+            global: Some(self.decoder.decode()?),
+            local,
+        })
+    }
+
+    /// Returns number of local groups.
+    pub fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns range of indexes of original shards in group with given `index`
+    /// or `None` if `index >= group_count`.
+    ///
+    /// Local repair of the group reads only these original shards
+    /// and local parity shard of the group.
+    pub fn group_range(&self, index: usize) -> Option<Range<usize>> {
+        group_range(self.original_count, self.group_size, index)
+    }
+
+    /// Creates new decoder with given configuration
+    /// and allocates required working space.
+    ///
+    /// Returns [`Error::UnsupportedShardCount`] if `group_size` is zero.
+    pub fn new(
+        original_count: usize,
+        group_size: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        check_group_size(original_count, recovery_count, group_size)?;
+        let decoder = ReedSolomonDecoder::new(original_count, recovery_count, shard_bytes)?;
+        let group_count = original_count.div_ceil(group_size);
+
+        let groups = (0..group_count)
+            .map(|_| Group {
+                xor: vec![0; shard_bytes],
+                local_parity_received: false,
+                original_received_count: 0,
+            })
+            .collect();
+
+        Ok(Self {
+            decoder,
+            groups,
+            group_size,
+            original_count,
+            original_received: vec![false; original_count],
+            recovery_count,
+            shard_bytes,
+        })
+    }
+}
+
+// ======================================================================
+// LrcDecoder - PRIVATE
+
+impl LrcDecoder {
+    fn reset_local(&mut self) {
+        for group in &mut self.groups {
+            group.xor.fill(0);
+            group.local_parity_received = false;
+            group.original_received_count = 0;
+        }
+        self.original_received.fill(false);
+    }
+}
+
+// ======================================================================
+// LrcDecoderResult - PUBLIC
+
+/// Result of LRC decoding. Contains the restored original shards.
+///
+/// This struct is created by [`LrcDecoder::decode`].
+pub struct LrcDecoderResult<'a> {
+    global: Option<DecoderResult<'a>>,
+    // Locally repaired shards ordered by index.
+    local: Vec<(usize, Vec<u8>)>,
+}
+
+impl<'a> LrcDecoderResult<'a> {
+    /// Returns restored original shard with given `index`
+    /// or `None` if given `index` doesn't correspond to
+    /// a missing original shard.
+    pub fn restored_original(&self, index: usize) -> Option<&[u8]> {
+        if let Ok(pos) = self.local.binary_search_by_key(&index, |(i, _)| *i) {
+            Some(&self.local[pos].1)
+        } else {
+            self.global.as_ref()?.restored_original(index)
+        }
+    }
+
+    /// Returns `true` if global recovery shards were used in decoding,
+    /// i.e. if some original shards couldn't be repaired locally.
+    pub fn used_global_recovery(&self) -> bool {
+        self.global.is_some()
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    // 10 original shards in groups of 4, 4, 2 with 3 recovery shards.
+    fn encode(original: &[Vec<u8>]) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let mut encoder = LrcEncoder::new(10, 4, 3, 64).unwrap();
+        assert_eq!(encoder.group_count(), 3);
+        for original in original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let result = encoder.encode().unwrap();
+
+        let local_parity = (0..3)
+            .map(|i| result.local_parity(i).unwrap().to_vec())
+            .collect();
+        let recovery = (0..3)
+            .map(|i| result.recovery(i).unwrap().to_vec())
+            .collect();
+        (local_parity, recovery)
+    }
+
+    #[test]
+    fn local_repair() {
+        let original = test_util::generate_original(10, 64, 0);
+        let (local_parity, _) = encode(&original);
+
+        let mut decoder = LrcDecoder::new(10, 4, 3, 64).unwrap();
+        assert_eq!(decoder.group_range(2), Some(8..10));
+        assert_eq!(decoder.group_range(3), None);
+
+        // Lose one shard from each group.
+        for i in [0, 1, 3, 4, 5, 6, 9] {
+            decoder.add_original_shard(i, &original[i]).unwrap();
+        }
+        for (i, local_parity) in local_parity.iter().enumerate() {
+            decoder.add_local_parity_shard(i, local_parity).unwrap();
+        }
+
+        let result = decoder.decode().unwrap();
+        assert!(!result.used_global_recovery());
+        for i in [2, 7, 8] {
+            assert_eq!(result.restored_original(i).unwrap(), original[i]);
+        }
+        assert!(result.restored_original(0).is_none());
+    }
+
+    #[test]
+    fn global_repair() {
+        let original = test_util::generate_original(10, 64, 0);
+        let (local_parity, recovery) = encode(&original);
+
+        // Two shards lost in group 0 and one in group 2.
+        // Group 2 is repaired locally which, with one recovery shard,
+        // gives enough shards for global decoding.
+        let mut decoder = LrcDecoder::new(10, 4, 3, 64).unwrap();
+        for i in [2, 3, 4, 5, 6, 7, 8] {
+            decoder.add_original_shard(i, &original[i]).unwrap();
+        }
+        decoder.add_local_parity_shard(2, &local_parity[2]).unwrap();
+
+        assert_eq!(
+            decoder.decode().err(),
+            Some(Error::NotEnoughShards {
+                original_count: 10,
+                original_received_count: 8,
+                recovery_received_count: 0,
+            })
+        );
+
+        decoder.add_recovery_shard(1, &recovery[1]).unwrap();
+        decoder.add_recovery_shard(2, &recovery[2]).unwrap();
+
+        let result = decoder.decode().unwrap();
+        assert!(result.used_global_recovery());
+        for i in [0, 1, 9] {
+            assert_eq!(result.restored_original(i).unwrap(), original[i]);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            LrcEncoder::new(10, 0, 3, 64).err(),
+            Some(Error::UnsupportedShardCount {
+                original_count: 10,
+                recovery_count: 3,
+            })
+        );
+
+        let mut decoder = LrcDecoder::new(10, 4, 3, 64).unwrap();
+
+        assert_eq!(
+            decoder.add_local_parity_shard(3, [0; 64]),
+            Err(Error::InvalidLocalParityShardIndex {
+                group_count: 3,
+                index: 3,
+            })
+        );
+
+        assert_eq!(
+            decoder.add_local_parity_shard(0, [0; 128]),
+            Err(Error::DifferentShardSize {
+                shard_bytes: 64,
+                got: 128,
+            })
+        );
+
+        decoder.add_local_parity_shard(0, [0; 64]).unwrap();
+        assert_eq!(
+            decoder.add_local_parity_shard(0, [0; 64]),
+            Err(Error::DuplicateLocalParityShardIndex { index: 0 })
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Encoding/decoding compatible with [`reed-solomon-novelpoly`] shards.
+//!
+//! **This is an advanced module which is not needed for [simple usage] or [basic usage].**
+//!
+//! [`reed-solomon-novelpoly`] implements the same construction of Lin, Chung and Han
+//! over `GF(2^16)` with same field polynomial and [`CANTOR_BASIS`].
+//! Its `encode(bytes, validator_count)` / `reconstruct(shards, validator_count)`
+//! differ from this crate only in parameters and shard layout:
+//!
+//! - **Parameters**
+//!     - Payload is split into runs of `k` 16-bit symbols, where `k` is
+//!       power of two given by [`original_count`].
+//!     - Each run is encoded separately into `validator_count` symbols,
+//!       first `k` of which are the run itself.
+//! - **Rate**
+//!     - Run of `k` symbols is at points `0 .. k` and
+//!       symbol `k + i` is at point `k + i`, which is exactly [`LowRate`]
+//!       with `original_count = k` and `recovery_count = validator_count - k`,
+//!       so no separate rate is needed.
+//! - **Shard layout**
+//!     - Validator shard `i` contains symbol `i` of every run,
+//!       each as 2 bytes big-endian.
+//!     - Shards of this crate contain 32 symbols per 64-byte block
+//!       as described in [algorithm > Shard](crate::algorithm#shard).
+//!
+//! [`encode`] and [`reconstruct`] of this module convert between these
+//! and produce/consume shards byte-compatible with `reed-solomon-novelpoly`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use reed_solomon_16::novelpoly;
+//!
+//! let payload = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+//!
+//! let shards = novelpoly::encode(payload, 10)?;
+//! assert_eq!(shards.len(), 10);
+//!
+//! // `original_count(10) == 4`, so any 4 shards are enough.
+//! let mut received: Vec<Option<Vec<u8>>> = vec![None; 10];
+//! for i in [1, 3, 8, 9] {
+//!     received[i] = Some(shards[i].clone());
+//! }
+//!
+//! let restored = novelpoly::reconstruct(received, 10)?;
+//! assert_eq!(&restored[..payload.len()], payload);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [`reed-solomon-novelpoly`]: https://crates.io/crates/reed-solomon-novelpoly
+//! [simple usage]: crate#simple-usage
+//! [basic usage]: crate#basic-usage
+//! [`CANTOR_BASIS`]: crate::engine::CANTOR_BASIS
+//! [`LowRate`]: crate::rate::LowRate
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    engine::{self, DefaultEngine, GF_ORDER},
+    rate::{LowRateDecoder, LowRateEncoder, RateDecoder, RateEncoder},
+    Error,
+};
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Returns number of original shards `k` used by `reed-solomon-novelpoly`
+/// for given `validator_count`, i.e. how many shards are needed to reconstruct.
+///
+/// This is the largest power of two not exceeding
+/// `(validator_count - 1) / 3 + 1`.
+///
+/// Returns [`Error::UnsupportedShardCount`] unless
+/// `2 <= validator_count <= 65536`.
+pub fn original_count(validator_count: usize) -> Result<usize, Error> {
+    if !(2..=GF_ORDER).contains(&validator_count) {
+        return Err(Error::UnsupportedShardCount {
+            original_count: 0,
+            recovery_count: validator_count,
+        });
+    }
+
+    let subset_size = (validator_count - 1) / 3 + 1;
+    Ok(1 << subset_size.ilog2())
+}
+
+/// Encodes `payload` into `validator_count` shards
+/// like `reed_solomon_novelpoly::encode`.
+///
+/// - Shard `i` is meant for validator `i`.
+/// - Shards are `2 * ceil(payload.len() / (2 * k))` bytes,
+///   where `k` is [`original_count`].
+pub fn encode(payload: &[u8], validator_count: usize) -> Result<Vec<Vec<u8>>, Error> {
+    let k = original_count(validator_count)?;
+
+    if payload.is_empty() {
+        return Err(Error::InvalidShardSize { shard_bytes: 0 });
+    }
+
+    let run_count = payload.len().div_ceil(2 * k);
+
+    let original: Vec<Vec<u8>> = (0..k)
+        .map(|i| {
+            (0..run_count)
+                .flat_map(|run| {
+                    let pos = run * 2 * k + i * 2;
+                    [byte_at(payload, pos), byte_at(payload, pos + 1)]
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut encoder = LowRateEncoder::new(
+        k,
+        validator_count - k,
+        shard_bytes(run_count),
+        DefaultEngine::new(),
+        None,
+    )?;
+
+    for original in &original {
+        encoder.add_original_shard(to_shard(original, run_count))?;
+    }
+
+    let result = encoder.encode()?;
+
+    let mut shards = original;
+    shards.extend(
+        result
+            .recovery_iter()
+            .map(|recovery| from_shard(recovery, run_count)),
+    );
+
+    Ok(shards)
+}
+
+/// Reconstructs payload from shards like `reed_solomon_novelpoly::reconstruct`.
+///
+/// - `received[i]` is the shard of validator `i`, or `None` if missing.
+/// - At least [`original_count`] shards must be given.
+/// - Returned payload is padded with zeroes to
+///   `k` times shard size, where `k` is [`original_count`].
+pub fn reconstruct<I, T>(received: I, validator_count: usize) -> Result<Vec<u8>, Error>
+where
+    I: IntoIterator<Item = Option<T>>,
+    T: AsRef<[u8]>,
+{
+    let k = original_count(validator_count)?;
+
+    let mut decoder: Option<LowRateDecoder<DefaultEngine>> = None;
+    let mut original: Vec<Option<Vec<u8>>> = vec![None; k];
+    let mut shard_len = None;
+    let mut received_count = 0;
+
+    for (index, shard) in received.into_iter().enumerate().take(validator_count) {
+        let shard = match shard {
+            Some(ref shard) => shard.as_ref(),
+            None => continue,
+        };
+
+        match shard_len {
+            None if shard.is_empty() || shard.len() % 2 != 0 => {
+                return Err(Error::InvalidShardSize {
+                    shard_bytes: shard.len(),
+                });
+            }
+            None => shard_len = Some(shard.len()),
+            Some(len) if len != shard.len() => {
+                return Err(Error::DifferentShardSize {
+                    shard_bytes: len,
+                    got: shard.len(),
+                });
+            }
+            Some(_) => {}
+        }
+
+        let run_count = shard.len() / 2;
+        let decoder = match &mut decoder {
+            Some(decoder) => decoder,
+            None => decoder.insert(LowRateDecoder::new(
+                k,
+                validator_count - k,
+                shard_bytes(run_count),
+                DefaultEngine::new(),
+                None,
+            )?),
+        };
+
+        if index < k {
+            original[index] = Some(shard.to_vec());
+            decoder.add_original_shard(index, to_shard(shard, run_count))?;
+        } else {
+            decoder.add_recovery_shard(index - k, to_shard(shard, run_count))?;
+        }
+
+        received_count += 1;
+    }
+
+    let (Some(mut decoder), Some(shard_len)) = (decoder, shard_len) else {
+        return Err(Error::NotEnoughShards {
+            original_count: k,
+            original_received_count: 0,
+            recovery_received_count: 0,
+        });
+    };
+
+    let run_count = shard_len / 2;
+
+    if received_count < k || original.iter().any(Option::is_none) {
+        let result = decoder.decode()?;
+        for (index, restored) in result.restored_original_iter() {
+            original[index] = Some(from_shard(restored, run_count));
+        }
+    }
+
+    let mut payload = Vec::with_capacity(k * shard_len);
+    for run in 0..run_count {
+        for original in &original {
+            let original = original.as_ref().unwrap();
+            payload.extend_from_slice(&original[run * 2..run * 2 + 2]);
+        }
+    }
+
+    Ok(payload)
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn byte_at(payload: &[u8], pos: usize) -> u8 {
+    payload.get(pos).copied().unwrap_or(0)
+}
+
+// Shard size in this crate for `run_count` symbols.
+fn shard_bytes(run_count: usize) -> usize {
+    engine::checked_next_multiple_of(run_count * 2, 64).unwrap()
+}
+
+// Converts shard of big-endian symbols to shard layout of this crate.
+fn to_shard(novelpoly: &[u8], run_count: usize) -> Vec<u8> {
+    let mut shard = vec![0; shard_bytes(run_count)];
+    for (i, symbol) in novelpoly.chunks_exact(2).enumerate() {
+        let pos = (i / 32) * 64 + i % 32;
+        shard[pos] = symbol[1];
+        shard[pos + 32] = symbol[0];
+    }
+    shard
+}
+
+// Converts shard layout of this crate to shard of big-endian symbols.
+fn from_shard(shard: &[u8], run_count: usize) -> Vec<u8> {
+    (0..run_count)
+        .flat_map(|i| {
+            let pos = (i / 32) * 64 + i % 32;
+            [shard[pos + 32], shard[pos]]
+        })
+        .collect()
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use reed_solomon_novelpoly::WrappedShard;
+
+    use super::*;
+    use crate::test_util;
+
+    fn payload(len: usize, seed: u8) -> Vec<u8> {
+        test_util::generate_original(1, len, seed).remove(0)
+    }
+
+    // ============================================================
+    // original_count
+
+    #[test]
+    fn original_count_matches_novelpoly() {
+        for validator_count in [2, 3, 4, 5, 10, 11, 100, 1000, 65536] {
+            let params = reed_solomon_novelpoly::CodeParams::derive_parameters(
+                validator_count,
+                reed_solomon_novelpoly::recoverablity_subset_size(validator_count),
+            )
+            .unwrap();
+            assert_eq!(original_count(validator_count), Ok(params.k()));
+        }
+
+        assert!(original_count(1).is_err());
+        assert!(original_count(65537).is_err());
+    }
+
+    // ============================================================
+    // encode
+
+    #[test]
+    fn encode_matches_novelpoly() {
+        for (len, validator_count) in [(1, 2), (57, 10), (1000, 11), (4321, 100), (100, 1000)] {
+            let payload = payload(len, validator_count as u8);
+
+            let expected: Vec<Vec<u8>> =
+                reed_solomon_novelpoly::encode::<WrappedShard>(&payload, validator_count)
+                    .unwrap()
+                    .into_iter()
+                    .map(WrappedShard::into_inner)
+                    .collect();
+
+            assert_eq!(encode(&payload, validator_count).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn encode_empty() {
+        assert_eq!(
+            encode(&[], 10),
+            Err(Error::InvalidShardSize { shard_bytes: 0 })
+        );
+    }
+
+    // ============================================================
+    // reconstruct
+
+    #[test]
+    fn reconstruct_from_novelpoly() {
+        let validator_count = 100;
+        let payload = payload(3000, 1);
+        let k = original_count(validator_count).unwrap();
+
+        let shards =
+            reed_solomon_novelpoly::encode::<WrappedShard>(&payload, validator_count).unwrap();
+
+        let received: Vec<_> = shards
+            .into_iter()
+            .enumerate()
+            .map(|(i, shard)| (i % 3 == 1).then(|| shard.into_inner()))
+            .collect();
+
+        let restored = reconstruct(received.clone(), validator_count).unwrap();
+        let expected = reed_solomon_novelpoly::reconstruct(
+            received
+                .into_iter()
+                .map(|shard| shard.map(WrappedShard::new))
+                .collect(),
+            validator_count,
+        )
+        .unwrap();
+
+        assert_eq!(restored, expected);
+        assert_eq!(&restored[..payload.len()], payload);
+        assert_eq!(restored.len() % (2 * k), 0);
+    }
+
+    #[test]
+    fn reconstruct_all_originals() {
+        let payload = payload(200, 2);
+        let shards = encode(&payload, 10).unwrap();
+
+        let received = shards.iter().map(Some).take(4);
+        let restored = reconstruct(received, 10).unwrap();
+
+        assert_eq!(&restored[..payload.len()], payload);
+    }
+
+    #[test]
+    fn reconstruct_errors() {
+        let shards = encode(&payload(200, 3), 10).unwrap();
+
+        let mut received = vec![None; 10];
+        received[5] = Some(&shards[5]);
+        assert_eq!(
+            reconstruct(received, 10),
+            Err(Error::NotEnoughShards {
+                original_count: 4,
+                original_received_count: 0,
+                recovery_received_count: 1,
+            })
+        );
+
+        assert_eq!(
+            reconstruct([Some(&shards[0][..]), Some(&shards[1][..2])], 10),
+            Err(Error::DifferentShardSize {
+                shard_bytes: shards[0].len(),
+                got: 2,
+            })
+        );
+
+        assert_eq!(
+            reconstruct([Some(&[1u8, 2, 3][..])], 10),
+            Err(Error::InvalidShardSize { shard_bytes: 3 })
+        );
+    }
+}
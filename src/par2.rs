@@ -0,0 +1,923 @@
+//! Reading and writing [PAR2] recovery files.
+//!
+//! **This is an advanced module which is not needed for [simple usage] or [basic usage].**
+//!
+//! PAR2 is a Reed-Solomon code of its own, so it doesn't use the FFT-based
+//! algorithm of this crate, only the `GF(2^16)` table arithmetic:
+//!
+//! - **Field**
+//!     - PAR2 uses [`PAR2_POLYNOMIAL`] in plain polynomial basis,
+//!       see [`tables::initialize_par2_exp_log`].
+//! - **Code**
+//!     - Input files are split into slices of `slice_size` bytes,
+//!       last slice of each file being zero-padded.
+//!     - Input slice `i` (counting over all files in file ID order)
+//!       has constant `c_i = 2^n_i` where `n_i` is the `i`:th positive integer
+//!       coprime to `65535`. Recovery slice with exponent `e` is
+//!       `sum(c_i^e * slice_i)` over little-endian 16-bit words.
+//!     - Repair solves the resulting Vandermonde system
+//!       with Gauss-Jordan elimination.
+//! - **Packets**
+//!     - Main, file description, input file slice checksum,
+//!       recovery slice and creator packets are supported.
+//!     - Damaged packets and packets of other recovery sets are skipped.
+//!       Other packet types, e.g. Unicode filenames, are ignored.
+//!
+//! TODO: Fixtures from `tests/fixtures/par2/generate.sh` still need to be
+//!       generated with `par2cmdline` and checked in. Until then the tests
+//!       parsing them and comparing [`create`] against them are ignored.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use reed_solomon_16::par2::{self, RecoverySet};
+//!
+//! let a = vec![1u8; 1000];
+//! let b = vec![2u8; 300];
+//!
+//! let par2 = par2::create(&[("a.bin", &a), ("b.bin", &b)], 128, 4)?;
+//! let set = RecoverySet::parse([&par2])?;
+//!
+//! let mut files = HashMap::new();
+//! files.insert("a.bin".to_string(), a.clone());
+//! files.get_mut("a.bin").unwrap()[500] ^= 1;
+//! assert_eq!(set.verify_file("a.bin", &files["a.bin"]), Some(vec![3]));
+//!
+//! // "b.bin" is missing completely, which is 3 slices.
+//! assert_eq!(set.repair(&mut files)?, 4);
+//! assert_eq!(files["a.bin"], a);
+//! assert_eq!(files["b.bin"], b);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [PAR2]: https://parchive.github.io/doc/Parity%20Volume%20Set%20Specification%20v2.0.html
+//! [simple usage]: crate#simple-usage
+//! [basic usage]: crate#basic-usage
+//! [`PAR2_POLYNOMIAL`]: crate::engine::tables::PAR2_POLYNOMIAL
+//! [`tables::initialize_par2_exp_log`]: crate::engine::tables::initialize_par2_exp_log
+
+use std::collections::{BTreeMap, HashMap};
+
+use fixedbitset::FixedBitSet;
+
+use crate::Error;
+
+mod field;
+mod hash;
+mod packet;
+
+// ======================================================================
+// CONST - PRIVATE
+
+/// Maximum number of input slices, i.e. number of integers coprime to `65535`.
+const MAX_SLICE_COUNT: usize = 32768;
+
+/// Maximum slice size, which limits memory used for a single slice.
+const MAX_SLICE_SIZE: usize = 1 << 26;
+
+/// Maximum number of recovery slices, exponents being `0 .. 65535`.
+const MAX_RECOVERY_COUNT: usize = 65535;
+
+/// File prefix which is hashed as `hash_16k`.
+const HASH_16K_BYTES: usize = 16384;
+
+// ======================================================================
+// FileDescription - PUBLIC
+
+/// Description of a file protected by a [`RecoverySet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDescription {
+    /// File ID, i.e. `MD5(hash_16k || length || name)`.
+    pub file_id: [u8; 16],
+    /// MD5 of the whole file.
+    pub hash: [u8; 16],
+    /// MD5 of the first 16 kiB of the file.
+    pub hash_16k: [u8; 16],
+    /// File length in bytes.
+    pub length: u64,
+    /// File name.
+    pub name: String,
+}
+
+impl FileDescription {
+    fn new(name: &str, data: &[u8]) -> Self {
+        let hash = hash::md5(data);
+        let hash_16k = hash::md5(&data[..data.len().min(HASH_16K_BYTES)]);
+        let length = data.len() as u64;
+
+        let mut hasher = hash::Md5::new();
+        hasher.update(&hash_16k);
+        hasher.update(&length.to_le_bytes());
+        hasher.update(name.as_bytes());
+
+        Self {
+            file_id: hasher.finalize(),
+            hash,
+            hash_16k,
+            length,
+            name: name.to_string(),
+        }
+    }
+
+    fn slice_count(&self, slice_size: usize) -> usize {
+        self.length.div_ceil(slice_size as u64) as usize
+    }
+}
+
+// ======================================================================
+// RecoverySet - PUBLIC
+
+/// Parsed PAR2 recovery set.
+///
+/// See [module documentation](self) for details and example.
+#[derive(Clone, Debug)]
+pub struct RecoverySet {
+    slice_size: usize,
+    files: Vec<FileDescription>,
+    // MD5 and CRC32 of each slice of each file, in same order as `files`.
+    checksums: Vec<Vec<([u8; 16], u32)>>,
+    recovery: BTreeMap<u32, Vec<u8>>,
+}
+
+impl RecoverySet {
+    /// Returns descriptions of protected files, in file ID order.
+    pub fn files(&self) -> &[FileDescription] {
+        &self.files
+    }
+
+    /// Parses recovery set from contents of PAR2 files,
+    /// e.g. `name.par2` and `name.vol00+10.par2`.
+    ///
+    /// Recovery set is determined by the first valid main packet found.
+    pub fn parse<I, T>(par2_files: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let par2_files: Vec<T> = par2_files.into_iter().collect();
+        let packets: Vec<_> = par2_files
+            .iter()
+            .flat_map(|data| packet::parse(data.as_ref()))
+            .collect();
+
+        // MAIN PACKET
+
+        let main = packets
+            .iter()
+            .find(|p| &p.kind == packet::TYPE_MAIN && hash::md5(p.body) == p.set_id)
+            .ok_or(Error::InvalidPar2 {
+                reason: "main packet not found",
+            })?;
+
+        if main.body.len() < 12 {
+            return Err(Error::InvalidPar2 {
+                reason: "main packet too short",
+            });
+        }
+
+        let slice_size = usize::try_from(packet::read_u64(main.body, 0)).unwrap_or(0);
+        let file_count = packet::read_u32(main.body, 8) as usize;
+
+        if slice_size == 0 || !slice_size.is_multiple_of(4) {
+            return Err(Error::InvalidPar2 {
+                reason: "invalid slice size",
+            });
+        }
+
+        if (main.body.len() - 12) / 16 < file_count {
+            return Err(Error::InvalidPar2 {
+                reason: "main packet too short",
+            });
+        }
+
+        let file_ids: Vec<_> = (0..file_count)
+            .map(|i| packet::read_id(main.body, 12 + 16 * i))
+            .collect();
+
+        // OTHER PACKETS
+
+        let mut descriptions = HashMap::new();
+        let mut checksums = HashMap::new();
+        let mut recovery = BTreeMap::new();
+
+        for p in packets.iter().filter(|p| p.set_id == main.set_id) {
+            match &p.kind {
+                packet::TYPE_FILE_DESC if p.body.len() >= 56 => {
+                    let description = FileDescription {
+                        file_id: packet::read_id(p.body, 0),
+                        hash: packet::read_id(p.body, 16),
+                        hash_16k: packet::read_id(p.body, 32),
+                        length: packet::read_u64(p.body, 48),
+                        name: String::from_utf8_lossy(packet::unpad(&p.body[56..])).into_owned(),
+                    };
+                    descriptions.insert(description.file_id, description);
+                }
+
+                packet::TYPE_IFSC if p.body.len() >= 16 => {
+                    let slices = p.body[16..]
+                        .chunks_exact(20)
+                        .map(|x| (packet::read_id(x, 0), packet::read_u32(x, 16)))
+                        .collect::<Vec<_>>();
+                    checksums.insert(packet::read_id(p.body, 0), slices);
+                }
+
+                packet::TYPE_RECOVERY_SLICE if Some(p.body.len()) == slice_size.checked_add(4) => {
+                    recovery.insert(packet::read_u32(p.body, 0), p.body[4..].to_vec());
+                }
+
+                _ => {}
+            }
+        }
+
+        let max_length = file_ids
+            .iter()
+            .filter_map(|file_id| descriptions.get(file_id))
+            .map(|description| description.length)
+            .max()
+            .unwrap_or(0);
+        check_slice_size_limit(slice_size, max_length)?;
+
+        // COLLECT FILES
+
+        let mut files = Vec::with_capacity(file_count);
+        let mut file_checksums = Vec::with_capacity(file_count);
+
+        for file_id in &file_ids {
+            let description = descriptions.remove(file_id).ok_or(Error::InvalidPar2 {
+                reason: "file description packet not found",
+            })?;
+
+            let slices = checksums.remove(file_id).ok_or(Error::InvalidPar2 {
+                reason: "input file slice checksum packet not found",
+            })?;
+
+            if slices.len() != description.slice_count(slice_size) {
+                return Err(Error::InvalidPar2 {
+                    reason: "wrong number of input file slice checksums",
+                });
+            }
+
+            files.push(description);
+            file_checksums.push(slices);
+        }
+
+        if file_checksums.iter().map(Vec::len).sum::<usize>() > MAX_SLICE_COUNT {
+            return Err(Error::InvalidPar2 {
+                reason: "too many input slices",
+            });
+        }
+
+        Ok(Self {
+            slice_size,
+            files,
+            checksums: file_checksums,
+            recovery,
+        })
+    }
+
+    /// Returns number of recovery slices available.
+    pub fn recovery_slice_count(&self) -> usize {
+        self.recovery.len()
+    }
+
+    /// Repairs damaged and missing files in-place,
+    /// returning number of repaired slices.
+    ///
+    /// - `files` maps file names to file contents.
+    ///   Missing files are added and files with wrong length are resized.
+    /// - Files which aren't part of this recovery set are left untouched.
+    /// - Returns [`Error::NotEnoughShards`] if there are
+    ///   fewer recovery slices than damaged slices,
+    ///   with input slices counted as original shards.
+    pub fn repair(&self, files: &mut HashMap<String, Vec<u8>>) -> Result<usize, Error> {
+        // FIND DAMAGED SLICES
+
+        let mut slices = Vec::new();
+        let mut damaged = Vec::new();
+
+        for (file_index, description) in self.files.iter().enumerate() {
+            let data = files.get(&description.name).map_or(&[][..], Vec::as_slice);
+            for slice_index in 0..self.checksums[file_index].len() {
+                let slice = self.slice(file_index, slice_index, data);
+                if !self.check(file_index, slice_index, &slice) {
+                    damaged.push((file_index, slice_index));
+                }
+                slices.push(slice);
+            }
+        }
+
+        if damaged.len() > self.recovery.len() {
+            return Err(Error::NotEnoughShards {
+                original_count: slices.len(),
+                original_received_count: slices.len() - damaged.len(),
+                recovery_received_count: self.recovery.len(),
+            });
+        }
+
+        if !damaged.is_empty() {
+            self.restore(files, &slices, &damaged)?;
+        }
+
+        for description in &self.files {
+            let data = files.entry(description.name.clone()).or_default();
+            data.resize(description.length as usize, 0);
+        }
+
+        Ok(damaged.len())
+    }
+
+    /// Returns slice size in bytes.
+    pub fn slice_size(&self) -> usize {
+        self.slice_size
+    }
+
+    /// Verifies given file, returning indexes of its damaged slices.
+    ///
+    /// - Returns `None` if file isn't part of this recovery set.
+    /// - Slices beyond the end of `data` are damaged.
+    pub fn verify_file(&self, name: &str, data: &[u8]) -> Option<Vec<usize>> {
+        let file_index = self.files.iter().position(|x| x.name == name)?;
+
+        Some(
+            (0..self.checksums[file_index].len())
+                .filter(|&slice_index| {
+                    !self.check(
+                        file_index,
+                        slice_index,
+                        &self.slice(file_index, slice_index, data),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+// ======================================================================
+// RecoverySet - PRIVATE
+
+impl RecoverySet {
+    fn check(&self, file_index: usize, slice_index: usize, slice: &[u8]) -> bool {
+        let (md5, crc32) = self.checksums[file_index][slice_index];
+        hash::crc32(slice) == crc32 && hash::md5(slice) == md5
+    }
+
+    // Returns zero-padded slice of `data`, ignoring anything past file length.
+    fn slice(&self, file_index: usize, slice_index: usize, data: &[u8]) -> Vec<u8> {
+        let length = (self.files[file_index].length as usize).min(data.len());
+        let start = (slice_index * self.slice_size).min(length);
+        let end = (start + self.slice_size).min(length);
+
+        let mut slice = data[start..end].to_vec();
+        slice.resize(self.slice_size, 0);
+        slice
+    }
+
+    // Solves and writes `damaged` slices, `slices` being all input slices.
+    fn restore(
+        &self,
+        files: &mut HashMap<String, Vec<u8>>,
+        slices: &[Vec<u8>],
+        damaged: &[(usize, usize)],
+    ) -> Result<(), Error> {
+        // SOLVE
+
+        let logs = field::input_slice_logs(slices.len());
+        let global_index = |file_index: usize, slice_index: usize| {
+            self.checksums[..file_index]
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>()
+                + slice_index
+        };
+        let missing: Vec<_> = damaged.iter().map(|&(f, s)| global_index(f, s)).collect();
+
+        let mut is_missing = FixedBitSet::with_capacity(slices.len());
+        for &i in &missing {
+            is_missing.insert(i);
+        }
+
+        // Vandermonde matrix of PAR2 can be singular, so recovery slices
+        // which are dependent on already chosen ones are skipped.
+
+        let mut chosen = field::RowEchelon::new(missing.len());
+        let mut matrix = Vec::with_capacity(missing.len());
+        let mut rhs = Vec::with_capacity(missing.len());
+
+        for (&exponent, recovery) in &self.recovery {
+            if matrix.len() == missing.len() {
+                break;
+            }
+
+            let row: Vec<_> = missing
+                .iter()
+                .map(|&i| field::pow2(logs[i], exponent))
+                .collect();
+
+            if !chosen.insert(&row) {
+                continue;
+            }
+            matrix.push(row);
+
+            let mut data = recovery.clone();
+            for (i, slice) in slices.iter().enumerate() {
+                if !is_missing.contains(i) {
+                    field::mul_add(&mut data, slice, field::pow2(logs[i], exponent));
+                }
+            }
+            rhs.push(data);
+        }
+
+        if matrix.len() < missing.len() || !field::invert(&mut matrix) {
+            return Err(Error::InvalidPar2 {
+                reason: "recovery slices are not independent",
+            });
+        }
+
+        // WRITE RESTORED SLICES
+
+        for (row, &(file_index, slice_index)) in damaged.iter().enumerate() {
+            let mut slice = vec![0; self.slice_size];
+            for (factor, data) in matrix[row].iter().zip(&rhs) {
+                field::mul_add(&mut slice, data, *factor);
+            }
+
+            if !self.check(file_index, slice_index, &slice) {
+                return Err(Error::InvalidPar2 {
+                    reason: "repaired slice doesn't match its checksum",
+                });
+            }
+
+            let description = &self.files[file_index];
+            let data = files.entry(description.name.clone()).or_default();
+            data.resize(description.length as usize, 0);
+
+            let start = slice_index * self.slice_size;
+            let end = (start + self.slice_size).min(data.len());
+            data[start..end].copy_from_slice(&slice[..end - start]);
+        }
+
+        Ok(())
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Creates PAR2 recovery set with `recovery_count` recovery slices,
+/// returning contents of a single PAR2 file with all packets.
+///
+/// - `files` are `(name, contents)` pairs.
+/// - `slice_size` must be non-zero multiple of 4.
+/// - Recovery slices have exponents `0 .. recovery_count`.
+///
+/// See [module documentation](self) for example.
+pub fn create(
+    files: &[(&str, &[u8])],
+    slice_size: usize,
+    recovery_count: usize,
+) -> Result<Vec<u8>, Error> {
+    if slice_size == 0 || !slice_size.is_multiple_of(4) {
+        return Err(Error::InvalidPar2 {
+            reason: "invalid slice size",
+        });
+    }
+
+    let mut files: Vec<_> = files
+        .iter()
+        .map(|(name, data)| (FileDescription::new(name, data), *data))
+        .collect();
+    files.sort_by_key(|(description, _)| description.file_id);
+
+    let slice_count = files
+        .iter()
+        .map(|(description, _)| description.slice_count(slice_size))
+        .sum::<usize>();
+
+    if files.is_empty() || slice_count > MAX_SLICE_COUNT || recovery_count > MAX_RECOVERY_COUNT {
+        return Err(Error::UnsupportedShardCount {
+            original_count: slice_count,
+            recovery_count,
+        });
+    }
+
+    let max_length = files
+        .iter()
+        .map(|(description, _)| description.length)
+        .max()
+        .unwrap_or(0);
+    check_slice_size_limit(slice_size, max_length)?;
+
+    // MAIN
+
+    let mut main = Vec::new();
+    main.extend_from_slice(&(slice_size as u64).to_le_bytes());
+    main.extend_from_slice(&(files.len() as u32).to_le_bytes());
+    for (description, _) in &files {
+        main.extend_from_slice(&description.file_id);
+    }
+    let set_id = hash::md5(&main);
+
+    let mut out = Vec::new();
+    packet::write(&mut out, &set_id, packet::TYPE_MAIN, &main);
+
+    // FILE DESCRIPTIONS AND CHECKSUMS
+
+    let mut slices = Vec::with_capacity(slice_count);
+
+    for (description, data) in &files {
+        let mut body = Vec::new();
+        body.extend_from_slice(&description.file_id);
+        body.extend_from_slice(&description.hash);
+        body.extend_from_slice(&description.hash_16k);
+        body.extend_from_slice(&description.length.to_le_bytes());
+        body.extend_from_slice(&packet::pad4(description.name.as_bytes()));
+        packet::write(&mut out, &set_id, packet::TYPE_FILE_DESC, &body);
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&description.file_id);
+        for chunk in data.chunks(slice_size) {
+            let mut slice = chunk.to_vec();
+            slice.resize(slice_size, 0);
+            body.extend_from_slice(&hash::md5(&slice));
+            body.extend_from_slice(&hash::crc32(&slice).to_le_bytes());
+            slices.push(slice);
+        }
+        packet::write(&mut out, &set_id, packet::TYPE_IFSC, &body);
+    }
+
+    // RECOVERY SLICES
+
+    let logs = field::input_slice_logs(slices.len());
+
+    for exponent in 0..recovery_count as u32 {
+        let mut body = vec![0; 4 + slice_size];
+        body[..4].copy_from_slice(&exponent.to_le_bytes());
+        for (slice, &log) in slices.iter().zip(&logs) {
+            field::mul_add(&mut body[4..], slice, field::pow2(log, exponent));
+        }
+        packet::write(&mut out, &set_id, packet::TYPE_RECOVERY_SLICE, &body);
+    }
+
+    // CREATOR
+
+    let creator = concat!("reed-solomon-16 ", env!("CARGO_PKG_VERSION"));
+    packet::write(
+        &mut out,
+        &set_id,
+        packet::TYPE_CREATOR,
+        &packet::pad4(creator.as_bytes()),
+    );
+
+    Ok(out)
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Returns error if `slice_size` is larger than the longest file
+// with length `max_length`, rounded up to multiple of 4,
+// or larger than `MAX_SLICE_SIZE`.
+fn check_slice_size_limit(slice_size: usize, max_length: u64) -> Result<(), Error> {
+    let max_length = max_length.checked_next_multiple_of(4).unwrap_or(u64::MAX);
+
+    if slice_size > MAX_SLICE_SIZE || slice_size as u64 > max_length {
+        Err(Error::InvalidPar2 {
+            reason: "slice size too large",
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::test_util;
+
+    fn sample_files() -> Vec<(String, Vec<u8>)> {
+        vec![
+            (
+                "first.bin".to_string(),
+                test_util::generate_original(1, 20000, 0).remove(0),
+            ),
+            (
+                "second.bin".to_string(),
+                test_util::generate_original(1, 1001, 1).remove(0),
+            ),
+            ("empty.bin".to_string(), Vec::new()),
+        ]
+    }
+
+    fn create_sample(
+        slice_size: usize,
+        recovery_count: usize,
+    ) -> (Vec<u8>, HashMap<String, Vec<u8>>) {
+        let files = sample_files();
+        let refs: Vec<_> = files
+            .iter()
+            .map(|(n, d)| (n.as_str(), d.as_slice()))
+            .collect();
+        let par2 = create(&refs, slice_size, recovery_count).unwrap();
+        (par2, files.into_iter().collect())
+    }
+
+    // ============================================================
+    // create / parse
+
+    #[test]
+    fn parse_created() {
+        let (par2, files) = create_sample(1024, 5);
+        let set = RecoverySet::parse([&par2]).unwrap();
+
+        assert_eq!(set.slice_size(), 1024);
+        assert_eq!(set.recovery_slice_count(), 5);
+        assert_eq!(set.files().len(), 3);
+        assert!(set.files().windows(2).all(|w| w[0].file_id < w[1].file_id));
+
+        for description in set.files() {
+            let data = &files[&description.name];
+            assert_eq!(description.length, data.len() as u64);
+            assert_eq!(description.hash, hash::md5(data));
+            assert_eq!(set.verify_file(&description.name, data), Some(vec![]));
+        }
+
+        assert_eq!(set.verify_file("unknown", &[]), None);
+    }
+
+    #[test]
+    fn parse_split_and_damaged() {
+        let (par2, _) = create_sample(1024, 5);
+
+        // Split packets over two "files" and damage one recovery slice.
+        let packets = packet::parse(&par2);
+        let split = packets.len() - 3;
+        let offset: usize = packets[..split]
+            .iter()
+            .map(|p| packet::HEADER_BYTES + p.body.len())
+            .sum();
+
+        let mut volume = par2[offset..].to_vec();
+        volume[packet::HEADER_BYTES + 10] ^= 1;
+
+        let set = RecoverySet::parse([&par2[..offset], &volume]).unwrap();
+        assert_eq!(set.recovery_slice_count(), 4);
+    }
+
+    #[test]
+    fn parse_without_main() {
+        assert_eq!(
+            RecoverySet::parse([[0u8; 100]]).unwrap_err(),
+            Error::InvalidPar2 {
+                reason: "main packet not found"
+            }
+        );
+    }
+
+    // Returns `par2` with slice size changed to `slice_size`
+    // and length of every file changed to `length`.
+    fn rewrite(par2: &[u8], slice_size: u64, length: u64) -> Vec<u8> {
+        let packets = packet::parse(par2);
+
+        let main = packets
+            .iter()
+            .find(|p| &p.kind == packet::TYPE_MAIN)
+            .unwrap();
+        let mut main_body = main.body.to_vec();
+        main_body[..8].copy_from_slice(&slice_size.to_le_bytes());
+        let set_id = hash::md5(&main_body);
+
+        let mut out = Vec::new();
+        for p in &packets {
+            let mut body = p.body.to_vec();
+            if &p.kind == packet::TYPE_MAIN {
+                body = main_body.clone();
+            } else if &p.kind == packet::TYPE_FILE_DESC {
+                body[48..56].copy_from_slice(&length.to_le_bytes());
+            }
+            packet::write(&mut out, &set_id, &p.kind, &body);
+        }
+        out
+    }
+
+    #[test]
+    fn parse_slice_size_too_large() {
+        let (par2, _) = create_sample(1024, 2);
+        let too_large = Err(Error::InvalidPar2 {
+            reason: "slice size too large",
+        });
+
+        // Largest file has 20000 bytes, so slice size 20000 passes
+        // size check, but checksums no longer match slice counts.
+        assert_eq!(
+            RecoverySet::parse([rewrite(&par2, 20000, 20000)]).map(|_| ()),
+            Err(Error::InvalidPar2 {
+                reason: "wrong number of input file slice checksums",
+            })
+        );
+        assert_eq!(
+            RecoverySet::parse([rewrite(&par2, 20004, 20000)]).map(|_| ()),
+            too_large
+        );
+        assert_eq!(
+            RecoverySet::parse([rewrite(&par2, u64::MAX - 3, 20000)]).map(|_| ()),
+            too_large
+        );
+
+        // Files are long enough, but slice size exceeds `MAX_SLICE_SIZE`.
+        assert_eq!(
+            RecoverySet::parse([rewrite(&par2, 1 << 32, 1 << 40)]).map(|_| ()),
+            too_large
+        );
+
+        assert_eq!(create(&[("a", &[1, 2, 3])], 8, 1).map(|_| ()), too_large);
+    }
+
+    #[test]
+    fn create_invalid() {
+        assert!(matches!(
+            create(&[("a", &[1, 2, 3])], 6, 1),
+            Err(Error::InvalidPar2 { .. })
+        ));
+        assert!(matches!(
+            create(&[], 4, 1),
+            Err(Error::UnsupportedShardCount { .. })
+        ));
+    }
+
+    // ============================================================
+    // verify_file / repair
+
+    #[test]
+    fn repair() {
+        let (par2, original) = create_sample(1024, 5);
+        let set = RecoverySet::parse([&par2]).unwrap();
+
+        let mut files = original.clone();
+        files.get_mut("first.bin").unwrap()[0] ^= 1;
+        files.get_mut("first.bin").unwrap()[19999] ^= 1;
+        files.get_mut("second.bin").unwrap().truncate(500);
+        files.remove("empty.bin");
+
+        assert_eq!(
+            set.verify_file("first.bin", &files["first.bin"]),
+            Some(vec![0, 19])
+        );
+        assert_eq!(
+            set.verify_file("second.bin", &files["second.bin"]),
+            Some(vec![0])
+        );
+
+        assert_eq!(set.repair(&mut files).unwrap(), 3);
+        assert_eq!(files, original);
+
+        assert_eq!(set.repair(&mut files).unwrap(), 0);
+    }
+
+    #[test]
+    fn repair_missing_file() {
+        let (par2, original) = create_sample(4096, 5);
+        let set = RecoverySet::parse([&par2]).unwrap();
+
+        let mut files = original.clone();
+        files.remove("first.bin");
+
+        assert_eq!(set.repair(&mut files).unwrap(), 5);
+        assert_eq!(files, original);
+    }
+
+    #[test]
+    fn repair_skips_dependent_recovery_slices() {
+        // Input slices with constants `2^1` and `2^256` have
+        // `(2^255)^257 = 1`, so recovery slices with exponents
+        // `0` and `257` are dependent when both slices are missing.
+        let logs = field::input_slice_logs(200);
+        let last = logs.iter().position(|&log| log == 256).unwrap();
+
+        let data = test_util::generate_original(1, (last + 1) * 4, 2).remove(0);
+        let par2 = create(&[("data.bin", &data)], 4, 259).unwrap();
+        let mut set = RecoverySet::parse([&par2]).unwrap();
+        set.recovery
+            .retain(|exponent, _| [0, 257, 258].contains(exponent));
+
+        let mut files = HashMap::new();
+        let mut damaged = data.clone();
+        damaged[0] ^= 1;
+        damaged[last * 4] ^= 1;
+        files.insert("data.bin".to_string(), damaged);
+
+        assert_eq!(set.repair(&mut files).unwrap(), 2);
+        assert_eq!(files["data.bin"], data);
+    }
+
+    #[test]
+    fn repair_not_enough() {
+        let (par2, original) = create_sample(1024, 2);
+        let set = RecoverySet::parse([&par2]).unwrap();
+
+        let mut files = original.clone();
+        files.get_mut("first.bin").unwrap()[0] ^= 1;
+        files.get_mut("first.bin").unwrap()[5000] ^= 1;
+        files.get_mut("second.bin").unwrap().clear();
+        files.remove("empty.bin");
+
+        assert_eq!(
+            set.repair(&mut files),
+            Err(Error::NotEnoughShards {
+                original_count: 21,
+                original_received_count: 18,
+                recovery_received_count: 2,
+            })
+        );
+    }
+
+    // ============================================================
+    // par2cmdline fixtures
+
+    // Parameters of `tests/fixtures/par2/generate.sh`.
+    const FIXTURE_SLICE_SIZE: usize = 1024;
+    const FIXTURE_RECOVERY_COUNT: usize = 5;
+
+    // Returns input files and contents of PAR2 files of fixture.
+    fn read_fixture() -> (HashMap<String, Vec<u8>>, Vec<Vec<u8>>) {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/par2");
+
+        let mut files = HashMap::new();
+        for name in ["first.bin", "second.bin"] {
+            let path = dir.join(name);
+            let data =
+                std::fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+            files.insert(name.to_string(), data);
+        }
+
+        let mut par2_files = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|x| x == "par2") {
+                par2_files.push(std::fs::read(path).unwrap());
+            }
+        }
+        assert!(!par2_files.is_empty(), "{}: no PAR2 files", dir.display());
+
+        (files, par2_files)
+    }
+
+    // Returns set ID, type and body of packets of all PAR2 files
+    // except creator packets, which identify the program used.
+    fn packets_of(par2_files: &[Vec<u8>]) -> BTreeSet<([u8; 16], [u8; 16], Vec<u8>)> {
+        par2_files
+            .iter()
+            .flat_map(|data| packet::parse(data))
+            .filter(|p| &p.kind != packet::TYPE_CREATOR)
+            .map(|p| (p.set_id, p.kind, p.body.to_vec()))
+            .collect()
+    }
+
+    // Requires fixtures generated with par2cmdline, see `read_fixture`.
+    #[test]
+    #[ignore]
+    fn parse_and_repair_par2cmdline() {
+        let (original, par2_files) = read_fixture();
+        let set = RecoverySet::parse(&par2_files).unwrap();
+
+        let mut names: Vec<_> = set.files().iter().map(|x| x.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["first.bin", "second.bin"]);
+        for description in set.files() {
+            let data = &original[&description.name];
+            assert_eq!(description.length, data.len() as u64);
+            assert_eq!(description.hash, hash::md5(data));
+            assert_eq!(set.verify_file(&description.name, data), Some(vec![]));
+        }
+
+        let mut files = original.clone();
+        files.get_mut("first.bin").unwrap()[0] ^= 1;
+        files.get_mut("first.bin").unwrap()[19999] ^= 1;
+        files.remove("second.bin");
+
+        assert_eq!(set.repair(&mut files).unwrap(), 3);
+        assert_eq!(files, original);
+    }
+
+    // Requires fixtures generated with par2cmdline, see `read_fixture`.
+    #[test]
+    #[ignore]
+    fn create_matches_par2cmdline() {
+        let (original, par2_files) = read_fixture();
+        let refs: Vec<_> = original
+            .iter()
+            .map(|(n, d)| (n.as_str(), d.as_slice()))
+            .collect();
+
+        let par2 = create(&refs, FIXTURE_SLICE_SIZE, FIXTURE_RECOVERY_COUNT).unwrap();
+
+        // Main, file description, input file slice checksum and
+        // recovery slice packets, latter identified by exponent.
+        assert_eq!(packets_of(&[par2]), packets_of(&par2_files));
+    }
+}
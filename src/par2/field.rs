@@ -0,0 +1,209 @@
+//! `GF(2^16)` arithmetic of PAR2, using [`tables::initialize_par2_exp_log`].
+
+use crate::engine::{
+    tables::{self, initialize_par2_exp_log},
+    GfElement, GF_MODULUS,
+};
+
+// ======================================================================
+// RowEchelon - CRATE
+
+/// Rows in row echelon form, for checking linear independence of rows.
+pub(crate) struct RowEchelon {
+    columns: usize,
+    // `(pivot column, row)` where row is normalized to have `1` at pivot.
+    rows: Vec<(usize, Vec<GfElement>)>,
+}
+
+impl RowEchelon {
+    /// Creates empty set of rows with given number of `columns`.
+    pub(crate) fn new(columns: usize) -> Self {
+        Self {
+            columns,
+            rows: Vec::with_capacity(columns),
+        }
+    }
+
+    /// Adds `row` if it is linearly independent of previously added rows,
+    /// returning `true` if it was added.
+    pub(crate) fn insert(&mut self, row: &[GfElement]) -> bool {
+        debug_assert_eq!(row.len(), self.columns);
+
+        let mut row = row.to_vec();
+        for (pivot, prev) in &self.rows {
+            let factor = row[*pivot];
+            if factor != 0 {
+                for (x, y) in row.iter_mut().zip(prev) {
+                    *x ^= mul(*y, factor);
+                }
+            }
+        }
+
+        let Some(pivot) = row.iter().position(|&x| x != 0) else {
+            return false;
+        };
+
+        let factor = inv(row[pivot]);
+        for x in &mut row {
+            *x = mul(*x, factor);
+        }
+
+        self.rows.push((pivot, row));
+        true
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+/// Returns `x * y`.
+pub(crate) fn mul(x: GfElement, y: GfElement) -> GfElement {
+    if y == 0 {
+        0
+    } else {
+        let (exp, log) = initialize_par2_exp_log();
+        tables::mul(x, log[y as usize], exp, log)
+    }
+}
+
+/// Returns `1 / x`, `x` must be non-zero.
+pub(crate) fn inv(x: GfElement) -> GfElement {
+    debug_assert_ne!(x, 0);
+    let (exp, log) = initialize_par2_exp_log();
+    exp[(GF_MODULUS - log[x as usize]) as usize]
+}
+
+/// Returns `2^(log * exponent)`.
+pub(crate) fn pow2(log: GfElement, exponent: u32) -> GfElement {
+    let (exp, _) = initialize_par2_exp_log();
+    let log = (log as u64 * exponent as u64) % GF_MODULUS as u64;
+    exp[log as usize]
+}
+
+/// Returns logarithms of PAR2 input slice constants,
+/// i.e. first `count` values `n` coprime to `65535`.
+pub(crate) fn input_slice_logs(count: usize) -> Vec<GfElement> {
+    (1..GF_MODULUS)
+        .filter(|n| n % 3 != 0 && n % 5 != 0 && n % 17 != 0 && n % 257 != 0)
+        .take(count)
+        .collect()
+}
+
+/// `x[] ^= y[] * factor` where `x` and `y` are
+/// little-endian 16-bit words.
+pub(crate) fn mul_add(x: &mut [u8], y: &[u8], factor: GfElement) {
+    debug_assert_eq!(x.len(), y.len());
+    debug_assert!(x.len().is_multiple_of(2));
+
+    if factor == 0 {
+        return;
+    }
+
+    let mut lo = [0; 256];
+    let mut hi = [0; 256];
+    for i in 0..256 {
+        lo[i] = mul(i as GfElement, factor);
+        hi[i] = mul((i << 8) as GfElement, factor);
+    }
+
+    for (x, y) in x.chunks_exact_mut(2).zip(y.chunks_exact(2)) {
+        let prod = lo[y[0] as usize] ^ hi[y[1] as usize];
+        x[0] ^= prod as u8;
+        x[1] ^= (prod >> 8) as u8;
+    }
+}
+
+/// Inverts square `matrix` in-place with Gauss-Jordan elimination,
+/// returning `false` if it is singular.
+pub(crate) fn invert(matrix: &mut [Vec<GfElement>]) -> bool {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<GfElement>> = (0..n)
+        .map(|i| (0..n).map(|j| (i == j) as GfElement).collect())
+        .collect();
+
+    for col in 0..n {
+        let Some(pivot) = (col..n).find(|&row| matrix[row][col] != 0) else {
+            return false;
+        };
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let factor = inv(matrix[col][col]);
+        for j in 0..n {
+            matrix[col][j] = mul(matrix[col][j], factor);
+            inverse[col][j] = mul(inverse[col][j], factor);
+        }
+
+        for row in 0..n {
+            let factor = matrix[row][col];
+            if row != col && factor != 0 {
+                for j in 0..n {
+                    matrix[row][j] ^= mul(matrix[col][j], factor);
+                    inverse[row][j] ^= mul(inverse[col][j], factor);
+                }
+            }
+        }
+    }
+
+    matrix.clone_from_slice(&inverse);
+    true
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::GF_ORDER;
+
+    #[test]
+    fn generator_has_full_order() {
+        let (exp, log) = initialize_par2_exp_log();
+        assert_eq!(exp[0], 1);
+        assert_eq!(exp[16], 0x100B);
+        for x in 1..GF_ORDER {
+            assert_eq!(exp[log[x] as usize] as usize, x);
+        }
+    }
+
+    #[test]
+    fn input_slice_constants() {
+        // From PAR2 specification.
+        assert_eq!(&input_slice_logs(8), &[1, 2, 4, 7, 8, 11, 13, 14]);
+        assert_eq!(input_slice_logs(40000).len(), 32768);
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn invert_vandermonde() {
+        let logs = input_slice_logs(5);
+        let original: Vec<Vec<GfElement>> = (0..5)
+            .map(|e| logs.iter().map(|&log| pow2(log, e)).collect())
+            .collect();
+
+        let mut inverse = original.clone();
+        assert!(invert(&mut inverse));
+
+        for i in 0..5 {
+            for j in 0..5 {
+                let x = (0..5).fold(0, |acc, k| acc ^ mul(original[i][k], inverse[k][j]));
+                assert_eq!(x, (i == j) as GfElement);
+            }
+        }
+
+        assert!(!invert(&mut [vec![1, 2], vec![1, 2]]));
+    }
+
+    #[test]
+    fn row_echelon() {
+        let mut rows = RowEchelon::new(3);
+        assert!(rows.insert(&[0, 1, 2]));
+        assert!(rows.insert(&[3, 0, 0]));
+        assert!(!rows.insert(&[0, 5, mul(5, 2)]));
+        assert!(!rows.insert(&[6, 7, mul(7, 2)]));
+        assert!(!rows.insert(&[0, 0, 0]));
+        assert!(rows.insert(&[0, 0, 1]));
+        assert!(!rows.insert(&[1, 2, 3]));
+    }
+}
@@ -0,0 +1,150 @@
+//! PAR2 packet reading and writing.
+
+use crate::par2::hash;
+
+// ======================================================================
+// CONST - CRATE
+
+pub(crate) const HEADER_BYTES: usize = 64;
+
+pub(crate) const TYPE_MAIN: &[u8; 16] = b"PAR 2.0\0Main\0\0\0\0";
+pub(crate) const TYPE_FILE_DESC: &[u8; 16] = b"PAR 2.0\0FileDesc";
+pub(crate) const TYPE_IFSC: &[u8; 16] = b"PAR 2.0\0IFSC\0\0\0\0";
+pub(crate) const TYPE_RECOVERY_SLICE: &[u8; 16] = b"PAR 2.0\0RecvSlic";
+pub(crate) const TYPE_CREATOR: &[u8; 16] = b"PAR 2.0\0Creator\0";
+
+// ======================================================================
+// CONST - PRIVATE
+
+const MAGIC: &[u8; 8] = b"PAR2\0PKT";
+
+// ======================================================================
+// Packet - CRATE
+
+/// Packet with valid header and hash.
+pub(crate) struct Packet<'a> {
+    pub(crate) set_id: [u8; 16],
+    pub(crate) kind: [u8; 16],
+    pub(crate) body: &'a [u8],
+}
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+/// Returns all valid packets in `data`.
+///
+/// Damaged packets are skipped by searching next magic,
+/// like PAR2 clients do.
+pub(crate) fn parse(data: &[u8]) -> Vec<Packet<'_>> {
+    let mut packets = Vec::new();
+    let mut pos = 0;
+
+    while pos + HEADER_BYTES <= data.len() {
+        match parse_one(&data[pos..]) {
+            Some((packet, len)) => {
+                packets.push(packet);
+                pos += len;
+            }
+            None => pos += 4,
+        }
+    }
+
+    packets
+}
+
+/// Appends packet with given `set_id`, `kind` and `body` to `out`.
+///
+/// `body.len()` must be multiple of 4.
+pub(crate) fn write(out: &mut Vec<u8>, set_id: &[u8; 16], kind: &[u8; 16], body: &[u8]) {
+    debug_assert!(body.len().is_multiple_of(4));
+
+    let mut hasher = hash::Md5::new();
+    hasher.update(set_id);
+    hasher.update(kind);
+    hasher.update(body);
+
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&((HEADER_BYTES + body.len()) as u64).to_le_bytes());
+    out.extend_from_slice(&hasher.finalize());
+    out.extend_from_slice(set_id);
+    out.extend_from_slice(kind);
+    out.extend_from_slice(body);
+}
+
+/// Returns `bytes` zero-padded to a multiple of 4.
+pub(crate) fn pad4(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().next_multiple_of(4), 0);
+    padded
+}
+
+/// Returns `bytes` with trailing zero padding removed.
+pub(crate) fn unpad(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+    &bytes[..len]
+}
+
+pub(crate) fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+pub(crate) fn read_u64(bytes: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap())
+}
+
+pub(crate) fn read_id(bytes: &[u8], pos: usize) -> [u8; 16] {
+    bytes[pos..pos + 16].try_into().unwrap()
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn parse_one(data: &[u8]) -> Option<(Packet<'_>, usize)> {
+    if &data[..8] != MAGIC {
+        return None;
+    }
+
+    let len = usize::try_from(read_u64(data, 8)).ok()?;
+    if len < HEADER_BYTES || !len.is_multiple_of(4) || len > data.len() {
+        return None;
+    }
+
+    if hash::md5(&data[32..len]) != data[16..32] {
+        return None;
+    }
+
+    Some((
+        Packet {
+            set_id: read_id(data, 32),
+            kind: read_id(data, 48),
+            body: &data[HEADER_BYTES..len],
+        },
+        len,
+    ))
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_and_skip_damaged() {
+        let set_id = [7; 16];
+
+        let mut data = Vec::new();
+        write(&mut data, &set_id, TYPE_CREATOR, &pad4(b"first"));
+        write(&mut data, &set_id, TYPE_CREATOR, &pad4(b"second"));
+        write(&mut data, &set_id, TYPE_CREATOR, &pad4(b"third"));
+        data[HEADER_BYTES + 72 + 2] ^= 1;
+
+        let packets = parse(&data);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(unpad(packets[0].body), b"first");
+        assert_eq!(unpad(packets[1].body), b"third");
+        assert_eq!(packets[1].set_id, set_id);
+        assert_eq!(&packets[1].kind, TYPE_CREATOR);
+    }
+}
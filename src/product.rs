@@ -0,0 +1,624 @@
+//! Two-dimensional product code over a grid of shards.
+//!
+//! Original shards are arranged in a grid of `rows` x `columns` shards
+//! which is extended with recovery shards in both dimensions:
+//!
+//! ```text
+//!            columns   row_recovery
+//!         +----------+-----+
+//!    rows | original | R   |
+//!         +----------+-----+
+//! column  | C        | C*R |
+//! recovery|          |     |
+//!         +----------+-----+
+//! ```
+//!
+//! - Each of the `rows` original rows is encoded with
+//!   [`DefaultRateEncoder`], generating `row_recovery` shards (`R`).
+//! - Then each of the `columns + row_recovery` columns is encoded,
+//!   generating `column_recovery` shards (`C` and `C*R`).
+//! - Because encoding is linear, every row and every column of
+//!   the resulting grid is a Reed-Solomon codeword.
+//!
+//! Shards are identified by their `(row, column)` position in the full grid,
+//! i.e. `row < rows + column_recovery` and `column < columns + row_recovery`.
+//! Positions with `row < rows` and `column < columns` are original shards.
+//!
+//! [`ProductDecoder`] decodes iteratively, alternating decoding of rows
+//! and columns, each of which can restore shards needed by the other.
+//! This recovers many loss patterns where a single row or column loses
+//! more shards than it has recovery shards, e.g. loss of whole rows.
+//! Loss patterns where every affected row and column loses
+//! too many shards can't be recovered.
+//!
+//! Rows share a single [`DefaultRateEncoder`] / [`DefaultRateDecoder`]
+//! and so do columns, so working space is allocated only once per dimension.
+//!
+//! # Example
+//!
+//! ```rust
+//! use reed_solomon_16::product::{ProductDecoder, ProductEncoder};
+//!
+//! // 3 x 4 original shards, 1 recovery shard per row and per column.
+//! let original: Vec<[u8; 64]> = (0..12u8).map(|i| [i; 64]).collect();
+//!
+//! let mut encoder = ProductEncoder::new(3, 4, 1, 1, 64)?;
+//! for shard in &original {
+//!     encoder.add_original_shard(shard)?;
+//! }
+//! let result = encoder.encode()?;
+//!
+//! // Lose the whole first row, which has only 1 recovery shard.
+//! let mut decoder = ProductDecoder::new(3, 4, 1, 1, 64)?;
+//! for row in 1..3 {
+//!     for column in 0..4 {
+//!         decoder.add_shard(row, column, &original[row * 4 + column])?;
+//!     }
+//! }
+//! for column in 0..5 {
+//!     decoder.add_shard(3, column, result.recovery(3, column).unwrap())?;
+//! }
+//!
+//! let result = decoder.decode()?;
+//! assert_eq!(result.restored_original(0, 2).unwrap(), original[2]);
+//! # Ok::<(), reed_solomon_16::Error>(())
+//! ```
+//!
+//! [`DefaultRateDecoder`]: crate::rate::DefaultRateDecoder
+//! [`DefaultRateEncoder`]: crate::rate::DefaultRateEncoder
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    engine::DefaultEngine,
+    rate::{DefaultRateDecoder, DefaultRateEncoder, RateDecoder, RateEncoder},
+    Error,
+};
+
+// ======================================================================
+// Grid - PRIVATE
+
+// Shards of the full grid in row-major order.
+struct Grid {
+    rows: usize,
+    columns: usize,
+    row_recovery: usize,
+    column_recovery: usize,
+    shard_bytes: usize,
+    data: Vec<u8>,
+}
+
+impl Grid {
+    fn new(
+        rows: usize,
+        columns: usize,
+        row_recovery: usize,
+        column_recovery: usize,
+        shard_bytes: usize,
+    ) -> Self {
+        let count = (rows + column_recovery) * (columns + row_recovery);
+        Self {
+            rows,
+            columns,
+            row_recovery,
+            column_recovery,
+            shard_bytes,
+            data: vec![0; count * shard_bytes],
+        }
+    }
+
+    fn full_columns(&self) -> usize {
+        self.columns + self.row_recovery
+    }
+
+    fn full_rows(&self) -> usize {
+        self.rows + self.column_recovery
+    }
+
+    // Returns position of given shard or `None` if it's outside of grid.
+    fn pos(&self, row: usize, column: usize) -> Option<usize> {
+        if row < self.full_rows() && column < self.full_columns() {
+            Some(row * self.full_columns() + column)
+        } else {
+            None
+        }
+    }
+
+    fn shard(&self, pos: usize) -> &[u8] {
+        &self.data[pos * self.shard_bytes..(pos + 1) * self.shard_bytes]
+    }
+
+    fn shard_mut(&mut self, pos: usize) -> &mut [u8] {
+        &mut self.data[pos * self.shard_bytes..(pos + 1) * self.shard_bytes]
+    }
+
+    // Returns positions of shards of given row.
+    fn row(&self, row: usize) -> Vec<usize> {
+        (0..self.full_columns())
+            .map(|column| row * self.full_columns() + column)
+            .collect()
+    }
+
+    // Returns positions of shards of given column.
+    fn column(&self, column: usize) -> Vec<usize> {
+        (0..self.full_rows())
+            .map(|row| row * self.full_columns() + column)
+            .collect()
+    }
+}
+
+// ======================================================================
+// ProductEncoder - PUBLIC
+
+/// Encoder of two-dimensional product code.
+///
+/// See [module documentation](crate::product) for details.
+pub struct ProductEncoder {
+    grid: Grid,
+    original_received_count: usize,
+    row_encoder: DefaultRateEncoder<DefaultEngine>,
+    column_encoder: DefaultRateEncoder<DefaultEngine>,
+}
+
+impl ProductEncoder {
+    /// Adds one original shard to the encoder.
+    ///
+    /// Original shards are added in row-major order, i.e.
+    /// `n`:th added shard has position `(n / columns, n % columns)`.
+    pub fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error> {
+        let original_shard = original_shard.as_ref();
+        let original_count = self.grid.rows * self.grid.columns;
+
+        if self.original_received_count == original_count {
+            Err(Error::TooManyOriginalShards { original_count })
+        } else if original_shard.len() != self.grid.shard_bytes {
+            Err(Error::DifferentShardSize {
+                shard_bytes: self.grid.shard_bytes,
+                got: original_shard.len(),
+            })
+        } else {
+            let row = self.original_received_count / self.grid.columns;
+            let column = self.original_received_count % self.grid.columns;
+            let pos = self.grid.pos(row, column).unwrap();
+            self.grid.shard_mut(pos).copy_from_slice(original_shard);
+            self.original_received_count += 1;
+            Ok(())
+        }
+    }
+
+    /// Encodes the added original shards returning [`ProductEncoderResult`]
+    /// which contains the generated recovery shards.
+    ///
+    /// Encoder is reset and ready for new round of encoding
+    /// when this returns.
+    pub fn encode(&mut self) -> Result<ProductEncoderResult<'_>, Error> {
+        let original_count = self.grid.rows * self.grid.columns;
+        if self.original_received_count < original_count {
+            return Err(Error::TooFewOriginalShards {
+                original_count,
+                original_received_count: self.original_received_count,
+            });
+        }
+        self.original_received_count = 0;
+
+        let (rows, columns) = (self.grid.rows, self.grid.columns);
+
+        for row in 0..rows {
+            let positions = self.grid.row(row);
+            encode_line(&mut self.row_encoder, &mut self.grid, &positions, columns)?;
+        }
+
+        for column in 0..self.grid.full_columns() {
+            let positions = self.grid.column(column);
+            encode_line(&mut self.column_encoder, &mut self.grid, &positions, rows)?;
+        }
+
+        Ok(ProductEncoderResult { grid: &self.grid })
+    }
+
+    /// Creates new encoder with given configuration
+    /// and allocates required working space.
+    ///
+    /// - Each row has `columns` original shards and `row_recovery` recovery shards.
+    /// - Each column has `rows` original shards and `column_recovery` recovery shards.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        row_recovery: usize,
+        column_recovery: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        let engine = DefaultEngine::new();
+        let row_encoder =
+            DefaultRateEncoder::new(columns, row_recovery, shard_bytes, engine.clone(), None)?;
+        let column_encoder =
+            DefaultRateEncoder::new(rows, column_recovery, shard_bytes, engine, None)?;
+
+        Ok(Self {
+            grid: Grid::new(rows, columns, row_recovery, column_recovery, shard_bytes),
+            original_received_count: 0,
+            row_encoder,
+            column_encoder,
+        })
+    }
+}
+
+// ======================================================================
+// ProductEncoderResult - PUBLIC
+
+/// Result of product encoding. Contains the generated recovery shards.
+///
+/// This struct is created by [`ProductEncoder::encode`].
+pub struct ProductEncoderResult<'a> {
+    grid: &'a Grid,
+}
+
+impl<'a> ProductEncoderResult<'a> {
+    /// Returns recovery shard at given position or `None` if
+    /// the position is outside of grid or contains an original shard.
+    pub fn recovery(&self, row: usize, column: usize) -> Option<&[u8]> {
+        if row < self.grid.rows && column < self.grid.columns {
+            None
+        } else {
+            Some(self.grid.shard(self.grid.pos(row, column)?))
+        }
+    }
+}
+
+// ======================================================================
+// ProductDecoder - PUBLIC
+
+/// Decoder of two-dimensional product code.
+///
+/// See [module documentation](crate::product) for details.
+pub struct ProductDecoder {
+    grid: Grid,
+    // Shards which are known, either received or restored.
+    known: Vec<bool>,
+    received: Vec<bool>,
+    row_decoder: DefaultRateDecoder<DefaultEngine>,
+    column_decoder: DefaultRateDecoder<DefaultEngine>,
+}
+
+impl ProductDecoder {
+    /// Adds one shard at given position to the decoder.
+    ///
+    /// Shards can be added in any order.
+    pub fn add_shard<T: AsRef<[u8]>>(
+        &mut self,
+        row: usize,
+        column: usize,
+        shard: T,
+    ) -> Result<(), Error> {
+        let shard = shard.as_ref();
+
+        if let Some(pos) = self.grid.pos(row, column) {
+            if self.received[pos] {
+                Err(Error::DuplicateShardPosition { row, column })
+            } else if shard.len() != self.grid.shard_bytes {
+                Err(Error::DifferentShardSize {
+                    shard_bytes: self.grid.shard_bytes,
+                    got: shard.len(),
+                })
+            } else {
+                self.grid.shard_mut(pos).copy_from_slice(shard);
+                self.known[pos] = true;
+                self.received[pos] = true;
+                Ok(())
+            }
+        } else {
+            Err(Error::InvalidShardPosition {
+                rows: self.grid.full_rows(),
+                columns: self.grid.full_columns(),
+                row,
+                column,
+            })
+        }
+    }
+
+    /// Decodes the added shards returning [`ProductDecoderResult`]
+    /// which contains the restored original shards.
+    ///
+    /// Rows and columns are decoded alternately until all original shards
+    /// are restored or no more progress can be made, in which case
+    /// [`Error::NotEnoughShards`] is returned. Added shards are kept in that case.
+    ///
+    /// When returned [`ProductDecoderResult`] is dropped the decoder is
+    /// automatically reset and ready for new round of decoding.
+    pub fn decode(&mut self) -> Result<ProductDecoderResult<'_>, Error> {
+        let (rows, columns) = (self.grid.rows, self.grid.columns);
+
+        loop {
+            let mut progress = false;
+
+            for row in 0..self.grid.full_rows() {
+                let positions = self.grid.row(row);
+                progress |= decode_line(
+                    &mut self.row_decoder,
+                    &mut self.grid,
+                    &mut self.known,
+                    &positions,
+                    columns,
+                )?;
+            }
+
+            for column in 0..self.grid.full_columns() {
+                let positions = self.grid.column(column);
+                progress |= decode_line(
+                    &mut self.column_decoder,
+                    &mut self.grid,
+                    &mut self.known,
+                    &positions,
+                    rows,
+                )?;
+            }
+
+            if !progress {
+                break;
+            }
+        }
+
+        let original_count = self.grid.rows * self.grid.columns;
+        let original_known = (0..self.grid.rows)
+            .flat_map(|row| (0..self.grid.columns).map(move |column| (row, column)))
+            .all(|(row, column)| self.known[self.grid.pos(row, column).unwrap()]);
+
+        if !original_known {
+            let mut original_received_count = 0;
+            for (pos, received) in self.received.iter().enumerate() {
+                let row = pos / self.grid.full_columns();
+                let column = pos % self.grid.full_columns();
+                if *received && row < self.grid.rows && column < self.grid.columns {
+                    original_received_count += 1;
+                }
+            }
+            let recovery_received_count =
+                self.received.iter().filter(|r| **r).count() - original_received_count;
+
+            // Forget restored shards so that retrying
+            // after adding more shards starts from received shards.
+            self.known.copy_from_slice(&self.received);
+
+            return Err(Error::NotEnoughShards {
+                original_count,
+                original_received_count,
+                recovery_received_count,
+            });
+        }
+
+        Ok(ProductDecoderResult { decoder: self })
+    }
+
+    /// Creates new decoder with given configuration
+    /// and allocates required working space.
+    ///
+    /// See [`ProductEncoder::new`] for the parameters.
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        row_recovery: usize,
+        column_recovery: usize,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        let engine = DefaultEngine::new();
+        let row_decoder =
+            DefaultRateDecoder::new(columns, row_recovery, shard_bytes, engine.clone(), None)?;
+        let column_decoder =
+            DefaultRateDecoder::new(rows, column_recovery, shard_bytes, engine, None)?;
+
+        let grid = Grid::new(rows, columns, row_recovery, column_recovery, shard_bytes);
+        let count = grid.full_rows() * grid.full_columns();
+
+        Ok(Self {
+            grid,
+            known: vec![false; count],
+            received: vec![false; count],
+            row_decoder,
+            column_decoder,
+        })
+    }
+}
+
+// ======================================================================
+// ProductDecoderResult - PUBLIC
+
+/// Result of product decoding. Contains the restored original shards.
+///
+/// This struct is created by [`ProductDecoder::decode`].
+pub struct ProductDecoderResult<'a> {
+    decoder: &'a mut ProductDecoder,
+}
+
+impl<'a> ProductDecoderResult<'a> {
+    /// Returns restored original shard at given position
+    /// or `None` if given position doesn't correspond to
+    /// a missing original shard.
+    pub fn restored_original(&self, row: usize, column: usize) -> Option<&[u8]> {
+        let grid = &self.decoder.grid;
+        if row < grid.rows && column < grid.columns {
+            let pos = grid.pos(row, column)?;
+            if self.decoder.received[pos] {
+                None
+            } else {
+                Some(grid.shard(pos))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+// ======================================================================
+// ProductDecoderResult - IMPL DROP
+
+impl<'a> Drop for ProductDecoderResult<'a> {
+    fn drop(&mut self) {
+        self.decoder.known.fill(false);
+        self.decoder.received.fill(false);
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Encodes a row or column whose first `original_count` positions
+// are original shards and rest are recovery shards.
+fn encode_line(
+    encoder: &mut DefaultRateEncoder<DefaultEngine>,
+    grid: &mut Grid,
+    positions: &[usize],
+    original_count: usize,
+) -> Result<(), Error> {
+    for &pos in &positions[..original_count] {
+        encoder.add_original_shard(grid.shard(pos))?;
+    }
+
+    let result = encoder.encode()?;
+    for (i, &pos) in positions[original_count..].iter().enumerate() {
+        grid.shard_mut(pos)
+            .copy_from_slice(result.recovery(i).unwrap());
+    }
+
+    Ok(())
+}
+
+// Decodes a row or column if it has missing shards and enough known shards,
+// marking restored shards as known. Returns `true` if shards were restored.
+fn decode_line(
+    decoder: &mut DefaultRateDecoder<DefaultEngine>,
+    grid: &mut Grid,
+    known: &mut [bool],
+    positions: &[usize],
+    original_count: usize,
+) -> Result<bool, Error> {
+    let known_count = positions.iter().filter(|&&pos| known[pos]).count();
+    if known_count == positions.len() || known_count < original_count {
+        return Ok(false);
+    }
+
+    for (i, &pos) in positions.iter().enumerate() {
+        if known[pos] {
+            if i < original_count {
+                decoder.add_original_shard(i, grid.shard(pos))?;
+            } else {
+                decoder.add_recovery_shard(i - original_count, grid.shard(pos))?;
+            }
+        }
+    }
+
+    let result = decoder.repair()?;
+    for (i, &pos) in positions.iter().enumerate() {
+        if !known[pos] {
+            let restored = if i < original_count {
+                result.restored_original(i)
+            } else {
+                result.restored_recovery(i - original_count)
+            };
+            grid.shard_mut(pos).copy_from_slice(restored.unwrap());
+            known[pos] = true;
+        }
+    }
+
+    Ok(true)
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    // Encodes 4 x 5 grid with 2 recovery shards per row and 1 per column
+    // and decodes it with given lost positions.
+    fn roundtrip(lost: &[(usize, usize)]) -> Result<(), Error> {
+        let original = test_util::generate_original(20, 64, 0);
+
+        let mut encoder = ProductEncoder::new(4, 5, 2, 1, 64).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let encoded = encoder.encode().unwrap();
+
+        let mut decoder = ProductDecoder::new(4, 5, 2, 1, 64).unwrap();
+        for row in 0..5 {
+            for column in 0..7 {
+                if !lost.contains(&(row, column)) {
+                    let shard = if row < 4 && column < 5 {
+                        &original[row * 5 + column]
+                    } else {
+                        encoded.recovery(row, column).unwrap()
+                    };
+                    decoder.add_shard(row, column, shard).unwrap();
+                }
+            }
+        }
+
+        let result = decoder.decode()?;
+        for &(row, column) in lost {
+            if row < 4 && column < 5 {
+                assert_eq!(
+                    result.restored_original(row, column).unwrap(),
+                    original[row * 5 + column]
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn recovery_is_codeword_in_both_dimensions() {
+        // Corner shards are restored both from their row and their column.
+        roundtrip(&[(4, 5), (4, 6)]).unwrap();
+        roundtrip(&[(0, 5), (1, 5), (4, 5)]).unwrap();
+    }
+
+    #[test]
+    fn whole_row_lost() {
+        roundtrip(&[(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5), (0, 6)]).unwrap();
+    }
+
+    #[test]
+    fn iterative() {
+        // Row 0 and column 0 both lose too many shards at first,
+        // but can be decoded after rows 1 and 2 have been decoded.
+        let lost = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 3), (2, 1)];
+        roundtrip(&lost).unwrap();
+    }
+
+    #[test]
+    fn errors() {
+        let mut decoder = ProductDecoder::new(4, 5, 2, 1, 64).unwrap();
+
+        decoder.add_shard(4, 6, [0; 64]).unwrap();
+        assert_eq!(
+            decoder.add_shard(4, 6, [0; 64]),
+            Err(Error::DuplicateShardPosition { row: 4, column: 6 })
+        );
+
+        assert_eq!(
+            decoder.add_shard(0, 7, [0; 64]),
+            Err(Error::InvalidShardPosition {
+                rows: 5,
+                columns: 7,
+                row: 0,
+                column: 7,
+            })
+        );
+    }
+
+    #[test]
+    fn not_enough_shards() {
+        // Every row and column containing lost shards loses too many.
+        let lost = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)];
+        assert_eq!(
+            roundtrip(&lost),
+            Err(Error::NotEnoughShards {
+                original_count: 20,
+                original_received_count: 14,
+                recovery_received_count: 15,
+            })
+        );
+    }
+}
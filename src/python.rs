@@ -0,0 +1,416 @@
+//! Python bindings with [PyO3].
+//!
+//! **This module is only available with `python` feature.**
+//!
+//! Python extension module `reed_solomon_16` is built with [maturin]
+//! using `pyproject.toml` in crate root, e.g. `maturin develop --release`.
+//! It mirrors [simple usage] and [basic usage] of this crate:
+//!
+//! - `encode(original_count, recovery_count, original)`
+//!   returns `list[bytes]` of recovery shards, see [`encode`].
+//! - `decode(original_count, recovery_count, original, recovery)`
+//!   takes `dict[int, shard]`:s and returns `dict[int, bytes]`
+//!   of restored original shards, see [`decode`].
+//! - `ReedSolomonEncoder` and `ReedSolomonDecoder` classes have methods
+//!   `add_original_shard`, `add_recovery_shard` (decoder only),
+//!   `encode` / `decode`, `reset` and static `supports`.
+//!
+//! Details:
+//!
+//! - Shards can be `bytes` or any object supporting buffer protocol
+//!   with C-contiguous bytes, e.g. `bytearray`, `memoryview` or
+//!   `numpy.ndarray` of `uint8`. Those are read in-place without copying.
+//!   Returned shards are new `bytes` objects.
+//! - Each [`Error`] variant is raised as exception class with same name,
+//!   e.g. `reed_solomon_16.InvalidShardSize`, all of which are
+//!   subclasses of `reed_solomon_16.Error` which itself
+//!   is a subclass of `ValueError`.
+//!
+//! # Example
+//!
+//! ```python
+//! import reed_solomon_16 as rs
+//!
+//! original = [bytes([i]) * 64 for i in range(3)]
+//! recovery = rs.encode(3, 5, original)
+//!
+//! restored = rs.decode(3, 5, {1: original[1]}, {1: recovery[1], 4: recovery[4]})
+//! assert restored == {0: original[0], 2: original[2]}
+//!
+//! try:
+//!     rs.encode(3, 5, [b"x" * 63] * 3)
+//! except rs.InvalidShardSize as e:
+//!     print(e)
+//! ```
+//!
+//! [PyO3]: https://pyo3.rs
+//! [maturin]: https://www.maturin.rs
+//! [simple usage]: crate#simple-usage
+//! [basic usage]: crate#basic-usage
+//! [`encode`]: crate::encode
+//! [`decode`]: crate::decode
+
+use std::{collections::HashMap, slice};
+
+use pyo3::{
+    buffer::PyBuffer,
+    create_exception,
+    exceptions::{PyBufferError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+use crate::Error;
+
+// ======================================================================
+// EXCEPTIONS
+
+macro_rules! exceptions {
+    ($($name:ident),* $(,)?) => {
+        mod exception {
+            use super::*;
+
+            create_exception!(reed_solomon_16, Error, PyValueError, "Base class of all errors.");
+            $( create_exception!(reed_solomon_16, $name, Error); )*
+        }
+
+        fn to_py_err(err: Error) -> PyErr {
+            let message = err.to_string();
+            match err {
+                $( Error::$name { .. } => exception::$name::new_err(message), )*
+            }
+        }
+
+        fn add_exceptions(module: &Bound<'_, PyModule>) -> PyResult<()> {
+            let py = module.py();
+            module.add("Error", py.get_type::<exception::Error>())?;
+            $( module.add(stringify!($name), py.get_type::<exception::$name>())?; )*
+            Ok(())
+        }
+    };
+}
+
+exceptions!(
+    ChecksumMismatch,
+    DifferentShardSize,
+    DuplicateLocalParityShardIndex,
+    DuplicateOriginalShardIndex,
+    DuplicateRecoveryShardIndex,
+    DuplicateShardPosition,
+    InvalidLocalParityShardIndex,
+    InvalidOriginalShardIndex,
+    InvalidPar2,
+    InvalidRecoveryShardIndex,
+    InvalidShardPosition,
+    InvalidShardSize,
+    NotEnoughShards,
+    TooFewOriginalShards,
+    TooManyCorruptedShards,
+    TooManyOriginalShards,
+    UnsupportedShardCount,
+);
+
+// ======================================================================
+// Shard - PRIVATE
+
+/// Shard borrowed from `bytes` or from a buffer protocol object.
+enum Shard<'py> {
+    Bytes(Bound<'py, PyBytes>),
+    Buffer(PyBuffer<u8>),
+}
+
+impl<'py> Shard<'py> {
+    fn extract(object: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(bytes) = object.downcast::<PyBytes>() {
+            return Ok(Shard::Bytes(bytes.clone()));
+        }
+
+        let buffer = PyBuffer::<u8>::get(object)?;
+        if !buffer.is_c_contiguous() {
+            return Err(PyBufferError::new_err("shard buffer must be C-contiguous"));
+        }
+        Ok(Shard::Buffer(buffer))
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Shard::Bytes(bytes) => bytes.as_bytes(),
+            // SAFETY: Buffer is C-contiguous `u8` data which stays alive
+            //         while `self` is borrowed. GIL is held during all
+            //         uses so Python code can't modify it concurrently.
+            Shard::Buffer(buffer) => unsafe {
+                slice::from_raw_parts(buffer.buf_ptr() as *const u8, buffer.len_bytes())
+            },
+        }
+    }
+}
+
+// ======================================================================
+// ReedSolomonEncoder - PUBLIC
+
+/// Python class `ReedSolomonEncoder`, see [`crate::ReedSolomonEncoder`].
+#[pyclass(name = "ReedSolomonEncoder", module = "reed_solomon_16", unsendable)]
+pub struct PyReedSolomonEncoder(crate::ReedSolomonEncoder);
+
+#[pymethods]
+impl PyReedSolomonEncoder {
+    #[new]
+    fn new(original_count: usize, recovery_count: usize, shard_bytes: usize) -> PyResult<Self> {
+        crate::ReedSolomonEncoder::new(original_count, recovery_count, shard_bytes)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    fn add_original_shard(&mut self, original_shard: &Bound<'_, PyAny>) -> PyResult<()> {
+        let shard = Shard::extract(original_shard)?;
+        self.0
+            .add_original_shard(shard.as_bytes())
+            .map_err(to_py_err)
+    }
+
+    fn encode<'py>(&mut self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyBytes>>> {
+        let result = self.0.encode().map_err(to_py_err)?;
+        Ok(result
+            .recovery_iter()
+            .map(|shard| PyBytes::new(py, shard))
+            .collect())
+    }
+
+    fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> PyResult<()> {
+        self.0
+            .reset(original_count, recovery_count, shard_bytes)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn supports(original_count: usize, recovery_count: usize) -> bool {
+        crate::ReedSolomonEncoder::supports(original_count, recovery_count)
+    }
+}
+
+// ======================================================================
+// ReedSolomonDecoder - PUBLIC
+
+/// Python class `ReedSolomonDecoder`, see [`crate::ReedSolomonDecoder`].
+#[pyclass(name = "ReedSolomonDecoder", module = "reed_solomon_16", unsendable)]
+pub struct PyReedSolomonDecoder(crate::ReedSolomonDecoder);
+
+#[pymethods]
+impl PyReedSolomonDecoder {
+    #[new]
+    fn new(original_count: usize, recovery_count: usize, shard_bytes: usize) -> PyResult<Self> {
+        crate::ReedSolomonDecoder::new(original_count, recovery_count, shard_bytes)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    fn add_original_shard(
+        &mut self,
+        index: usize,
+        original_shard: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let shard = Shard::extract(original_shard)?;
+        self.0
+            .add_original_shard(index, shard.as_bytes())
+            .map_err(to_py_err)
+    }
+
+    fn add_recovery_shard(
+        &mut self,
+        index: usize,
+        recovery_shard: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let shard = Shard::extract(recovery_shard)?;
+        self.0
+            .add_recovery_shard(index, shard.as_bytes())
+            .map_err(to_py_err)
+    }
+
+    fn decode<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let result = self.0.decode().map_err(to_py_err)?;
+        let restored = PyDict::new(py);
+        for (index, shard) in result.restored_original_iter() {
+            restored.set_item(index, PyBytes::new(py, shard))?;
+        }
+        Ok(restored)
+    }
+
+    fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> PyResult<()> {
+        self.0
+            .reset(original_count, recovery_count, shard_bytes)
+            .map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn supports(original_count: usize, recovery_count: usize) -> bool {
+        crate::ReedSolomonDecoder::supports(original_count, recovery_count)
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+#[pyfunction]
+fn encode<'py>(
+    py: Python<'py>,
+    original_count: usize,
+    recovery_count: usize,
+    original: Vec<Bound<'py, PyAny>>,
+) -> PyResult<Vec<Bound<'py, PyBytes>>> {
+    let original = original
+        .iter()
+        .map(Shard::extract)
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let recovery = crate::encode(
+        original_count,
+        recovery_count,
+        original.iter().map(Shard::as_bytes),
+    )
+    .map_err(to_py_err)?;
+
+    Ok(recovery
+        .iter()
+        .map(|shard| PyBytes::new(py, shard))
+        .collect())
+}
+
+#[pyfunction]
+fn decode<'py>(
+    py: Python<'py>,
+    original_count: usize,
+    recovery_count: usize,
+    original: HashMap<usize, Bound<'py, PyAny>>,
+    recovery: HashMap<usize, Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let extract = |shards: HashMap<usize, Bound<'py, PyAny>>| {
+        shards
+            .into_iter()
+            .map(|(index, shard)| Ok((index, Shard::extract(&shard)?)))
+            .collect::<PyResult<Vec<_>>>()
+    };
+    let original = extract(original)?;
+    let recovery = extract(recovery)?;
+
+    let restored = crate::decode(
+        original_count,
+        recovery_count,
+        original
+            .iter()
+            .map(|(index, shard)| (*index, shard.as_bytes())),
+        recovery
+            .iter()
+            .map(|(index, shard)| (*index, shard.as_bytes())),
+    )
+    .map_err(to_py_err)?;
+
+    let result = PyDict::new(py);
+    for (index, shard) in restored {
+        result.set_item(index, PyBytes::new(py, &shard))?;
+    }
+    Ok(result)
+}
+
+#[pymodule]
+fn reed_solomon_16(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(encode, module)?)?;
+    module.add_function(wrap_pyfunction!(decode, module)?)?;
+    module.add_class::<PyReedSolomonEncoder>()?;
+    module.add_class::<PyReedSolomonDecoder>()?;
+    add_exceptions(module)
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::types::PyModule;
+
+    use super::*;
+
+    fn run(code: &str) {
+        let code = CString::new(code).unwrap();
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "reed_solomon_16").unwrap();
+            reed_solomon_16(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("rs", module).unwrap();
+            py.run(&code, Some(&globals), None)
+                .map_err(|err| err.display(py))
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn encode_decode() {
+        run(r#"
+original = [bytes([i]) * 64 for i in range(3)]
+recovery = rs.encode(3, 5, [original[0], bytearray(original[1]), memoryview(original[2])])
+assert len(recovery) == 5
+
+restored = rs.decode(3, 5, {1: original[1]}, {1: recovery[1], 4: bytearray(recovery[4])})
+assert restored == {0: original[0], 2: original[2]}
+"#);
+    }
+
+    #[test]
+    fn encoder_decoder() {
+        run(r#"
+original = [bytes([i]) * 64 for i in range(3)]
+
+encoder = rs.ReedSolomonEncoder(3, 2, 64)
+for shard in original:
+    encoder.add_original_shard(shard)
+recovery = encoder.encode()
+assert recovery == rs.encode(3, 2, original)
+
+decoder = rs.ReedSolomonDecoder(3, 2, 64)
+decoder.add_original_shard(0, original[0])
+decoder.add_recovery_shard(0, recovery[0])
+decoder.add_recovery_shard(1, memoryview(recovery[1]))
+assert decoder.decode() == {1: original[1], 2: original[2]}
+
+assert rs.ReedSolomonEncoder.supports(60000, 4000)
+assert not rs.ReedSolomonDecoder.supports(60000, 5000)
+"#);
+    }
+
+    #[test]
+    fn errors() {
+        run(r#"
+try:
+    rs.ReedSolomonEncoder(3, 2, 63)
+    assert False
+except rs.InvalidShardSize as e:
+    assert isinstance(e, rs.Error)
+    assert isinstance(e, ValueError)
+
+decoder = rs.ReedSolomonDecoder(3, 2, 64)
+try:
+    decoder.decode()
+    assert False
+except rs.NotEnoughShards:
+    pass
+
+try:
+    rs.encode(2, 1, [b"x" * 64, memoryview(b"x" * 128)[::2]])
+    assert False
+except BufferError:
+    pass
+"#);
+    }
+}
@@ -13,6 +13,8 @@
 //!
 //! - [`DefaultRate`], [`DefaultRateEncoder`], [`DefaultRateDecoder`]
 //!     - Encoding/decoding using high or low rate as appropriate.
+//!     - Which rate is used can be checked with [`use_high_rate`].
+//!     - [`DefaultRateDecoder::with_rate`] decodes shards encoded with either rate.
 //!     - These are basically same as [`ReedSolomonEncoder`]
 //!       and [`ReedSolomonDecoder`] except with slightly different API
 //!       which allows specifying [`Engine`] and working space.
@@ -28,8 +30,21 @@
 //! [`ReedSolomonDecoder`]: crate::ReedSolomonDecoder
 //! [`DefaultEngine`]: crate::engine::DefaultEngine
 
-use crate::{engine::Engine, DecoderResult, EncoderResult, Error};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
 
+#[cfg(feature = "alloc")]
+use crate::{
+    checksum,
+    engine::{Engine, Gf16, GfElement},
+    DecoderResult, EncoderResult, ShardKind,
+};
+use crate::{engine::GF_ORDER, Error};
+
+#[cfg(feature = "alloc")]
+pub(crate) use self::error_correction::{Field, Gf16Field};
+#[cfg(feature = "alloc")]
 pub use self::{
     decoder_work::DecoderWork,
     encoder_work::EncoderWork,
@@ -38,16 +53,24 @@ pub use self::{
     rate_low::{LowRate, LowRateDecoder, LowRateEncoder},
 };
 
+#[cfg(feature = "alloc")]
 mod decoder_work;
+#[cfg(feature = "alloc")]
 mod encoder_work;
+#[cfg(feature = "alloc")]
+mod error_correction;
+#[cfg(feature = "alloc")]
 mod rate_default;
+#[cfg(feature = "alloc")]
 mod rate_high;
+#[cfg(feature = "alloc")]
 mod rate_low;
 
 // ======================================================================
 // Rate - PUBLIC
 
 /// Reed-Solomon encoder/decoder generator using specific rate.
+#[cfg(feature = "alloc")]
 pub trait Rate<E: Engine> {
     // ============================================================
     // REQUIRED
@@ -64,6 +87,88 @@ pub trait Rate<E: Engine> {
     // ============================================================
     // PROVIDED
 
+    /// Calculates only recovery shard `recovery_index`,
+    /// given all original shards in order.
+    ///
+    /// - Result is same as [`EncoderResult::recovery`]
+    ///   of [`Self::RateEncoder`] with same shard counts.
+    /// - Default implementation encodes all recovery shards
+    ///   with [`Self::RateEncoder`] and returns the requested one.
+    /// - Rates of this crate override this to calculate recovery shard
+    ///   directly as weighted sum of original shards, which takes
+    ///   `O(original_count)` time and needs memory only for two shards.
+    ///
+    /// This is useful when each recovery shard is calculated
+    /// by a different party which needs only its own recovery shard.
+    /// When all recovery shards are needed, [`RateEncoder`] is much faster.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::{
+    ///     engine::NoSimd,
+    ///     rate::{DefaultRate, Rate},
+    /// };
+    ///
+    /// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+    /// let recovery = reed_solomon_16::encode(3, 5, original)?;
+    ///
+    /// let recovery_4 = DefaultRate::encode_one(3, 5, 4, original, &NoSimd::new())?;
+    /// assert_eq!(recovery_4, recovery[4]);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    fn encode_one<T>(
+        original_count: usize,
+        recovery_count: usize,
+        recovery_index: usize,
+        original: T,
+        engine: &E,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: IntoIterator,
+        T::Item: AsRef<[u8]>,
+    {
+        if !Self::supports(original_count, recovery_count) {
+            return Err(Error::UnsupportedShardCount {
+                original_count,
+                recovery_count,
+            });
+        }
+
+        if recovery_index >= recovery_count {
+            return Err(Error::InvalidRecoveryShardIndex {
+                recovery_count,
+                index: recovery_index,
+            });
+        }
+
+        let mut original = original.into_iter().peekable();
+        let shard_bytes = match original.peek() {
+            Some(original_shard) => original_shard.as_ref().len(),
+            None => {
+                return Err(Error::TooFewOriginalShards {
+                    original_count,
+                    original_received_count: 0,
+                })
+            }
+        };
+
+        let mut encoder = Self::encoder(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            engine.clone(),
+            None,
+        )?;
+
+        for original_shard in original {
+            encoder.add_original_shard(original_shard)?;
+        }
+
+        let result = encoder.encode()?;
+        Ok(result.recovery(recovery_index).unwrap().to_vec())
+    }
+
     /// Creates new encoder. This is same as [`RateEncoder::new`].
     fn encoder(
         original_count: usize,
@@ -86,6 +191,73 @@ pub trait Rate<E: Engine> {
         Self::RateDecoder::new(original_count, recovery_count, shard_bytes, engine, work)
     }
 
+    /// Returns generator matrix of the code with given
+    /// `original_count` / `recovery_count` combination.
+    ///
+    /// - `matrix[i][j]` is the coefficient of original shard `j`
+    ///   in recovery shard `i`, i.e. every 16-bit element of recovery shard `i`
+    ///   is `sum(matrix[i][j] * original[j])` over all original shards `j`.
+    /// - Elements are [`Gf16`] and shards store them as described in
+    ///   [algorithm > Shard](crate::algorithm#shard).
+    /// - Matrix is calculated by encoding unit vectors, so it is always
+    ///   consistent with [`Self::RateEncoder`].
+    ///
+    /// Matrix has `original_count * recovery_count` elements,
+    /// so this is only practical for moderate shard counts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::{
+    ///     engine::{Gf16, NoSimd},
+    ///     rate::{DefaultRate, Rate},
+    /// };
+    ///
+    /// let matrix = DefaultRate::generator_matrix(3, 2, NoSimd::new())?;
+    ///
+    /// assert_eq!(matrix.len(), 2);
+    /// assert_eq!(matrix[0].len(), 3);
+    /// assert!(matrix.iter().flatten().all(|&c| c != Gf16::ZERO));
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    fn generator_matrix(
+        original_count: usize,
+        recovery_count: usize,
+        engine: E,
+    ) -> Result<Vec<Vec<Gf16>>, Error> {
+        // Each batch encodes one unit vector per element of a shard.
+        const MAX_BATCH: usize = 1024;
+
+        let batch = original_count.clamp(1, MAX_BATCH);
+        let shard_bytes = batch.div_ceil(32) * 64;
+
+        let mut encoder = Self::encoder(original_count, recovery_count, shard_bytes, engine, None)?;
+
+        let mut matrix = vec![vec![Gf16::ZERO; original_count]; recovery_count];
+        let mut shard = vec![0; shard_bytes];
+
+        for batch_start in (0..original_count).step_by(batch) {
+            let batch_end = original_count.min(batch_start + batch);
+
+            for j in 0..original_count {
+                shard.fill(0);
+                if (batch_start..batch_end).contains(&j) {
+                    set_element(&mut shard, j - batch_start, Gf16::ONE);
+                }
+                encoder.add_original_shard(&shard)?;
+            }
+
+            let result = encoder.encode()?;
+            for (row, recovery) in matrix.iter_mut().zip(result.recovery_iter()) {
+                for (e, coeff) in row[batch_start..batch_end].iter_mut().enumerate() {
+                    *coeff = get_element(recovery, e);
+                }
+            }
+        }
+
+        Ok(matrix)
+    }
+
     /// Returns `Ok(())` if given `original_count` / `recovery_count`
     /// combination is supported and given `shard_bytes` is valid.
     fn validate(
@@ -110,6 +282,7 @@ pub trait Rate<E: Engine> {
 // RateEncoder - PUBLIC
 
 /// Reed-Solomon encoder using specific rate.
+#[cfg(feature = "alloc")]
 pub trait RateEncoder<E: Engine>
 where
     Self: Sized,
@@ -124,7 +297,7 @@ where
     fn add_original_shard<T: AsRef<[u8]>>(&mut self, original_shard: T) -> Result<(), Error>;
 
     /// Like [`ReedSolomonEncoder::encode`](crate::ReedSolomonEncoder::encode).
-    fn encode(&mut self) -> Result<EncoderResult, Error>;
+    fn encode(&mut self) -> Result<EncoderResult<'_>, Error>;
 
     /// Consumes this encoder returning its [`Engine`] and [`EncoderWork`]
     /// so that they can be re-used by another encoder.
@@ -151,6 +324,31 @@ where
     // ============================================================
     // PROVIDED
 
+    /// Like [`ReedSolomonEncoder::add_original_shard_with_checksum`](crate::ReedSolomonEncoder::add_original_shard_with_checksum).
+    fn add_original_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        original_shard: T,
+    ) -> Result<u32, Error> {
+        let original_shard = original_shard.as_ref();
+        self.add_original_shard(original_shard)?;
+        Ok(checksum::crc32c(original_shard))
+    }
+
+    /// Like [`ReedSolomonEncoder::find_mismatch`](crate::ReedSolomonEncoder::find_mismatch).
+    fn find_mismatch<R, T>(&mut self, recovery: R) -> Result<Option<usize>, Error>
+    where
+        R: IntoIterator<Item = (usize, T)>,
+        T: AsRef<[u8]>,
+    {
+        let result = self.encode()?;
+        for (index, recovery_shard) in recovery {
+            if !result.matches_recovery(index, recovery_shard.as_ref())? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
     /// Returns `true` if given `original_count` / `recovery_count`
     /// combination is supported.
     ///
@@ -170,12 +368,29 @@ where
     ) -> Result<(), Error> {
         Self::Rate::validate(original_count, recovery_count, shard_bytes)
     }
+
+    /// Like [`ReedSolomonEncoder::verify`](crate::ReedSolomonEncoder::verify).
+    fn verify<R, T>(&mut self, recovery: R) -> Result<Vec<usize>, Error>
+    where
+        R: IntoIterator<Item = (usize, T)>,
+        T: AsRef<[u8]>,
+    {
+        let result = self.encode()?;
+        let mut mismatch = Vec::new();
+        for (index, recovery_shard) in recovery {
+            if !result.matches_recovery(index, recovery_shard.as_ref())? {
+                mismatch.push(index);
+            }
+        }
+        Ok(mismatch)
+    }
 }
 
 // ======================================================================
 // RateDecoder - PUBLIC
 
 /// Reed-Solomon decoder using specific rate.
+#[cfg(feature = "alloc")]
 pub trait RateDecoder<E: Engine>
 where
     Self: Sized,
@@ -201,12 +416,18 @@ where
     ) -> Result<(), Error>;
 
     /// Like [`ReedSolomonDecoder::decode`](crate::ReedSolomonDecoder::decode).
-    fn decode(&mut self) -> Result<DecoderResult, Error>;
+    fn decode(&mut self) -> Result<DecoderResult<'_>, Error>;
+
+    /// Like [`ReedSolomonDecoder::decode_with_errors`](crate::ReedSolomonDecoder::decode_with_errors).
+    fn decode_with_errors(&mut self) -> Result<DecoderResult<'_>, Error>;
 
     /// Consumes this decoder returning its [`Engine`] and [`DecoderWork`]
     /// so that they can be re-used by another decoder.
     fn into_parts(self) -> (E, DecoderWork);
 
+    /// Like [`ReedSolomonDecoder::missing_original_indexes`](crate::ReedSolomonDecoder::missing_original_indexes).
+    fn missing_original_indexes(&self) -> Vec<usize>;
+
     /// Like [`ReedSolomonDecoder::new`](crate::ReedSolomonDecoder::new)
     /// with [`Engine`] to use and optional working space to be re-used.
     fn new(
@@ -217,6 +438,21 @@ where
         work: Option<DecoderWork>,
     ) -> Result<Self, Error>;
 
+    /// Like [`ReedSolomonDecoder::original_count`](crate::ReedSolomonDecoder::original_count).
+    fn original_count(&self) -> usize;
+
+    /// Like [`ReedSolomonDecoder::original_received_count`](crate::ReedSolomonDecoder::original_received_count).
+    fn original_received_count(&self) -> usize;
+
+    /// Like [`ReedSolomonDecoder::recovery_count`](crate::ReedSolomonDecoder::recovery_count).
+    fn recovery_count(&self) -> usize;
+
+    /// Like [`ReedSolomonDecoder::recovery_received_count`](crate::ReedSolomonDecoder::recovery_received_count).
+    fn recovery_received_count(&self) -> usize;
+
+    /// Like [`ReedSolomonDecoder::repair`](crate::ReedSolomonDecoder::repair).
+    fn repair(&mut self) -> Result<DecoderResult<'_>, Error>;
+
     /// Like [`ReedSolomonDecoder::reset`](crate::ReedSolomonDecoder::reset).
     fn reset(
         &mut self,
@@ -228,6 +464,53 @@ where
     // ============================================================
     // PROVIDED
 
+    /// Like [`ReedSolomonDecoder::add_original_shard_with_checksum`](crate::ReedSolomonDecoder::add_original_shard_with_checksum).
+    fn add_original_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+        checksum: u32,
+    ) -> Result<(), Error> {
+        let original_shard = original_shard.as_ref();
+        if checksum::crc32c(original_shard) != checksum {
+            Err(Error::ChecksumMismatch {
+                index,
+                kind: ShardKind::Original,
+            })
+        } else {
+            self.add_original_shard(index, original_shard)
+        }
+    }
+
+    /// Like [`ReedSolomonDecoder::add_recovery_shard_with_checksum`](crate::ReedSolomonDecoder::add_recovery_shard_with_checksum).
+    fn add_recovery_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+        checksum: u32,
+    ) -> Result<(), Error> {
+        let recovery_shard = recovery_shard.as_ref();
+        if checksum::crc32c(recovery_shard) != checksum {
+            Err(Error::ChecksumMismatch {
+                index,
+                kind: ShardKind::Recovery,
+            })
+        } else {
+            self.add_recovery_shard(index, recovery_shard)
+        }
+    }
+
+    /// Like [`ReedSolomonDecoder::can_decode`](crate::ReedSolomonDecoder::can_decode).
+    fn can_decode(&self) -> bool {
+        self.needed_shard_count() == 0
+    }
+
+    /// Like [`ReedSolomonDecoder::needed_shard_count`](crate::ReedSolomonDecoder::needed_shard_count).
+    fn needed_shard_count(&self) -> usize {
+        self.original_count()
+            .saturating_sub(self.original_received_count() + self.recovery_received_count())
+    }
+
     /// Returns `true` if given `original_count` / `recovery_count`
     /// combination is supported.
     ///
@@ -248,3 +531,471 @@ where
         Self::Rate::validate(original_count, recovery_count, shard_bytes)
     }
 }
+
+// ======================================================================
+// FUNCTIONS - PUBLIC
+
+/// Returns `true` if [`DefaultRate`] uses high rate
+/// and `false` if it uses low rate with given shard counts.
+///
+/// High rate and low rate produce different recovery shards,
+/// so decoding must use the same rate that encoding used.
+/// This choice depends only on `original_count` and `recovery_count`
+/// and is considered part of the data format,
+/// i.e. it won't change in future versions.
+/// So data encoded with [`DefaultRate`] never needs
+/// any information about the rate to be decoded.
+///
+/// Shards encoded explicitly with [`HighRateEncoder`] or [`LowRateEncoder`]
+/// can be decoded with [`DefaultRateDecoder::with_rate`].
+///
+/// Returns [`Error::UnsupportedShardCount`] if given shard counts
+/// are not supported by either rate.
+///
+/// # Examples
+///
+/// Data encoded with [`encode`] can be decoded
+/// with explicitly chosen rate:
+///
+/// ```rust
+/// use reed_solomon_16::{
+///     engine::DefaultEngine,
+///     rate::{self, LowRateDecoder, RateDecoder},
+/// };
+///
+/// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+/// let recovery = reed_solomon_16::encode(3, 5, original)?;
+///
+/// assert_eq!(rate::use_high_rate(3, 5)?, false);
+///
+/// let engine = DefaultEngine::new();
+/// let mut decoder = LowRateDecoder::new(3, 5, 64, engine, None)?;
+/// decoder.add_recovery_shard(0, &recovery[0])?;
+/// decoder.add_recovery_shard(1, &recovery[1])?;
+/// decoder.add_recovery_shard(2, &recovery[2])?;
+///
+/// let result = decoder.decode()?;
+/// assert_eq!(result.restored_original(1).unwrap(), original[1]);
+/// # Ok::<(), reed_solomon_16::Error>(())
+/// ```
+///
+/// [`encode`]: crate::encode
+pub fn use_high_rate(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
+    if original_count > GF_ORDER || recovery_count > GF_ORDER {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    let original_count_pow2 = original_count.next_power_of_two();
+    let recovery_count_pow2 = recovery_count.next_power_of_two();
+
+    let smaller_pow2 = core::cmp::min(original_count_pow2, recovery_count_pow2);
+    let larger = core::cmp::max(original_count, recovery_count);
+
+    if original_count == 0 || recovery_count == 0 || smaller_pow2 + larger > GF_ORDER {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    match original_count_pow2.cmp(&recovery_count_pow2) {
+        Ordering::Less => {
+            // The "correct" rate is generally faster here,
+            // and also must be used if `recovery_count > 32768`.
+
+            Ok(false)
+        }
+
+        Ordering::Greater => {
+            // The "correct" rate is generally faster here,
+            // and also must be used if `original_count > 32768`.
+
+            Ok(true)
+        }
+
+        Ordering::Equal => {
+            // Here counter-intuitively the "wrong" rate is generally faster
+            // in decoding if `original_count` and `recovery_count` differ a lot.
+
+            if original_count <= recovery_count {
+                // Using the "wrong" rate on purpose.
+                Ok(true)
+            } else {
+                // Using the "wrong" rate on purpose.
+                Ok(false)
+            }
+        }
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+// Returns `(x, y)` such that weight of original shard `original_index`
+// in recovery shard `recovery_index` is `x / y`.
+//
+// With high rate original shard `j` is at point `chunk_size + j` and recovery
+// shard `i` at point `i`. Lagrange coefficient over the chunk of original shard `j`
+// simplifies to `W(chunk_start) / (point_j - point_i)` where `W` is
+// normalized subspace polynomial of size `chunk_size`, and with Cantor basis
+// `W(chunk_start)` is just `chunk_start / chunk_size` as an element.
+//
+// Low rate is the same with original and recovery shards swapped,
+// evaluating over first chunk.
+pub(crate) const fn coefficient(
+    high_rate: bool,
+    original_count: usize,
+    recovery_count: usize,
+    original_index: usize,
+    recovery_index: usize,
+) -> (usize, usize) {
+    if high_rate {
+        let chunk_size = recovery_count.next_power_of_two();
+        let point = chunk_size + original_index;
+        (point / chunk_size, point ^ recovery_index)
+    } else {
+        let chunk_size = original_count.next_power_of_two();
+        let point = chunk_size + recovery_index;
+        (point / chunk_size, point ^ original_index)
+    }
+}
+
+// Calculates single recovery shard as `sum(coefficient(j) * original[j])`.
+//
+// Used by `Rate::encode_one` implementations which
+// differ only in how coefficients are calculated.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_one_with<R, E, F, T, C>(
+    original_count: usize,
+    recovery_count: usize,
+    recovery_index: usize,
+    original: T,
+    field: &F,
+    coefficient: C,
+) -> Result<Vec<u8>, Error>
+where
+    R: Rate<E>,
+    E: Engine,
+    F: Field,
+    T: IntoIterator,
+    T::Item: AsRef<[u8]>,
+    C: Fn(usize) -> usize,
+{
+    if !R::supports(original_count, recovery_count) {
+        return Err(Error::UnsupportedShardCount {
+            original_count,
+            recovery_count,
+        });
+    }
+
+    if recovery_index >= recovery_count {
+        return Err(Error::InvalidRecoveryShardIndex {
+            recovery_count,
+            index: recovery_index,
+        });
+    }
+
+    let mut recovery = Vec::new();
+    let mut temp = Vec::new();
+    let mut original_received_count = 0;
+
+    for original_shard in original {
+        let original_shard = original_shard.as_ref();
+
+        if original_received_count == 0 {
+            R::validate(original_count, recovery_count, original_shard.len())?;
+            recovery = vec![0; original_shard.len()];
+            temp = vec![0; original_shard.len()];
+        } else if original_shard.len() != recovery.len() {
+            return Err(Error::DifferentShardSize {
+                shard_bytes: recovery.len(),
+                got: original_shard.len(),
+            });
+        }
+
+        if original_received_count == original_count {
+            return Err(Error::TooManyOriginalShards { original_count });
+        }
+
+        let coefficient = coefficient(original_received_count);
+        if coefficient != 0 {
+            temp.copy_from_slice(original_shard);
+            field.mul_shard(&mut temp, coefficient);
+            E::xor(&mut recovery, &temp);
+        }
+
+        original_received_count += 1;
+    }
+
+    if original_received_count < original_count {
+        return Err(Error::TooFewOriginalShards {
+            original_count,
+            original_received_count,
+        });
+    }
+
+    Ok(recovery)
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Returns element `index` of `shard`.
+#[cfg(feature = "alloc")]
+fn get_element(shard: &[u8], index: usize) -> Gf16 {
+    let pos = (index / 32) * 64 + index % 32;
+    Gf16(GfElement::from(shard[pos]) | (GfElement::from(shard[pos + 32]) << 8))
+}
+
+// Sets element `index` of `shard` to `value`.
+#[cfg(feature = "alloc")]
+fn set_element(shard: &mut [u8], index: usize, value: Gf16) {
+    let pos = (index / 32) * 64 + index % 32;
+    shard[pos] = value.0 as u8;
+    shard[pos + 32] = (value.0 >> 8) as u8;
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use super::*;
+    use crate::{engine::NoSimd, test_util};
+
+    // High rate without own `encode_one`, for testing the default implementation.
+    struct EncoderOnlyRate<E: Engine>(PhantomData<E>);
+
+    impl<E: Engine> Rate<E> for EncoderOnlyRate<E> {
+        type RateEncoder = HighRateEncoder<E>;
+        type RateDecoder = HighRateDecoder<E>;
+
+        fn supports(original_count: usize, recovery_count: usize) -> bool {
+            HighRate::<E>::supports(original_count, recovery_count)
+        }
+    }
+
+    fn check_generator_matrix<R: Rate<NoSimd>>(original_count: usize, recovery_count: usize) {
+        let matrix = R::generator_matrix(original_count, recovery_count, NoSimd::new()).unwrap();
+        let original = test_util::generate_original(original_count, 128, 0);
+
+        let mut encoder =
+            R::encoder(original_count, recovery_count, 128, NoSimd::new(), None).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let result = encoder.encode().unwrap();
+
+        for (i, recovery) in result.recovery_iter().enumerate() {
+            for e in 0..64 {
+                let expected: Gf16 = (0..original_count)
+                    .map(|j| matrix[i][j] * get_element(&original[j], e))
+                    .sum();
+                assert_eq!(get_element(recovery, e), expected);
+            }
+        }
+    }
+
+    fn check_encode_one<R: Rate<NoSimd>>(original_count: usize, recovery_count: usize) {
+        let engine = NoSimd::new();
+        let original = test_util::generate_original(original_count, 128, 0);
+
+        let mut encoder =
+            R::encoder(original_count, recovery_count, 128, NoSimd::new(), None).unwrap();
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        let result = encoder.encode().unwrap();
+
+        for (i, recovery) in result.recovery_iter().enumerate() {
+            let one = R::encode_one(original_count, recovery_count, i, &original, &engine).unwrap();
+            assert_eq!(one, recovery);
+        }
+    }
+
+    fn check_encode_one_errors<R: Rate<NoSimd>>() {
+        let engine = NoSimd::new();
+        let original = test_util::generate_original(3, 64, 0);
+
+        assert_eq!(
+            R::encode_one(0, 2, 0, &original, &engine),
+            Err(Error::UnsupportedShardCount {
+                original_count: 0,
+                recovery_count: 2,
+            })
+        );
+
+        assert_eq!(
+            R::encode_one(3, 2, 2, &original, &engine),
+            Err(Error::InvalidRecoveryShardIndex {
+                recovery_count: 2,
+                index: 2
+            })
+        );
+
+        assert_eq!(
+            R::encode_one(2, 2, 0, &original, &engine),
+            Err(Error::TooManyOriginalShards { original_count: 2 })
+        );
+
+        assert_eq!(
+            R::encode_one(4, 2, 0, &original, &engine),
+            Err(Error::TooFewOriginalShards {
+                original_count: 4,
+                original_received_count: 3,
+            })
+        );
+
+        assert_eq!(
+            R::encode_one(2, 2, 0, [&[0u8; 64][..]; 0], &engine),
+            Err(Error::TooFewOriginalShards {
+                original_count: 2,
+                original_received_count: 0,
+            })
+        );
+
+        assert_eq!(
+            R::encode_one(2, 2, 0, [&[0u8; 64][..], &[0u8; 128]], &engine),
+            Err(Error::DifferentShardSize {
+                shard_bytes: 64,
+                got: 128,
+            })
+        );
+
+        assert_eq!(
+            R::encode_one(1, 2, 0, [[0u8; 65]], &engine),
+            Err(Error::InvalidShardSize { shard_bytes: 65 })
+        );
+    }
+
+    // ============================================================
+    // encode_one / generator_matrix
+
+    #[test]
+    fn generator_matrix_default_rate() {
+        check_generator_matrix::<DefaultRate<NoSimd>>(3, 2);
+        check_generator_matrix::<DefaultRate<NoSimd>>(2, 3);
+    }
+
+    #[test]
+    fn generator_matrix_high_rate() {
+        check_generator_matrix::<HighRate<NoSimd>>(1, 1);
+        check_generator_matrix::<HighRate<NoSimd>>(40, 7);
+        check_generator_matrix::<HighRate<NoSimd>>(1100, 3);
+    }
+
+    #[test]
+    fn generator_matrix_low_rate() {
+        check_generator_matrix::<LowRate<NoSimd>>(1, 1);
+        check_generator_matrix::<LowRate<NoSimd>>(7, 40);
+    }
+
+    #[test]
+    fn encode_one_high_rate() {
+        check_encode_one::<HighRate<NoSimd>>(1, 1);
+        check_encode_one::<HighRate<NoSimd>>(40, 7);
+        check_encode_one::<HighRate<NoSimd>>(70, 20);
+    }
+
+    #[test]
+    fn encode_one_low_rate() {
+        check_encode_one::<LowRate<NoSimd>>(1, 1);
+        check_encode_one::<LowRate<NoSimd>>(7, 40);
+        check_encode_one::<LowRate<NoSimd>>(20, 70);
+    }
+
+    #[test]
+    fn encode_one_default_rate() {
+        check_encode_one::<DefaultRate<NoSimd>>(3, 5);
+        check_encode_one::<DefaultRate<NoSimd>>(5, 3);
+    }
+
+    #[test]
+    fn encode_one_encoder_only_rate() {
+        check_encode_one::<EncoderOnlyRate<NoSimd>>(1, 1);
+        check_encode_one::<EncoderOnlyRate<NoSimd>>(40, 7);
+        check_encode_one::<EncoderOnlyRate<NoSimd>>(70, 20);
+    }
+
+    #[test]
+    fn encode_one_errors() {
+        check_encode_one_errors::<HighRate<NoSimd>>();
+        check_encode_one_errors::<LowRate<NoSimd>>();
+        check_encode_one_errors::<EncoderOnlyRate<NoSimd>>();
+    }
+
+    #[test]
+    fn generator_matrix_unsupported() {
+        assert_eq!(
+            HighRate::<NoSimd>::generator_matrix(0, 1, NoSimd::new()),
+            Err(Error::UnsupportedShardCount {
+                original_count: 0,
+                recovery_count: 1,
+            })
+        );
+    }
+
+    // ============================================================
+    // use_high_rate
+
+    // `use_high_rate` is part of the data format,
+    // so these must never change.
+    #[test]
+    fn use_high_rate() {
+        fn err(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
+            Err(Error::UnsupportedShardCount {
+                original_count,
+                recovery_count,
+            })
+        }
+
+        for (original_count, recovery_count, expected) in [
+            (0, 1, err(0, 1)),
+            (1, 0, err(1, 0)),
+            // CORRECT RATE
+            (3, 5, Ok(false)),
+            (5, 3, Ok(true)),
+            (10, 256, Ok(false)),
+            (256, 10, Ok(true)),
+            (1, 65535, Ok(false)),
+            (65535, 1, Ok(true)),
+            // WRONG RATE, i.e. `Ordering::Equal`
+            (1, 1, Ok(true)),
+            (3, 3, Ok(true)),
+            (3, 4, Ok(true)),
+            (4, 3, Ok(false)),
+            (5, 8, Ok(true)),
+            (8, 5, Ok(false)),
+            (65, 128, Ok(true)),
+            (128, 65, Ok(false)),
+            (1000, 1000, Ok(true)),
+            (32768, 32768, Ok(true)),
+            // LOW RATE LIMIT
+            (4096, 61440, Ok(false)),
+            (4096, 61441, err(4096, 61441)),
+            (4097, 61440, err(4097, 61440)),
+            (1, 65536, err(1, 65536)),
+            // HIGH RATE LIMIT
+            (61440, 4096, Ok(true)),
+            (61440, 4097, err(61440, 4097)),
+            (61441, 4096, err(61441, 4096)),
+            (65536, 1, err(65536, 1)),
+            (32769, 32768, err(32769, 32768)),
+            // OVERFLOW CHECK
+            (usize::MAX, usize::MAX, err(usize::MAX, usize::MAX)),
+        ] {
+            assert_eq!(
+                super::use_high_rate(original_count, recovery_count),
+                expected,
+                "{} {}",
+                original_count,
+                recovery_count
+            );
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use alloc::vec::Vec;
+
 use fixedbitset::FixedBitSet;
 
 use crate::{
-    engine::{Shards, ShardsRefMut},
+    engine::{Engine, Shards, ShardsRefMut},
+    rate::error_correction::{self, Field, Gf16Field},
     Error,
 };
 
@@ -24,6 +27,13 @@ pub struct DecoderWork {
     // May contain extra zero bits.
     received: FixedBitSet,
     shards: Shards,
+
+    // Whether missing recovery shards were restored by latest decode.
+    recovery_restored: bool,
+
+    // Indexes of shards found corrupted by `correct_errors`.
+    corrupted_original: Vec<usize>,
+    corrupted_recovery: Vec<usize>,
 }
 
 impl DecoderWork {
@@ -42,6 +52,11 @@ impl DecoderWork {
             recovery_received_count: 0,
             received: FixedBitSet::new(),
             shards: Shards::new(),
+
+            recovery_restored: false,
+
+            corrupted_original: Vec::new(),
+            corrupted_recovery: Vec::new(),
         }
     }
 }
@@ -115,18 +130,109 @@ impl DecoderWork {
         }
     }
 
+    // Locates corrupted shards among received shards
+    // and marks them as not received.
+    // - This must be called before `decode_begin`.
+    pub(crate) fn correct_errors<E: Engine>(&mut self, engine: &E) -> Result<(), Error> {
+        self.correct_errors_in::<E, _>(&Gf16Field::new(engine))
+    }
+
+    // Like `correct_errors` but with arithmetic of given `field`.
+    pub(crate) fn correct_errors_in<E: Engine, F: Field>(
+        &mut self,
+        field: &F,
+    ) -> Result<(), Error> {
+        let received_count = self.original_received_count + self.recovery_received_count;
+        if received_count < self.original_count {
+            return Err(Error::NotEnoughShards {
+                original_count: self.original_count,
+                original_received_count: self.original_received_count,
+                recovery_received_count: self.recovery_received_count,
+            });
+        }
+
+        // Positions right after original shards up to the next shards
+        // (or end of working space) are known to be zero,
+        // other positions without received shards are erasures.
+        let work_count = self.shards.shard_count();
+        let original_end = self.original_base_pos + self.original_count;
+        let zero_end = if self.recovery_base_pos > self.original_base_pos {
+            self.recovery_base_pos
+        } else {
+            work_count
+        };
+
+        let mut received = Vec::with_capacity(received_count);
+        let mut erased = Vec::new();
+        for pos in 0..work_count {
+            if pos < self.received.len() && self.received[pos] {
+                received.push(pos);
+            } else if pos < original_end || pos >= zero_end {
+                erased.push(pos);
+            }
+        }
+
+        // Errors are located column by column, so merged `corrupted`
+        // can be larger than what any single column can correct.
+        let redundancy = received_count - self.original_count;
+        let corrupted = error_correction::locate_errors::<E, _>(
+            field,
+            &self.shards,
+            self.shard_bytes,
+            &received,
+            &erased,
+            redundancy,
+        )
+        .filter(|corrupted| corrupted.len() <= redundancy)
+        .ok_or(Error::TooManyCorruptedShards {
+            original_count: self.original_count,
+            original_received_count: self.original_received_count,
+            recovery_received_count: self.recovery_received_count,
+        })?;
+
+        for pos in corrupted {
+            self.received.set(pos, false);
+            if pos >= self.original_base_pos && pos < original_end {
+                self.original_received_count -= 1;
+                self.corrupted_original.push(pos - self.original_base_pos);
+            } else {
+                self.recovery_received_count -= 1;
+                self.corrupted_recovery.push(pos - self.recovery_base_pos);
+            }
+        }
+
+        Ok(())
+    }
+
+    // This must only be called by `DecoderResult`.
+    pub(crate) fn corrupted_original(&self) -> &[usize] {
+        &self.corrupted_original
+    }
+
+    // This must only be called by `DecoderResult`.
+    pub(crate) fn corrupted_recovery(&self) -> &[usize] {
+        &self.corrupted_recovery
+    }
+
     // Begin decode.
+    // - If `restore_recovery` is `true` then missing recovery shards
+    //   must be restored in addition to missing original shards.
     // - Returned `FixedBitSet` may contain extra zero bits.
     pub(crate) fn decode_begin(
         &mut self,
-    ) -> Result<Option<(ShardsRefMut, usize, usize, &FixedBitSet)>, Error> {
+        restore_recovery: bool,
+    ) -> Result<Option<(ShardsRefMut<'_>, usize, usize, &FixedBitSet)>, Error> {
+        self.recovery_restored = restore_recovery;
+
         if self.original_received_count + self.recovery_received_count < self.original_count {
             Err(Error::NotEnoughShards {
                 original_count: self.original_count,
                 original_received_count: self.original_received_count,
                 recovery_received_count: self.recovery_received_count,
             })
-        } else if self.original_received_count == self.original_count {
+        } else if self.original_received_count == self.original_count
+            && (!restore_recovery || self.recovery_received_count == self.recovery_count)
+        {
             Ok(None)
         } else {
             Ok(Some((
@@ -138,10 +244,28 @@ impl DecoderWork {
         }
     }
 
+    pub(crate) fn missing_original_indexes(&self) -> Vec<usize> {
+        (0..self.original_count)
+            .filter(|index| !self.received[self.original_base_pos + index])
+            .collect()
+    }
+
     pub(crate) fn original_count(&self) -> usize {
         self.original_count
     }
 
+    pub(crate) fn original_received_count(&self) -> usize {
+        self.original_received_count
+    }
+
+    pub(crate) fn recovery_count(&self) -> usize {
+        self.recovery_count
+    }
+
+    pub(crate) fn recovery_received_count(&self) -> usize {
+        self.recovery_received_count
+    }
+
     pub(crate) fn reset(
         &mut self,
         original_count: usize,
@@ -161,8 +285,11 @@ impl DecoderWork {
 
         self.original_received_count = 0;
         self.recovery_received_count = 0;
+        self.recovery_restored = false;
+        self.corrupted_original.clear();
+        self.corrupted_recovery.clear();
 
-        let max_received_pos = std::cmp::max(
+        let max_received_pos = core::cmp::max(
             original_base_pos + original_count,
             recovery_base_pos + recovery_count,
         );
@@ -179,6 +306,9 @@ impl DecoderWork {
         self.original_received_count = 0;
         self.recovery_received_count = 0;
         self.received.clear();
+        self.recovery_restored = false;
+        self.corrupted_original.clear();
+        self.corrupted_recovery.clear();
     }
 
     // This must only be called by `DecoderResult`.
@@ -191,4 +321,15 @@ impl DecoderWork {
             None
         }
     }
+
+    // This must only be called by `DecoderResult`.
+    pub(crate) fn restored_recovery(&self, index: usize) -> Option<&[u8]> {
+        let pos = self.recovery_base_pos + index;
+
+        if self.recovery_restored && index < self.recovery_count && !self.received[pos] {
+            Some(&self.shards[pos])
+        } else {
+            None
+        }
+    }
 }
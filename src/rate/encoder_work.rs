@@ -68,7 +68,7 @@ impl EncoderWork {
         }
     }
 
-    pub(crate) fn encode_begin(&mut self) -> Result<(ShardsRefMut, usize, usize), Error> {
+    pub(crate) fn encode_begin(&mut self) -> Result<(ShardsRefMut<'_>, usize, usize), Error> {
         if self.original_received_count != self.original_count {
             Err(Error::TooFewOriginalShards {
                 original_count: self.original_count,
@@ -92,6 +92,10 @@ impl EncoderWork {
         }
     }
 
+    pub(crate) fn recovery_count(&self) -> usize {
+        self.recovery_count
+    }
+
     pub(crate) fn reset(
         &mut self,
         original_count: usize,
@@ -110,4 +114,8 @@ impl EncoderWork {
     pub(crate) fn reset_received(&mut self) {
         self.original_received_count = 0;
     }
+
+    pub(crate) fn shard_bytes(&self) -> usize {
+        self.shard_bytes
+    }
 }
@@ -0,0 +1,268 @@
+use alloc::{vec, vec::Vec};
+
+use fixedbitset::FixedBitSet;
+
+use crate::engine::{
+    self,
+    tables::{self, Exp, Log},
+    Engine, GfElement, Shards,
+};
+
+// Locating corrupted shards at unknown positions.
+//
+// Both rates produce a Reed-Solomon codeword over work positions
+// `0..work_count`: Shard at position `i` is evaluation of a polynomial
+// at field element `i`. Some positions are known to be zero
+// and the rest of the positions which don't contain received shards
+// are erasures.
+//
+// Dropping erasures, the received shards and known zeros form
+// a Reed-Solomon code with `received_count - original_count` redundancy.
+// Each symbol column of the shards is a separate codeword
+// which is checked with syndromes and, if those are non-zero,
+// corrupted positions are located with Berlekamp-Massey
+// and an exhaustive root search over received positions.
+
+// ======================================================================
+// Field - CRATE
+
+// Galois field arithmetic needed for locating errors.
+//
+// Elements are given as `usize` so that same algorithm works
+// for both `GF(2^16)` and `GF(2^8)`.
+pub(crate) trait Field {
+    // Returns `x / y` where `y` is non-zero.
+    fn div_element(&self, x: usize, y: usize) -> usize;
+
+    // Returns `x * y`.
+    fn mul_element(&self, x: usize, y: usize) -> usize;
+
+    // `x[] *= y` where `y` is non-zero.
+    fn mul_shard(&self, x: &mut [u8], y: usize);
+
+    // Returns symbol at given `index` of `shard`.
+    fn symbol(shard: &[u8], index: usize) -> usize;
+
+    // Returns number of symbols in a shard of `shard_bytes` bytes.
+    fn symbol_count(shard_bytes: usize) -> usize;
+}
+
+// ======================================================================
+// Gf16Field - CRATE
+
+// `GF(2^16)` arithmetic using given `Engine` for shards.
+pub(crate) struct Gf16Field<'a, E: Engine> {
+    engine: &'a E,
+    exp: &'static Exp,
+    log: &'static Log,
+}
+
+impl<'a, E: Engine> Gf16Field<'a, E> {
+    pub(crate) fn new(engine: &'a E) -> Self {
+        let (exp, log) = tables::initialize_exp_log();
+        Self { engine, exp, log }
+    }
+}
+
+impl<'a, E: Engine> Field for Gf16Field<'a, E> {
+    fn div_element(&self, x: usize, y: usize) -> usize {
+        if x == 0 {
+            0
+        } else {
+            let log_m = engine::sub_mod(self.log[x], self.log[y]);
+            self.exp[log_m as usize] as usize
+        }
+    }
+
+    fn mul_element(&self, x: usize, y: usize) -> usize {
+        if y == 0 {
+            0
+        } else {
+            tables::mul(x as GfElement, self.log[y], self.exp, self.log) as usize
+        }
+    }
+
+    fn mul_shard(&self, x: &mut [u8], y: usize) {
+        self.engine.mul(x, self.log[y]);
+    }
+
+    // 16-bit symbols are split to low and high bytes,
+    // see [algorithm > Shard](crate::algorithm#shard).
+    fn symbol(shard: &[u8], index: usize) -> usize {
+        let lo = index / 32 * 64 + index % 32;
+        let hi = lo + 32;
+        (shard[lo] as usize) | ((shard[hi] as usize) << 8)
+    }
+
+    fn symbol_count(shard_bytes: usize) -> usize {
+        shard_bytes / 2
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - CRATE
+
+// Returns positions of corrupted shards or `None`
+// if there are more corrupted shards than can be located.
+//
+// - `received` contains positions of received shards.
+// - `erased` contains positions which are neither received nor known zeros.
+pub(crate) fn locate_errors<E: Engine, F: Field>(
+    field: &F,
+    shards: &Shards,
+    shard_bytes: usize,
+    received: &[usize],
+    erased: &[usize],
+    redundancy: usize,
+) -> Option<Vec<usize>> {
+    if redundancy == 0 {
+        return Some(Vec::new());
+    }
+
+    // SYNDROMES
+
+    // syndrome[l] = sum of shard[i] * u[i] * i^l over received `i`
+    // where `u[i]` are the dual code multipliers, up to a common constant.
+
+    let mut syndromes = Shards::new();
+    syndromes.resize(redundancy, shard_bytes);
+
+    let mut temp = vec![0u8; shard_bytes];
+
+    for &pos in received {
+        let mut u = 1;
+        for &erased_pos in erased {
+            u = field.mul_element(u, pos ^ erased_pos);
+        }
+
+        temp.copy_from_slice(&shards[pos]);
+        field.mul_shard(&mut temp, u);
+        E::xor(&mut syndromes[0], &temp);
+
+        if pos != 0 {
+            for l in 1..redundancy {
+                field.mul_shard(&mut temp, pos);
+                E::xor(&mut syndromes[l], &temp);
+            }
+        }
+    }
+
+    // LOCATE ERRORS COLUMN BY COLUMN
+
+    let mut corrupted = FixedBitSet::with_capacity(received.iter().max().unwrap() + 1);
+    let mut syndrome = vec![0; redundancy];
+
+    for column in 0..F::symbol_count(shard_bytes) {
+        for (l, s) in syndrome.iter_mut().enumerate() {
+            *s = F::symbol(&syndromes[l], column);
+        }
+
+        if syndrome.iter().all(|s| *s == 0) {
+            continue;
+        }
+
+        let (locator, error_count) = berlekamp_massey(field, &syndrome);
+        if error_count * 2 > redundancy {
+            return None;
+        }
+
+        // Roots of `x^error_count * locator(1/x)` are the error locations.
+
+        let mut found = 0;
+        for &pos in received {
+            let mut value = 0;
+            for coeff in &locator[..=error_count] {
+                value = field.mul_element(value, pos) ^ coeff;
+            }
+            if value == 0 {
+                corrupted.insert(pos);
+                found += 1;
+            }
+        }
+
+        if found != error_count {
+            return None;
+        }
+    }
+
+    Some(corrupted.ones().collect())
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+// Returns shortest LFSR `(connection_polynomial, length)` generating `s`.
+fn berlekamp_massey<F: Field>(field: &F, s: &[usize]) -> (Vec<usize>, usize) {
+    let n = s.len();
+
+    let mut c = vec![0; n + 1];
+    let mut b = vec![0; n + 1];
+    c[0] = 1;
+    b[0] = 1;
+
+    let mut length = 0;
+    let mut shift = 1;
+    let mut prev_discrepancy = 1;
+
+    for i in 0..n {
+        let mut discrepancy = s[i];
+        for j in 1..=length {
+            discrepancy ^= field.mul_element(c[j], s[i - j]);
+        }
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let coeff = field.div_element(discrepancy, prev_discrepancy);
+
+        if 2 * length <= i {
+            let prev_c = c.clone();
+            for j in 0..=n - shift {
+                c[j + shift] ^= field.mul_element(b[j], coeff);
+            }
+            length = i + 1 - length;
+            b = prev_c;
+            prev_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            for j in 0..=n - shift {
+                c[j + shift] ^= field.mul_element(b[j], coeff);
+            }
+            shift += 1;
+        }
+    }
+
+    (c, length)
+}
+
+// ======================================================================
+// TESTS
+
+// Error correction is tested via `decode_with_errors` tests of rates.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::NoSimd;
+
+    #[test]
+    fn berlekamp_massey_finds_single_error() {
+        let engine = NoSimd::new();
+        let field = Gf16Field::new(&engine);
+
+        // Single error with value `y` at location `x`: s[l] = y * x^l
+        let x = 1234;
+        let y = 4321;
+        let mut s = vec![y];
+        for l in 1..4 {
+            s.push(field.mul_element(s[l - 1], x));
+        }
+
+        let (c, length) = berlekamp_massey(&field, &s);
+        assert_eq!(length, 1);
+        assert_eq!(c[0], 1);
+        assert_eq!(field.mul_element(c[1], 1), x);
+    }
+}
@@ -1,78 +1,57 @@
-use std::{cmp::Ordering, marker::PhantomData};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 
 use crate::{
-    engine::{Engine, GF_ORDER},
+    engine::Engine,
     rate::{
-        DecoderWork, EncoderWork, HighRateDecoder, HighRateEncoder, LowRateDecoder, LowRateEncoder,
-        Rate, RateDecoder, RateEncoder,
+        use_high_rate, DecoderWork, EncoderWork, HighRate, HighRateDecoder, HighRateEncoder,
+        LowRate, LowRateDecoder, LowRateEncoder, Rate, RateDecoder, RateEncoder,
     },
     DecoderResult, EncoderResult, Error,
 };
 
-// ======================================================================
-// FUNCTIONS - PRIVATE
-
-fn use_high_rate(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
-    if original_count > GF_ORDER || recovery_count > GF_ORDER {
-        return Err(Error::UnsupportedShardCount {
-            original_count,
-            recovery_count,
-        });
-    }
-
-    let original_count_pow2 = original_count.next_power_of_two();
-    let recovery_count_pow2 = recovery_count.next_power_of_two();
-
-    let smaller_pow2 = std::cmp::min(original_count_pow2, recovery_count_pow2);
-    let larger = std::cmp::max(original_count, recovery_count);
-
-    if original_count == 0 || recovery_count == 0 || smaller_pow2 + larger > GF_ORDER {
-        return Err(Error::UnsupportedShardCount {
-            original_count,
-            recovery_count,
-        });
-    }
-
-    match original_count_pow2.cmp(&recovery_count_pow2) {
-        Ordering::Less => {
-            // The "correct" rate is generally faster here,
-            // and also must be used if `recovery_count > 32768`.
-
-            Ok(false)
-        }
-
-        Ordering::Greater => {
-            // The "correct" rate is generally faster here,
-            // and also must be used if `original_count > 32768`.
-
-            Ok(true)
-        }
-
-        Ordering::Equal => {
-            // Here counter-intuitively the "wrong" rate is generally faster
-            // in decoding if `original_count` and `recovery_count` differ a lot.
-
-            if original_count <= recovery_count {
-                // Using the "wrong" rate on purpose.
-                Ok(true)
-            } else {
-                // Using the "wrong" rate on purpose.
-                Ok(false)
-            }
-        }
-    }
-}
-
 // ======================================================================
 // DefaultRate - PUBLIC
 
 /// Reed-Solomon encoder/decoder generator using high or low rate as appropriate.
+///
+/// See [`use_high_rate`] for which rate is used.
 pub struct DefaultRate<E: Engine>(PhantomData<E>);
 
 impl<E: Engine> Rate<E> for DefaultRate<E> {
     type RateEncoder = DefaultRateEncoder<E>;
     type RateDecoder = DefaultRateDecoder<E>;
 
+    fn encode_one<T>(
+        original_count: usize,
+        recovery_count: usize,
+        recovery_index: usize,
+        original: T,
+        engine: &E,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: IntoIterator,
+        T::Item: AsRef<[u8]>,
+    {
+        if use_high_rate(original_count, recovery_count)? {
+            HighRate::encode_one(
+                original_count,
+                recovery_count,
+                recovery_index,
+                original,
+                engine,
+            )
+        } else {
+            LowRate::encode_one(
+                original_count,
+                recovery_count,
+                recovery_index,
+                original,
+                engine,
+            )
+        }
+    }
+
     fn supports(original_count: usize, recovery_count: usize) -> bool {
         use_high_rate(original_count, recovery_count).is_ok()
     }
@@ -81,20 +60,16 @@ impl<E: Engine> Rate<E> for DefaultRate<E> {
 // ======================================================================
 // InnerEncoder - PRIVATE
 
+#[derive(Default)]
 enum InnerEncoder<E: Engine> {
     High(HighRateEncoder<E>),
     Low(LowRateEncoder<E>),
 
     // This is only used temporarily during `reset`, never anywhere else.
+    #[default]
     None,
 }
 
-impl<E: Engine> Default for InnerEncoder<E> {
-    fn default() -> Self {
-        InnerEncoder::None
-    }
-}
-
 // ======================================================================
 // DefaultRateEncoder - PUBLIC
 
@@ -118,7 +93,7 @@ impl<E: Engine> RateEncoder<E> for DefaultRateEncoder<E> {
         }
     }
 
-    fn encode(&mut self) -> Result<EncoderResult, Error> {
+    fn encode(&mut self) -> Result<EncoderResult<'_>, Error> {
         match &mut self.0 {
             InnerEncoder::High(high) => high.encode(),
             InnerEncoder::Low(low) => low.encode(),
@@ -170,7 +145,7 @@ impl<E: Engine> RateEncoder<E> for DefaultRateEncoder<E> {
     ) -> Result<(), Error> {
         let new_rate_is_high = use_high_rate(original_count, recovery_count)?;
 
-        self.0 = match std::mem::take(&mut self.0) {
+        self.0 = match core::mem::take(&mut self.0) {
             InnerEncoder::High(mut high) => {
                 if new_rate_is_high {
                     high.reset(original_count, recovery_count, shard_bytes)?;
@@ -213,20 +188,16 @@ impl<E: Engine> RateEncoder<E> for DefaultRateEncoder<E> {
 // ======================================================================
 // InnerDecoder - PRIVATE
 
+#[derive(Default)]
 enum InnerDecoder<E: Engine> {
     High(HighRateDecoder<E>),
     Low(LowRateDecoder<E>),
 
     // This is only used temporarily during `reset`, never anywhere else.
+    #[default]
     None,
 }
 
-impl<E: Engine> Default for InnerDecoder<E> {
-    fn default() -> Self {
-        InnerDecoder::None
-    }
-}
-
 // ======================================================================
 // DefaultRateDecoder - PUBLIC
 
@@ -239,6 +210,140 @@ impl<E: Engine> Default for InnerDecoder<E> {
 /// [`ReedSolomonDecoder`]: crate::ReedSolomonDecoder
 pub struct DefaultRateDecoder<E: Engine>(InnerDecoder<E>);
 
+impl<E: Engine> DefaultRateDecoder<E> {
+    /// Returns `true` if this decoder decodes shards encoded with high rate
+    /// and `false` if it decodes shards encoded with low rate.
+    pub fn is_high_rate(&self) -> bool {
+        match &self.0 {
+            InnerDecoder::High(_) => true,
+            InnerDecoder::Low(_) => false,
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
+    /// Like [`RateDecoder::reset`] but with given rate,
+    /// see [`with_rate`](DefaultRateDecoder::with_rate).
+    pub fn reset_with_rate(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        high_rate: bool,
+    ) -> Result<(), Error> {
+        // Validate first so that `self` is left intact on error.
+        if high_rate {
+            HighRate::<E>::validate(original_count, recovery_count, shard_bytes)?;
+        } else {
+            LowRate::<E>::validate(original_count, recovery_count, shard_bytes)?;
+        }
+
+        self.0 = match core::mem::take(&mut self.0) {
+            InnerDecoder::High(mut high) => {
+                if high_rate {
+                    high.reset(original_count, recovery_count, shard_bytes)?;
+                    InnerDecoder::High(high)
+                } else {
+                    let (engine, work) = high.into_parts();
+                    InnerDecoder::Low(LowRateDecoder::new(
+                        original_count,
+                        recovery_count,
+                        shard_bytes,
+                        engine,
+                        Some(work),
+                    )?)
+                }
+            }
+
+            InnerDecoder::Low(mut low) => {
+                if high_rate {
+                    let (engine, work) = low.into_parts();
+                    InnerDecoder::High(HighRateDecoder::new(
+                        original_count,
+                        recovery_count,
+                        shard_bytes,
+                        engine,
+                        Some(work),
+                    )?)
+                } else {
+                    low.reset(original_count, recovery_count, shard_bytes)?;
+                    InnerDecoder::Low(low)
+                }
+            }
+
+            InnerDecoder::None => unreachable!(),
+        };
+
+        Ok(())
+    }
+
+    /// Like [`RateDecoder::new`] but decodes shards encoded with high rate
+    /// if `high_rate` is `true` and shards encoded with low rate otherwise,
+    /// instead of using the rate chosen by [`use_high_rate`].
+    ///
+    /// This allows decoding shards encoded with [`HighRateEncoder`]
+    /// or [`LowRateEncoder`] when other rate would be chosen by default.
+    /// Shards encoded with [`DefaultRate`] don't need this,
+    /// as [`use_high_rate`] is part of the data format.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::{
+    ///     engine::DefaultEngine,
+    ///     rate::{self, DefaultRateDecoder, HighRateEncoder, RateDecoder, RateEncoder},
+    /// };
+    ///
+    /// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+    ///
+    /// // `DefaultRate` would use low rate here.
+    /// assert_eq!(rate::use_high_rate(3, 5)?, false);
+    ///
+    /// let mut encoder = HighRateEncoder::new(3, 5, 64, DefaultEngine::new(), None)?;
+    /// for original in &original {
+    ///     encoder.add_original_shard(original)?;
+    /// }
+    /// let result = encoder.encode()?;
+    /// let recovery: Vec<_> = result.recovery_iter().collect();
+    ///
+    /// let mut decoder = DefaultRateDecoder::with_rate(3, 5, 64, DefaultEngine::new(), None, true)?;
+    /// decoder.add_recovery_shard(0, recovery[0])?;
+    /// decoder.add_recovery_shard(1, recovery[1])?;
+    /// decoder.add_recovery_shard(2, recovery[2])?;
+    ///
+    /// let result = decoder.decode()?;
+    /// assert_eq!(result.restored_original(1).unwrap(), original[1]);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    pub fn with_rate(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        engine: E,
+        work: Option<DecoderWork>,
+        high_rate: bool,
+    ) -> Result<Self, Error> {
+        let inner = if high_rate {
+            InnerDecoder::High(HighRateDecoder::new(
+                original_count,
+                recovery_count,
+                shard_bytes,
+                engine,
+                work,
+            )?)
+        } else {
+            InnerDecoder::Low(LowRateDecoder::new(
+                original_count,
+                recovery_count,
+                shard_bytes,
+                engine,
+                work,
+            )?)
+        };
+
+        Ok(Self(inner))
+    }
+}
+
 impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
     type Rate = DefaultRate<E>;
 
@@ -266,7 +371,7 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
         }
     }
 
-    fn decode(&mut self) -> Result<DecoderResult, Error> {
+    fn decode(&mut self) -> Result<DecoderResult<'_>, Error> {
         match &mut self.0 {
             InnerDecoder::High(high) => high.decode(),
             InnerDecoder::Low(low) => low.decode(),
@@ -274,6 +379,14 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
         }
     }
 
+    fn decode_with_errors(&mut self) -> Result<DecoderResult<'_>, Error> {
+        match &mut self.0 {
+            InnerDecoder::High(high) => high.decode_with_errors(),
+            InnerDecoder::Low(low) => low.decode_with_errors(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
     fn into_parts(self) -> (E, DecoderWork) {
         match self.0 {
             InnerDecoder::High(high) => high.into_parts(),
@@ -282,6 +395,14 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
         }
     }
 
+    fn missing_original_indexes(&self) -> Vec<usize> {
+        match &self.0 {
+            InnerDecoder::High(high) => high.missing_original_indexes(),
+            InnerDecoder::Low(low) => low.missing_original_indexes(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
     fn new(
         original_count: usize,
         recovery_count: usize,
@@ -289,25 +410,55 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
         engine: E,
         work: Option<DecoderWork>,
     ) -> Result<Self, Error> {
-        let inner = if use_high_rate(original_count, recovery_count)? {
-            InnerDecoder::High(HighRateDecoder::new(
-                original_count,
-                recovery_count,
-                shard_bytes,
-                engine,
-                work,
-            )?)
-        } else {
-            InnerDecoder::Low(LowRateDecoder::new(
-                original_count,
-                recovery_count,
-                shard_bytes,
-                engine,
-                work,
-            )?)
-        };
+        let high_rate = use_high_rate(original_count, recovery_count)?;
+        Self::with_rate(
+            original_count,
+            recovery_count,
+            shard_bytes,
+            engine,
+            work,
+            high_rate,
+        )
+    }
 
-        Ok(Self(inner))
+    fn original_count(&self) -> usize {
+        match &self.0 {
+            InnerDecoder::High(high) => high.original_count(),
+            InnerDecoder::Low(low) => low.original_count(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
+    fn original_received_count(&self) -> usize {
+        match &self.0 {
+            InnerDecoder::High(high) => high.original_received_count(),
+            InnerDecoder::Low(low) => low.original_received_count(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
+    fn recovery_count(&self) -> usize {
+        match &self.0 {
+            InnerDecoder::High(high) => high.recovery_count(),
+            InnerDecoder::Low(low) => low.recovery_count(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
+    fn recovery_received_count(&self) -> usize {
+        match &self.0 {
+            InnerDecoder::High(high) => high.recovery_received_count(),
+            InnerDecoder::Low(low) => low.recovery_received_count(),
+            InnerDecoder::None => unreachable!(),
+        }
+    }
+
+    fn repair(&mut self) -> Result<DecoderResult<'_>, Error> {
+        match &mut self.0 {
+            InnerDecoder::High(high) => high.repair(),
+            InnerDecoder::Low(low) => low.repair(),
+            InnerDecoder::None => unreachable!(),
+        }
     }
 
     fn reset(
@@ -316,45 +467,8 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
         recovery_count: usize,
         shard_bytes: usize,
     ) -> Result<(), Error> {
-        let new_rate_is_high = use_high_rate(original_count, recovery_count)?;
-
-        self.0 = match std::mem::take(&mut self.0) {
-            InnerDecoder::High(mut high) => {
-                if new_rate_is_high {
-                    high.reset(original_count, recovery_count, shard_bytes)?;
-                    InnerDecoder::High(high)
-                } else {
-                    let (engine, work) = high.into_parts();
-                    InnerDecoder::Low(LowRateDecoder::new(
-                        original_count,
-                        recovery_count,
-                        shard_bytes,
-                        engine,
-                        Some(work),
-                    )?)
-                }
-            }
-
-            InnerDecoder::Low(mut low) => {
-                if new_rate_is_high {
-                    let (engine, work) = low.into_parts();
-                    InnerDecoder::High(HighRateDecoder::new(
-                        original_count,
-                        recovery_count,
-                        shard_bytes,
-                        engine,
-                        Some(work),
-                    )?)
-                } else {
-                    low.reset(original_count, recovery_count, shard_bytes)?;
-                    InnerDecoder::Low(low)
-                }
-            }
-
-            InnerDecoder::None => unreachable!(),
-        };
-
-        Ok(())
+        let high_rate = use_high_rate(original_count, recovery_count)?;
+        self.reset_with_rate(original_count, recovery_count, shard_bytes, high_rate)
     }
 }
 
@@ -362,9 +476,10 @@ impl<E: Engine> RateDecoder<E> for DefaultRateDecoder<E> {
 // TESTS
 
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use super::*;
-    use crate::test_util;
+    use crate::{engine::NoSimd, test_util};
 
     // ============================================================
     // ROUNDTRIPS - SINGLE ROUND
@@ -379,7 +494,7 @@ mod tests {
                 1024,
                 recovery_hash,
                 &[*recovery_count..*original_count],
-                &[0..std::cmp::min(*original_count, *recovery_count)],
+                &[0..core::cmp::min(*original_count, *recovery_count)],
                 *seed,
             );
         }
@@ -439,41 +554,81 @@ mod tests {
     }
 
     // ============================================================
-    // use_high_rate
+    // with_rate
 
     #[test]
-    fn use_high_rate() {
-        fn err(original_count: usize, recovery_count: usize) -> Result<bool, Error> {
-            Err(Error::UnsupportedShardCount {
-                original_count,
-                recovery_count,
-            })
-        }
-
-        for (original_count, recovery_count, expected) in [
-            (0, 1, err(0, 1)),
-            (1, 0, err(1, 0)),
-            // CORRECT/WRONG RATE
-            (3, 3, Ok(true)),
-            (3, 4, Ok(true)),
-            (3, 5, Ok(false)),
-            (4, 3, Ok(false)),
-            (5, 3, Ok(true)),
-            // LOW RATE LIMIT
-            (4096, 61440, Ok(false)),
-            (4096, 61441, err(4096, 61441)),
-            (4097, 61440, err(4097, 61440)),
-            // HIGH RATE LIMIT
-            (61440, 4096, Ok(true)),
-            (61440, 4097, err(61440, 4097)),
-            (61441, 4096, err(61441, 4096)),
-            // OVERFLOW CHECK
-            (usize::MAX, usize::MAX, err(usize::MAX, usize::MAX)),
-        ] {
+    fn with_rate_decodes_other_rate() {
+        // (3, 5) uses low rate and (5, 3) uses high rate by default,
+        // so these decode shards encoded with the other rate.
+        for (original_count, recovery_count, high_rate) in [(3, 5, true), (5, 3, false)] {
             assert_eq!(
                 super::use_high_rate(original_count, recovery_count),
-                expected
+                Ok(!high_rate)
             );
+
+            let original = test_util::generate_original(original_count, 64, 0);
+            let recovery: Vec<Vec<u8>> = if high_rate {
+                let mut encoder =
+                    HighRateEncoder::new(original_count, recovery_count, 64, NoSimd::new(), None)
+                        .unwrap();
+                for original in &original {
+                    encoder.add_original_shard(original).unwrap();
+                }
+                let result = encoder.encode().unwrap();
+                result.recovery_iter().map(|s| s.to_vec()).collect()
+            } else {
+                let mut encoder =
+                    LowRateEncoder::new(original_count, recovery_count, 64, NoSimd::new(), None)
+                        .unwrap();
+                for original in &original {
+                    encoder.add_original_shard(original).unwrap();
+                }
+                let result = encoder.encode().unwrap();
+                result.recovery_iter().map(|s| s.to_vec()).collect()
+            };
+
+            let mut decoder = DefaultRateDecoder::with_rate(
+                original_count,
+                recovery_count,
+                64,
+                NoSimd::new(),
+                None,
+                high_rate,
+            )
+            .unwrap();
+            assert_eq!(decoder.is_high_rate(), high_rate);
+
+            // Missing originals are restored from the same number of recovery shards.
+            let missing = original_count.min(recovery_count);
+            for (i, original) in original.iter().enumerate().skip(missing) {
+                decoder.add_original_shard(i, original).unwrap();
+            }
+            for (i, recovery) in recovery.iter().enumerate().take(missing) {
+                decoder.add_recovery_shard(i, recovery).unwrap();
+            }
+
+            let result = decoder.decode().unwrap();
+            for (i, original) in original.iter().enumerate().take(missing) {
+                assert_eq!(result.restored_original(i).unwrap(), original);
+            }
         }
     }
+
+    #[test]
+    fn reset_with_rate() {
+        let mut decoder = DefaultRateDecoder::new(3, 5, 64, NoSimd::new(), None).unwrap();
+        assert!(!decoder.is_high_rate());
+
+        decoder.reset_with_rate(3, 5, 64, true).unwrap();
+        assert!(decoder.is_high_rate());
+
+        assert_eq!(
+            decoder.reset_with_rate(3, 5, 65, false),
+            Err(Error::InvalidShardSize { shard_bytes: 65 })
+        );
+        assert!(decoder.is_high_rate());
+
+        decoder.reset(3, 5, 64).unwrap();
+        assert!(!decoder.is_high_rate());
+    }
 }
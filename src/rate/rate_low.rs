@@ -1,8 +1,9 @@
-use std::marker::PhantomData;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 
 use crate::{
     engine::{self, Engine, GF_MODULUS, GF_ORDER},
-    rate::{DecoderWork, EncoderWork, Rate, RateDecoder, RateEncoder},
+    rate::{self, DecoderWork, EncoderWork, Field, Gf16Field, Rate, RateDecoder, RateEncoder},
     DecoderResult, EncoderResult, Error,
 };
 
@@ -16,6 +17,32 @@ impl<E: Engine> Rate<E> for LowRate<E> {
     type RateEncoder = LowRateEncoder<E>;
     type RateDecoder = LowRateDecoder<E>;
 
+    fn encode_one<T>(
+        original_count: usize,
+        recovery_count: usize,
+        recovery_index: usize,
+        original: T,
+        engine: &E,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: IntoIterator,
+        T::Item: AsRef<[u8]>,
+    {
+        let field = Gf16Field::new(engine);
+        rate::encode_one_with::<Self, _, _, _, _>(
+            original_count,
+            recovery_count,
+            recovery_index,
+            original,
+            &field,
+            |j| {
+                let (x, y) =
+                    rate::coefficient(false, original_count, recovery_count, j, recovery_index);
+                field.div_element(x, y)
+            },
+        )
+    }
+
     fn supports(original_count: usize, recovery_count: usize) -> bool {
         original_count > 0
             && recovery_count > 0
@@ -41,7 +68,7 @@ impl<E: Engine> RateEncoder<E> for LowRateEncoder<E> {
         self.work.add_original_shard(original_shard)
     }
 
-    fn encode(&mut self) -> Result<EncoderResult, Error> {
+    fn encode(&mut self) -> Result<EncoderResult<'_>, Error> {
         let (mut work, original_count, recovery_count) = self.work.encode_begin()?;
         let chunk_size = original_count.next_power_of_two();
         let engine = &self.engine;
@@ -165,12 +192,75 @@ impl<E: Engine> RateDecoder<E> for LowRateDecoder<E> {
         self.work.add_recovery_shard(index, recovery_shard)
     }
 
-    fn decode(&mut self) -> Result<DecoderResult, Error> {
+    fn decode(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.decode_private(false)
+    }
+
+    fn decode_with_errors(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.work.correct_errors(&self.engine)?;
+        self.decode()
+    }
+
+    fn into_parts(self) -> (E, DecoderWork) {
+        (self.engine, self.work)
+    }
+
+    fn missing_original_indexes(&self) -> Vec<usize> {
+        self.work.missing_original_indexes()
+    }
+
+    fn new(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        engine: E,
+        work: Option<DecoderWork>,
+    ) -> Result<Self, Error> {
+        let mut work = work.unwrap_or_default();
+        Self::reset_work(original_count, recovery_count, shard_bytes, &mut work)?;
+        Ok(Self { work, engine })
+    }
+
+    fn original_count(&self) -> usize {
+        self.work.original_count()
+    }
+
+    fn original_received_count(&self) -> usize {
+        self.work.original_received_count()
+    }
+
+    fn recovery_count(&self) -> usize {
+        self.work.recovery_count()
+    }
+
+    fn recovery_received_count(&self) -> usize {
+        self.work.recovery_received_count()
+    }
+
+    fn repair(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.decode_private(true)
+    }
+
+    fn reset(
+        &mut self,
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+    ) -> Result<(), Error> {
+        Self::reset_work(original_count, recovery_count, shard_bytes, &mut self.work)
+    }
+}
+
+// ======================================================================
+// LowRateDecoder - PRIVATE
+
+impl<E: Engine> LowRateDecoder<E> {
+    fn decode_private(&mut self, restore_recovery: bool) -> Result<DecoderResult<'_>, Error> {
         let (mut work, original_count, recovery_count, received) =
-            if let Some(stuff) = self.work.decode_begin()? {
+            if let Some(stuff) = self.work.decode_begin(restore_recovery)? {
                 stuff
             } else {
-                // Nothing to do, original data is complete.
+                // Nothing to do, all requested shards are complete.
                 return Ok(DecoderResult::new(&mut self.work));
             };
 
@@ -241,41 +331,19 @@ impl<E: Engine> RateDecoder<E> for LowRateDecoder<E> {
             }
         }
 
+        if restore_recovery {
+            for i in chunk_size..recovery_end {
+                if !received[i] {
+                    self.engine.mul(&mut work[i], GF_MODULUS - erasures[i]);
+                }
+            }
+        }
+
         // DONE
 
         Ok(DecoderResult::new(&mut self.work))
     }
 
-    fn into_parts(self) -> (E, DecoderWork) {
-        (self.engine, self.work)
-    }
-
-    fn new(
-        original_count: usize,
-        recovery_count: usize,
-        shard_bytes: usize,
-        engine: E,
-        work: Option<DecoderWork>,
-    ) -> Result<Self, Error> {
-        let mut work = work.unwrap_or_default();
-        Self::reset_work(original_count, recovery_count, shard_bytes, &mut work)?;
-        Ok(Self { work, engine })
-    }
-
-    fn reset(
-        &mut self,
-        original_count: usize,
-        recovery_count: usize,
-        shard_bytes: usize,
-    ) -> Result<(), Error> {
-        Self::reset_work(original_count, recovery_count, shard_bytes, &mut self.work)
-    }
-}
-
-// ======================================================================
-// LowRateDecoder - PRIVATE
-
-impl<E: Engine> LowRateDecoder<E> {
     fn reset_work(
         original_count: usize,
         recovery_count: usize,
@@ -309,6 +377,7 @@ impl<E: Engine> LowRateDecoder<E> {
 // TESTS
 
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use super::*;
     use crate::test_util;
@@ -345,7 +414,7 @@ mod tests {
                 1024,
                 recovery_hash,
                 &[*recovery_count..*original_count],
-                &[0..std::cmp::min(*original_count, *recovery_count)],
+                &[0..core::cmp::min(*original_count, *recovery_count)],
                 *seed,
             );
         }
@@ -429,6 +498,9 @@ mod tests {
             Error,
         };
 
+        test_rate_decoder_error_correction! {LowRate}
+        test_rate_decoder_repair! {LowRate}
+
         #[test]
         fn decoder() {
             assert!(LowRate::<NoSimd>::decoder(4096, 61440, 64, NoSimd::new(), None).is_ok());
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use crate::{
     engine::DefaultEngine,
     rate::{DefaultRate, DefaultRateDecoder, DefaultRateEncoder, Rate, RateDecoder, RateEncoder},
@@ -23,6 +25,21 @@ impl ReedSolomonEncoder {
         self.0.add_original_shard(original_shard)
     }
 
+    /// Like [`add_original_shard`] but also returns checksum of the shard.
+    ///
+    /// The returned checksum can be given to decoder with
+    /// [`ReedSolomonDecoder::add_original_shard_with_checksum`].
+    /// See [`checksum`] module for details.
+    ///
+    /// [`add_original_shard`]: ReedSolomonEncoder::add_original_shard
+    /// [`checksum`]: crate::checksum
+    pub fn add_original_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        original_shard: T,
+    ) -> Result<u32, Error> {
+        self.0.add_original_shard_with_checksum(original_shard)
+    }
+
     /// Encodes the added original shards returning [`EncoderResult`]
     /// which contains the generated recovery shards.
     ///
@@ -32,10 +49,26 @@ impl ReedSolomonEncoder {
     /// See [basic usage](crate#basic-usage) for an example.
     ///
     /// [`reset`]: ReedSolomonEncoder::reset
-    pub fn encode(&mut self) -> Result<EncoderResult, Error> {
+    pub fn encode(&mut self) -> Result<EncoderResult<'_>, Error> {
         self.0.encode()
     }
 
+    /// Encodes the added original shards and returns index
+    /// of the first given recovery shard which doesn't match
+    /// the generated recovery shard, or `None` if all match.
+    ///
+    /// This is like [`verify`] except that it
+    /// stops comparing at the first mismatch.
+    ///
+    /// [`verify`]: ReedSolomonEncoder::verify
+    pub fn find_mismatch<R, T>(&mut self, recovery: R) -> Result<Option<usize>, Error>
+    where
+        R: IntoIterator<Item = (usize, T)>,
+        T: AsRef<[u8]>,
+    {
+        self.0.find_mismatch(recovery)
+    }
+
     /// Creates new encoder with given configuration
     /// and allocates required working space.
     ///
@@ -82,6 +115,55 @@ impl ReedSolomonEncoder {
     pub fn supports(original_count: usize, recovery_count: usize) -> bool {
         DefaultRate::<DefaultEngine>::supports(original_count, recovery_count)
     }
+
+    /// Verifies that given recovery shards are consistent
+    /// with the added original shards.
+    ///
+    /// - All original shards must be added before calling this.
+    /// - Any subset of recovery shards can be given,
+    ///   with their indexes, in any order.
+    /// - Returns indexes of given recovery shards which don't match
+    ///   the recovery shards generated from the original shards,
+    ///   in the order they were given.
+    ///   Empty result means that all shards are consistent.
+    ///
+    /// Recovery shards are generated to working space of this encoder
+    /// and compared one at a time, so this doesn't allocate
+    /// a full set of recovery shards.
+    /// Afterwards the encoder is ready for new round of encoding,
+    /// like after dropping [`EncoderResult`].
+    ///
+    /// See also [`find_mismatch`] which stops at the first mismatch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::ReedSolomonEncoder;
+    ///
+    /// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+    /// let mut recovery = reed_solomon_16::encode(3, 2, original)?;
+    ///
+    /// // Recovery shard #1 has been corrupted.
+    /// recovery[1][0] ^= 1;
+    ///
+    /// let mut encoder = ReedSolomonEncoder::new(3, 2, 64)?;
+    /// for original in original {
+    ///     encoder.add_original_shard(original)?;
+    /// }
+    ///
+    /// let mismatch = encoder.verify(recovery.iter().enumerate())?;
+    /// assert_eq!(mismatch, [1]);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    ///
+    /// [`find_mismatch`]: ReedSolomonEncoder::find_mismatch
+    pub fn verify<R, T>(&mut self, recovery: R) -> Result<Vec<usize>, Error>
+    where
+        R: IntoIterator<Item = (usize, T)>,
+        T: AsRef<[u8]>,
+    {
+        self.0.verify(recovery)
+    }
 }
 
 // ======================================================================
@@ -107,6 +189,25 @@ impl ReedSolomonDecoder {
         self.0.add_original_shard(index, original_shard)
     }
 
+    /// Like [`add_original_shard`] but first verifies
+    /// that the shard matches the given `checksum`.
+    ///
+    /// Returns [`Error::ChecksumMismatch`] if it doesn't,
+    /// in which case the shard is not added.
+    /// See [`checksum`] module for details.
+    ///
+    /// [`add_original_shard`]: ReedSolomonDecoder::add_original_shard
+    /// [`checksum`]: crate::checksum
+    pub fn add_original_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        original_shard: T,
+        checksum: u32,
+    ) -> Result<(), Error> {
+        self.0
+            .add_original_shard_with_checksum(index, original_shard, checksum)
+    }
+
     /// Adds one recovery shard to the decoder.
     ///
     /// - Shards can be added in any order.
@@ -121,6 +222,33 @@ impl ReedSolomonDecoder {
         self.0.add_recovery_shard(index, recovery_shard)
     }
 
+    /// Like [`add_recovery_shard`] but first verifies
+    /// that the shard matches the given `checksum`.
+    ///
+    /// Returns [`Error::ChecksumMismatch`] if it doesn't,
+    /// in which case the shard is not added.
+    /// See [`checksum`] module for details.
+    ///
+    /// [`add_recovery_shard`]: ReedSolomonDecoder::add_recovery_shard
+    /// [`checksum`]: crate::checksum
+    pub fn add_recovery_shard_with_checksum<T: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        recovery_shard: T,
+        checksum: u32,
+    ) -> Result<(), Error> {
+        self.0
+            .add_recovery_shard_with_checksum(index, recovery_shard, checksum)
+    }
+
+    /// Returns `true` if enough shards have been added for decoding,
+    /// i.e. [`decode`] won't return [`Error::NotEnoughShards`].
+    ///
+    /// [`decode`]: ReedSolomonDecoder::decode
+    pub fn can_decode(&self) -> bool {
+        self.0.can_decode()
+    }
+
     /// Decodes the added shards returning [`DecoderResult`]
     /// which contains the restored original shards.
     ///
@@ -130,10 +258,92 @@ impl ReedSolomonDecoder {
     /// See [basic usage](crate#basic-usage) for an example.
     ///
     /// [`reset`]: ReedSolomonDecoder::reset
-    pub fn decode(&mut self) -> Result<DecoderResult, Error> {
+    pub fn decode(&mut self) -> Result<DecoderResult<'_>, Error> {
         self.0.decode()
     }
 
+    /// Like [`decode`] but also corrects corrupted shards at unknown positions.
+    ///
+    /// Shards given to decoder beyond the required `original_count`
+    /// are used to locate corrupted shards:
+    /// With `original_count + n` shards given, up to `n / 2`
+    /// corrupted shards are located and then treated as missing.
+    /// Corrupted original shards are restored and all corrupted
+    /// shards are reported by [`DecoderResult::corrupted_original`]
+    /// and [`DecoderResult::corrupted_recovery`].
+    ///
+    /// - Returns [`Error::TooManyCorruptedShards`] if
+    ///   corrupted shards were detected but can't be corrected.
+    /// - If there are more than `n / 2` corrupted shards
+    ///   they may be left undetected or corrected wrongly.
+    /// - This is much slower than [`decode`], taking roughly
+    ///   `O(n * (original_count + n))` shard multiplications.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::{ReedSolomonDecoder, ReedSolomonEncoder};
+    ///
+    /// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+    /// let recovery = reed_solomon_16::encode(3, 2, original)?;
+    ///
+    /// let mut decoder = ReedSolomonDecoder::new(3, 2, 64)?;
+    ///
+    /// // Original shard #1 has been silently corrupted.
+    /// let mut corrupted = original[1];
+    /// corrupted[5] ^= 0x40;
+    ///
+    /// decoder.add_original_shard(0, original[0])?;
+    /// decoder.add_original_shard(1, corrupted)?;
+    /// decoder.add_original_shard(2, original[2])?;
+    /// decoder.add_recovery_shard(0, &recovery[0])?;
+    /// decoder.add_recovery_shard(1, &recovery[1])?;
+    ///
+    /// let result = decoder.decode_with_errors()?;
+    /// assert_eq!(result.corrupted_original(), &[1]);
+    /// assert_eq!(result.restored_original(1).unwrap(), original[1]);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    ///
+    /// [`decode`]: ReedSolomonDecoder::decode
+    pub fn decode_with_errors(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.0.decode_with_errors()
+    }
+
+    /// Returns indexes of original shards which haven't been added,
+    /// in ascending order.
+    ///
+    /// These are the original shards which decoding will restore.
+    pub fn missing_original_indexes(&self) -> Vec<usize> {
+        self.0.missing_original_indexes()
+    }
+
+    /// Returns number of additional shards needed before decoding is possible,
+    /// in any combination of original shards and recovery shards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::ReedSolomonDecoder;
+    ///
+    /// let mut decoder = ReedSolomonDecoder::new(3, 2, 64)?;
+    /// decoder.add_original_shard(1, [0u8; 64])?;
+    ///
+    /// assert_eq!(decoder.needed_shard_count(), 2);
+    /// assert_eq!(decoder.can_decode(), false);
+    /// assert_eq!(decoder.missing_original_indexes(), [0, 2]);
+    ///
+    /// decoder.add_recovery_shard(0, [0u8; 64])?;
+    /// decoder.add_recovery_shard(1, [0u8; 64])?;
+    ///
+    /// assert_eq!(decoder.needed_shard_count(), 0);
+    /// assert_eq!(decoder.can_decode(), true);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    pub fn needed_shard_count(&self) -> usize {
+        self.0.needed_shard_count()
+    }
+
     /// Creates new decoder with given configuration
     /// and allocates required working space.
     ///
@@ -152,6 +362,60 @@ impl ReedSolomonDecoder {
         )?))
     }
 
+    /// Returns configured number of original shards.
+    pub fn original_count(&self) -> usize {
+        self.0.original_count()
+    }
+
+    /// Returns number of original shards added since
+    /// creation or latest reset/decode.
+    pub fn original_received_count(&self) -> usize {
+        self.0.original_received_count()
+    }
+
+    /// Returns configured number of recovery shards.
+    pub fn recovery_count(&self) -> usize {
+        self.0.recovery_count()
+    }
+
+    /// Returns number of recovery shards added since
+    /// creation or latest reset/decode.
+    pub fn recovery_received_count(&self) -> usize {
+        self.0.recovery_received_count()
+    }
+
+    /// Like [`decode`] but also restores missing recovery shards,
+    /// returning [`DecoderResult`] which contains both
+    /// the restored original shards and the restored recovery shards.
+    ///
+    /// This gives a complete set of shards without a separate
+    /// round of encoding and with only minor extra cost over [`decode`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use reed_solomon_16::ReedSolomonDecoder;
+    ///
+    /// let original = [[1u8; 64], [2u8; 64], [3u8; 64]];
+    /// let recovery = reed_solomon_16::encode(3, 3, original)?;
+    ///
+    /// let mut decoder = ReedSolomonDecoder::new(3, 3, 64)?;
+    /// decoder.add_original_shard(0, original[0])?;
+    /// decoder.add_original_shard(2, original[2])?;
+    /// decoder.add_recovery_shard(1, &recovery[1])?;
+    ///
+    /// let result = decoder.repair()?;
+    /// assert_eq!(result.restored_original(1).unwrap(), original[1]);
+    /// assert_eq!(result.restored_recovery(0).unwrap(), recovery[0]);
+    /// assert_eq!(result.restored_recovery(2).unwrap(), recovery[2]);
+    /// # Ok::<(), reed_solomon_16::Error>(())
+    /// ```
+    ///
+    /// [`decode`]: ReedSolomonDecoder::decode
+    pub fn repair(&mut self) -> Result<DecoderResult<'_>, Error> {
+        self.0.repair()
+    }
+
     /// Resets decoder to given configuration.
     ///
     /// - Added shards are forgotten.
@@ -270,6 +534,151 @@ mod tests {
         );
     }
 
+    // ============================================================
+    // CHECKSUMS
+
+    #[test]
+    fn roundtrip_with_checksums() {
+        let original = test_util::generate_original(3, 1024, 0);
+
+        let mut encoder = ReedSolomonEncoder::new(3, 2, 1024).unwrap();
+        let mut decoder = ReedSolomonDecoder::new(3, 2, 1024).unwrap();
+
+        let original_checksums: Vec<_> = original
+            .iter()
+            .map(|original| encoder.add_original_shard_with_checksum(original).unwrap())
+            .collect();
+
+        for (original, checksum) in original.iter().zip(&original_checksums) {
+            assert_eq!(crate::checksum::crc32c(original), *checksum);
+        }
+
+        let result = encoder.encode().unwrap();
+        let recovery: Vec<_> = result.recovery_iter().map(|s| s.to_vec()).collect();
+        let recovery_checksums: Vec<_> = (0..2)
+            .map(|i| result.recovery_checksum(i).unwrap())
+            .collect();
+        assert!(result.recovery_checksum(2).is_none());
+        drop(result);
+
+        let mut corrupted = recovery[0].clone();
+        corrupted[100] ^= 0x10;
+
+        assert_eq!(
+            decoder.add_recovery_shard_with_checksum(0, &corrupted, recovery_checksums[0]),
+            Err(Error::ChecksumMismatch {
+                index: 0,
+                kind: crate::ShardKind::Recovery,
+            })
+        );
+
+        decoder
+            .add_original_shard_with_checksum(1, &original[1], original_checksums[1])
+            .unwrap();
+        decoder
+            .add_recovery_shard_with_checksum(0, &recovery[0], recovery_checksums[0])
+            .unwrap();
+        decoder
+            .add_recovery_shard_with_checksum(1, &recovery[1], recovery_checksums[1])
+            .unwrap();
+
+        let result = decoder.decode().unwrap();
+        assert_eq!(result.restored_original(0).unwrap(), original[0]);
+        assert_eq!(result.restored_original(2).unwrap(), original[2]);
+    }
+
+    // ============================================================
+    // STATUS
+
+    #[test]
+    fn status() {
+        // (3, 2) uses high rate and (2, 3) uses low rate.
+        for (original_count, recovery_count) in [(3, 2), (2, 3)] {
+            let mut decoder = ReedSolomonDecoder::new(original_count, recovery_count, 64).unwrap();
+
+            assert_eq!(decoder.original_count(), original_count);
+            assert_eq!(decoder.recovery_count(), recovery_count);
+            assert_eq!(decoder.original_received_count(), 0);
+            assert_eq!(decoder.recovery_received_count(), 0);
+            assert_eq!(decoder.needed_shard_count(), original_count);
+            assert!(!decoder.can_decode());
+            assert_eq!(
+                decoder.missing_original_indexes(),
+                (0..original_count).collect::<Vec<_>>()
+            );
+
+            decoder.add_original_shard(1, [0; 64]).unwrap();
+            decoder.add_recovery_shard(0, [0; 64]).unwrap();
+
+            assert_eq!(decoder.original_received_count(), 1);
+            assert_eq!(decoder.recovery_received_count(), 1);
+            assert_eq!(decoder.needed_shard_count(), original_count - 2);
+            assert_eq!(decoder.can_decode(), original_count == 2);
+            assert!(!decoder.missing_original_indexes().contains(&1));
+
+            decoder.add_recovery_shard(1, [0; 64]).unwrap();
+
+            assert_eq!(decoder.needed_shard_count(), 0);
+            assert!(decoder.can_decode());
+
+            drop(decoder.decode().unwrap());
+
+            assert_eq!(decoder.original_received_count(), 0);
+            assert_eq!(decoder.recovery_received_count(), 0);
+        }
+    }
+
+    // ============================================================
+    // VERIFY
+
+    #[test]
+    fn verify() {
+        let original = test_util::generate_original(5, 1024, 0);
+        let recovery = crate::encode(5, 4, &original).unwrap();
+
+        let mut encoder = ReedSolomonEncoder::new(5, 4, 1024).unwrap();
+
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        assert_eq!(
+            encoder.verify(recovery.iter().enumerate()).unwrap(),
+            [0usize; 0]
+        );
+
+        let mut corrupted = recovery.clone();
+        corrupted[1][0] ^= 1;
+        corrupted[3][1023] ^= 0x80;
+
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        assert_eq!(
+            encoder.verify(corrupted.iter().enumerate().rev()).unwrap(),
+            [3, 1]
+        );
+
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        assert_eq!(
+            encoder
+                .find_mismatch([(0, &corrupted[0]), (1, &corrupted[1]), (3, &corrupted[3])])
+                .unwrap(),
+            Some(1)
+        );
+
+        for original in &original {
+            encoder.add_original_shard(original).unwrap();
+        }
+        assert_eq!(
+            encoder
+                .find_mismatch([(2, &corrupted[2]), (0, &corrupted[0])])
+                .unwrap(),
+            None
+        );
+    }
+
     // ==================================================
     // supports
 
@@ -0,0 +1,918 @@
+//! Striped encoding of byte streams with [`std::io`].
+//!
+//! Requires `std` feature.
+//!
+//! [`EncodingWriter`] accepts a byte stream of any length through
+//! [`Write`] and writes each shard to its own sink, so the whole
+//! stream never needs to be in memory. [`DecodingReader`] is its counterpart
+//! which reads the shards back, restoring the original byte stream through
+//! [`Read`] even if some shards are missing or fail mid-stream.
+//!
+//! # Stream format
+//!
+//! - The stream is cut into **stripes** of `original_count * shard_bytes` bytes.
+//!   Original shard `i` of a stripe is bytes
+//!   `i * shard_bytes .. (i + 1) * shard_bytes` of the stripe.
+//! - Each stripe is encoded separately and each of the
+//!   `original_count + recovery_count` shards of a stripe is written
+//!   to its own sink, i.e. each sink gets one shard per stripe.
+//! - Final stripe is padded with zero bytes so that it ends with
+//!   an 8-byte **length trailer**: total length of the stream in bytes
+//!   as little-endian `u64`. If final partial stripe doesn't have room for
+//!   the trailer, one more stripe is added. So there is always at least
+//!   one stripe, even if the stream is empty.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::io::{Read, Write};
+//! use reed_solomon_16::stream::{DecodingReader, EncodingWriter};
+//!
+//! // 2 original shards and 1 recovery shard, each 64 bytes per stripe.
+//! let mut writer = EncodingWriter::new(vec![Vec::new(); 2], vec![Vec::new(); 1], 64)?;
+//! writer.write_all(&[1u8; 200])?;
+//! let (original, recovery) = writer.finish()?;
+//!
+//! // 200 bytes + 8-byte trailer needs two stripes of 128 bytes.
+//! assert_eq!(original[0].len(), 128);
+//! assert_eq!(recovery[0].len(), 128);
+//!
+//! // Lose original shard 0 and read the stream back.
+//! let original = vec![None, Some(&original[1][..])];
+//! let recovery = vec![Some(&recovery[0][..])];
+//! let mut reader = DecodingReader::new(original, recovery, 64)?;
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data)?;
+//! assert_eq!(data, [1u8; 200]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    io::{self, Read, Write},
+    mem,
+};
+
+use crate::{Error, ReedSolomonDecoder, ReedSolomonEncoder};
+
+// ======================================================================
+// CONST - PUBLIC
+
+/// Size of the length trailer at the end of final stripe in bytes.
+pub const TRAILER_BYTES: usize = 8;
+
+// ======================================================================
+// DecodingReader - PUBLIC
+
+/// [`Read`] which reads shards from per-shard readers
+/// and restores the original byte stream.
+///
+/// - Stream is read stripe by stripe. When all original shards
+///   of a stripe are read successfully, they are passed through
+///   without decoding and recovery readers are not read at all.
+/// - Otherwise recovery shards are read as needed and the stripe is
+///   decoded with a reused [`ReedSolomonDecoder`]. Recovery readers
+///   which weren't read for earlier stripes first skip those.
+/// - Readers which fail are dropped and not used for later stripes.
+/// - Final stripe is detected by original readers ending cleanly at a stripe
+///   boundary right after a stripe whose length trailer matches the number
+///   of stripes read. Its length trailer is checked and trimmed, along with
+///   the padding. If original readers end elsewhere, e.g. because original
+///   shard streams were truncated, recovery readers are tried instead.
+/// - Any error returned by [`read`] is final and later calls return an error.
+///
+/// See [module documentation](crate::stream) for the stream format and an example.
+///
+/// [`read`]: DecodingReader::read
+pub struct DecodingReader<R: Read> {
+    decoder: ReedSolomonDecoder,
+    original: Vec<Option<ShardReader<R>>>,
+    recovery: Vec<Option<ShardReader<R>>>,
+    shard_bytes: usize,
+    recovery_shard: Vec<u8>,
+    // Whether each original shard of the stripe being read was received.
+    received: Vec<bool>,
+
+    // Last bytes of previous stripe, of which `held[held_pos..held_len]`
+    // is still unread. These are held back until it's known whether
+    // they are padding before a final stripe which has only the trailer.
+    held: [u8; TRAILER_BYTES],
+    held_pos: usize,
+    held_len: usize,
+    // Stripe being read, of which `current[pos..end]` is still unread.
+    current: Vec<u8>,
+    has_current: bool,
+    pos: usize,
+    end: usize,
+    // Next stripe, needed to know if `current` is the final stripe.
+    next: Vec<u8>,
+    has_next: bool,
+
+    next_stripe_index: u64,
+    read_bytes: u64,
+    decoded_stripe_count: u64,
+    started: bool,
+    finished: bool,
+    failed: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Returns number of stripes which have been decoded so far,
+    /// i.e. which didn't have all original shards available.
+    pub fn decoded_stripe_count(&self) -> u64 {
+        self.decoded_stripe_count
+    }
+
+    /// Creates new reader which reads original shards from `original` readers
+    /// and recovery shards from `recovery` readers, `None` marking missing ones.
+    ///
+    /// Shard counts are given by the number of readers and each stripe
+    /// has `shard_bytes` bytes per shard, see [`ReedSolomonDecoder::new`].
+    pub fn new(
+        original: Vec<Option<R>>,
+        recovery: Vec<Option<R>>,
+        shard_bytes: usize,
+    ) -> Result<Self, Error> {
+        let decoder = ReedSolomonDecoder::new(original.len(), recovery.len(), shard_bytes)?;
+        let stripe_bytes = original.len() * shard_bytes;
+        let original_count = original.len();
+
+        Ok(Self {
+            decoder,
+            original: original.into_iter().map(ShardReader::new).collect(),
+            recovery: recovery.into_iter().map(ShardReader::new).collect(),
+            shard_bytes,
+            recovery_shard: vec![0; shard_bytes],
+            received: vec![false; original_count],
+
+            held: [0; TRAILER_BYTES],
+            held_pos: 0,
+            held_len: 0,
+            current: vec![0; stripe_bytes],
+            has_current: false,
+            pos: 0,
+            end: 0,
+            next: vec![0; stripe_bytes],
+            has_next: false,
+
+            next_stripe_index: 0,
+            read_bytes: 0,
+            decoded_stripe_count: 0,
+            started: false,
+            finished: false,
+            failed: false,
+        })
+    }
+
+    // ============================================================
+    // PRIVATE
+
+    fn stripe_bytes(&self) -> usize {
+        self.original.len() * self.shard_bytes
+    }
+
+    // Makes sure that `held[held_pos..held_len]` or `current[pos..end]`
+    // is non-empty unless at end of stream.
+    fn fill(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.read_next()?;
+        }
+
+        while self.held_pos == self.held_len && self.pos == self.end && !self.finished {
+            if !self.has_next {
+                return Err(invalid_data("stream has no final stripe"));
+            }
+
+            let stripe_bytes = self.stripe_bytes();
+            let data_bytes = stripe_bytes - TRAILER_BYTES;
+
+            if self.has_current {
+                self.held.copy_from_slice(&self.current[data_bytes..]);
+                self.held_pos = 0;
+                self.held_len = TRAILER_BYTES;
+            }
+
+            mem::swap(&mut self.current, &mut self.next);
+            self.has_current = true;
+            self.read_next()?;
+            self.pos = 0;
+
+            if self.has_next {
+                self.end = data_bytes;
+            } else {
+                let mut trailer = [0; TRAILER_BYTES];
+                trailer.copy_from_slice(&self.current[data_bytes..]);
+                let total_bytes = u64::from_le_bytes(trailer);
+
+                let final_bytes = total_bytes
+                    .checked_sub(self.read_bytes)
+                    .filter(|bytes| *bytes <= (self.held_len + data_bytes) as u64)
+                    .ok_or_else(|| invalid_data("invalid length trailer"))?
+                    as usize;
+
+                if final_bytes <= self.held_len {
+                    self.held_len = final_bytes;
+                    self.end = 0;
+                } else {
+                    self.end = final_bytes - self.held_len;
+                }
+                self.finished = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Returns `true` if `current` is the final stripe of a stream
+    // of `stripe_count` stripes according to its length trailer.
+    fn is_final_stripe(&self, stripe_count: u64) -> bool {
+        if !self.has_current {
+            return false;
+        }
+
+        let stripe_bytes = self.stripe_bytes();
+        let data_bytes = stripe_bytes - TRAILER_BYTES;
+
+        let mut trailer = [0; TRAILER_BYTES];
+        trailer.copy_from_slice(&self.current[data_bytes..]);
+        let total_bytes = u64::from_le_bytes(trailer);
+
+        // Full stripes, then final stripe with the trailer. If the tail
+        // doesn't fit before the trailer, it gets a stripe of its own.
+        let full_stripe_count = total_bytes / stripe_bytes as u64;
+        let tail_bytes = total_bytes % stripe_bytes as u64;
+        let expected = if tail_bytes > data_bytes as u64 {
+            full_stripe_count + 2
+        } else {
+            full_stripe_count + 1
+        };
+
+        stripe_count == expected
+    }
+
+    // Reads next stripe into `next`, setting `has_next`.
+    fn read_next(&mut self) -> io::Result<()> {
+        let mut next = mem::take(&mut self.next);
+        let result = self.read_stripe(&mut next);
+        self.next = next;
+        self.has_next = result?;
+        Ok(())
+    }
+
+    // Reads stripe `next_stripe_index` into `stripe`, decoding if needed.
+    //
+    // Returns `Ok(false)` if shard streams have ended.
+    //
+    // When all original shard streams have ended right after the final
+    // stripe, recovery shard streams are not read. Otherwise they are tried
+    // in case original shard streams have been truncated, stopping at
+    // first recovery shard stream which has also ended.
+    fn read_stripe(&mut self, stripe: &mut [u8]) -> io::Result<bool> {
+        let index = self.next_stripe_index;
+        let original_count = self.original.len();
+        let mut ended = false;
+
+        for ((reader, shard), received) in self
+            .original
+            .iter_mut()
+            .zip(stripe.chunks_exact_mut(self.shard_bytes))
+            .zip(self.received.iter_mut())
+        {
+            *received = read_shard(reader, index, shard, &mut ended);
+        }
+
+        let original_received_count = self.received.iter().filter(|r| **r).count();
+
+        if original_received_count == original_count {
+            self.next_stripe_index += 1;
+            return Ok(true);
+        } else if original_received_count == 0 && ended && self.is_final_stripe(index) {
+            return Ok(false);
+        }
+
+        // NOT ALL ORIGINAL SHARDS RECEIVED - USE RECOVERY SHARDS
+
+        let mut recovery_received_count = 0;
+        for (recovery_index, reader) in self.recovery.iter_mut().enumerate() {
+            if original_received_count + recovery_received_count == original_count {
+                break;
+            }
+
+            let mut recovery_ended = false;
+            if read_shard(reader, index, &mut self.recovery_shard, &mut recovery_ended) {
+                self.decoder
+                    .add_recovery_shard(recovery_index, &self.recovery_shard)
+                    .map_err(io::Error::other)?;
+                recovery_received_count += 1;
+            } else if recovery_ended {
+                ended = true;
+                if original_received_count + recovery_received_count == 0 {
+                    break;
+                }
+            }
+        }
+
+        if original_received_count + recovery_received_count == 0 && ended {
+            return Ok(false);
+        } else if original_received_count + recovery_received_count < original_count {
+            return Err(io::Error::other(Error::NotEnoughShards {
+                original_count,
+                original_received_count,
+                recovery_received_count,
+            }));
+        }
+
+        for (original_index, shard) in stripe.chunks_exact(self.shard_bytes).enumerate() {
+            if self.received[original_index] {
+                self.decoder
+                    .add_original_shard(original_index, shard)
+                    .map_err(io::Error::other)?;
+            }
+        }
+
+        let result = self.decoder.decode().map_err(io::Error::other)?;
+        for (original_index, restored) in result.restored_original_iter() {
+            let pos = original_index * self.shard_bytes;
+            stripe[pos..pos + self.shard_bytes].copy_from_slice(restored);
+        }
+
+        self.decoded_stripe_count += 1;
+        self.next_stripe_index += 1;
+        Ok(true)
+    }
+}
+
+// ======================================================================
+// DecodingReader - IMPL READ
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.failed {
+            return Err(io::Error::other("earlier read failed"));
+        }
+
+        if let Err(err) = self.fill() {
+            self.failed = true;
+            return Err(err);
+        }
+
+        let count = if self.held_pos < self.held_len {
+            let count = buf.len().min(self.held_len - self.held_pos);
+            buf[..count].copy_from_slice(&self.held[self.held_pos..self.held_pos + count]);
+            self.held_pos += count;
+            count
+        } else {
+            let count = buf.len().min(self.end - self.pos);
+            buf[..count].copy_from_slice(&self.current[self.pos..self.pos + count]);
+            self.pos += count;
+            count
+        };
+
+        self.read_bytes += count as u64;
+        Ok(count)
+    }
+}
+
+// ======================================================================
+// EncodingWriter - PUBLIC
+
+/// [`Write`] which encodes a byte stream in stripes
+/// and writes shards to per-shard sinks.
+///
+/// - [`finish`] must be called after all data has been written,
+///   to write the final stripe with the length trailer.
+/// - After any error the stream is incomplete and should be discarded.
+///
+/// See [module documentation](crate::stream) for the stream format and an example.
+///
+/// [`finish`]: EncodingWriter::finish
+pub struct EncodingWriter<W: Write> {
+    encoder: ReedSolomonEncoder,
+    original: Vec<W>,
+    recovery: Vec<W>,
+    shard_bytes: usize,
+    stripe: Vec<u8>,
+    total_bytes: u64,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    /// Finishes the stream by writing the final stripe
+    /// with the length trailer, then flushes all sinks
+    /// and returns them as `(original, recovery)`.
+    pub fn finish(mut self) -> io::Result<(Vec<W>, Vec<W>)> {
+        let stripe_bytes = self.stripe_bytes();
+
+        if self.stripe.len() > stripe_bytes - TRAILER_BYTES {
+            self.stripe.resize(stripe_bytes, 0);
+            self.write_stripe()?;
+        }
+
+        self.stripe.resize(stripe_bytes - TRAILER_BYTES, 0);
+        self.stripe
+            .extend_from_slice(&self.total_bytes.to_le_bytes());
+        self.write_stripe()?;
+
+        self.flush()?;
+        Ok((self.original, self.recovery))
+    }
+
+    /// Creates new writer which writes original shards to `original` sinks
+    /// and recovery shards to `recovery` sinks.
+    ///
+    /// Shard counts are given by the number of sinks and each stripe
+    /// has `shard_bytes` bytes per shard, see [`ReedSolomonEncoder::new`].
+    pub fn new(original: Vec<W>, recovery: Vec<W>, shard_bytes: usize) -> Result<Self, Error> {
+        let encoder = ReedSolomonEncoder::new(original.len(), recovery.len(), shard_bytes)?;
+        let stripe_bytes = original.len() * shard_bytes;
+
+        Ok(Self {
+            encoder,
+            original,
+            recovery,
+            shard_bytes,
+            stripe: Vec::with_capacity(stripe_bytes),
+            total_bytes: 0,
+        })
+    }
+
+    /// Returns total number of bytes written so far.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    // ============================================================
+    // PRIVATE
+
+    fn stripe_bytes(&self) -> usize {
+        self.original.len() * self.shard_bytes
+    }
+
+    // Encodes full `stripe` and writes all its shards to sinks.
+    fn write_stripe(&mut self) -> io::Result<()> {
+        debug_assert_eq!(self.stripe.len(), self.stripe_bytes());
+
+        for original_shard in self.stripe.chunks_exact(self.shard_bytes) {
+            self.encoder
+                .add_original_shard(original_shard)
+                .map_err(invalid_input)?;
+        }
+
+        let result = self.encoder.encode().map_err(invalid_input)?;
+
+        for (sink, original_shard) in self
+            .original
+            .iter_mut()
+            .zip(self.stripe.chunks_exact(self.shard_bytes))
+        {
+            sink.write_all(original_shard)?;
+        }
+
+        for (sink, recovery_shard) in self.recovery.iter_mut().zip(result.recovery_iter()) {
+            sink.write_all(recovery_shard)?;
+        }
+
+        self.stripe.clear();
+        Ok(())
+    }
+}
+
+// ======================================================================
+// EncodingWriter - IMPL WRITE
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let count = buf.len().min(self.stripe_bytes() - self.stripe.len());
+        self.stripe.extend_from_slice(&buf[..count]);
+        self.total_bytes += count as u64;
+
+        if self.stripe.len() == self.stripe_bytes() {
+            self.write_stripe()?;
+        }
+
+        Ok(count)
+    }
+
+    /// Flushes all sinks.
+    ///
+    /// This doesn't write the current partial stripe,
+    /// which is written only when it's full or in [`finish`].
+    ///
+    /// [`finish`]: EncodingWriter::finish
+    fn flush(&mut self) -> io::Result<()> {
+        for sink in self.original.iter_mut().chain(self.recovery.iter_mut()) {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// ======================================================================
+// ShardReader - PRIVATE
+
+// Reader of a single shard stream, tracking index of the next stripe.
+struct ShardReader<R> {
+    reader: R,
+    stripe_index: u64,
+}
+
+impl<R: Read> ShardReader<R> {
+    fn new(reader: Option<R>) -> Option<Self> {
+        reader.map(|reader| Self {
+            reader,
+            stripe_index: 0,
+        })
+    }
+
+    // Reads shard of stripe `stripe_index` into `shard`,
+    // first skipping shards of earlier stripes if needed.
+    //
+    // Returns `Ok(false)` if stream ends cleanly before the shard.
+    fn read(&mut self, stripe_index: u64, shard: &mut [u8]) -> io::Result<bool> {
+        debug_assert!(self.stripe_index <= stripe_index);
+
+        let skip_bytes = (stripe_index - self.stripe_index) * shard.len() as u64;
+        if skip_bytes > 0 {
+            let skipped = io::copy(&mut (&mut self.reader).take(skip_bytes), &mut io::sink())?;
+            if skipped < skip_bytes {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            self.stripe_index = stripe_index;
+        }
+
+        let mut filled = 0;
+        while filled < shard.len() {
+            match self.reader.read(&mut shard[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(count) => filled += count,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.stripe_index += 1;
+        Ok(true)
+    }
+}
+
+// ======================================================================
+// FUNCTIONS - PRIVATE
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn invalid_input(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err)
+}
+
+// Reads shard of stripe `stripe_index` from `reader` if it's available,
+// dropping `reader` if it fails and setting `ended` if it has ended.
+//
+// Returns `true` if shard was read.
+fn read_shard<R: Read>(
+    reader: &mut Option<ShardReader<R>>,
+    stripe_index: u64,
+    shard: &mut [u8],
+    ended: &mut bool,
+) -> bool {
+    match reader
+        .as_mut()
+        .map(|reader| reader.read(stripe_index, shard))
+    {
+        None => false,
+        Some(Ok(true)) => true,
+        Some(Ok(false)) => {
+            *ended = true;
+            false
+        }
+        Some(Err(_)) => {
+            *reader = None;
+            false
+        }
+    }
+}
+
+// ======================================================================
+// TESTS
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    // Reader over `data` which fails when reading past `fail_at`
+    // and records whether it has been read at all.
+    struct TestReader<'a> {
+        data: &'a [u8],
+        fail_at: usize,
+        used: Rc<Cell<bool>>,
+    }
+
+    impl<'a> TestReader<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Self::failing_at(data, usize::MAX)
+        }
+
+        fn failing_at(data: &'a [u8], fail_at: usize) -> Self {
+            Self {
+                data,
+                fail_at,
+                used: Rc::new(Cell::new(false)),
+            }
+        }
+    }
+
+    impl Read for TestReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.used.set(true);
+            if self.fail_at == 0 {
+                return Err(io::ErrorKind::ConnectionReset.into());
+            }
+            let count = buf.len().min(self.fail_at).min(self.data.len());
+            buf[..count].copy_from_slice(&self.data[..count]);
+            self.data = &self.data[count..];
+            self.fail_at -= count;
+            Ok(count)
+        }
+    }
+
+    fn test_data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    // Decodes `original` and `recovery` shard streams,
+    // returning decoded data and number of decoded stripes.
+    fn decode_stream(
+        original: Vec<Option<TestReader<'_>>>,
+        recovery: Vec<Option<TestReader<'_>>>,
+        shard_bytes: usize,
+    ) -> io::Result<(Vec<u8>, u64)> {
+        let mut reader = DecodingReader::new(original, recovery, shard_bytes).unwrap();
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Ok((data, reader.decoded_stripe_count()))
+    }
+
+    // Returns shards which `EncodingWriter` should write for `data`.
+    fn expected(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        data: &[u8],
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let stripe_bytes = original_count * shard_bytes;
+
+        let mut padded = data.to_vec();
+        let padded_len = (data.len() + TRAILER_BYTES).next_multiple_of(stripe_bytes);
+        padded.resize(padded_len - TRAILER_BYTES, 0);
+        padded.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+        let mut original = vec![Vec::new(); original_count];
+        let mut recovery = vec![Vec::new(); recovery_count];
+
+        for stripe in padded.chunks_exact(stripe_bytes) {
+            let shards: Vec<_> = stripe.chunks_exact(shard_bytes).collect();
+            for (sink, shard) in original.iter_mut().zip(&shards) {
+                sink.extend_from_slice(shard);
+            }
+            let encoded = crate::encode(original_count, recovery_count, &shards).unwrap();
+            for (sink, shard) in recovery.iter_mut().zip(encoded) {
+                sink.extend_from_slice(&shard);
+            }
+        }
+
+        (original, recovery)
+    }
+
+    fn encode_stream(
+        original_count: usize,
+        recovery_count: usize,
+        shard_bytes: usize,
+        data: &[u8],
+        write_size: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let mut writer = EncodingWriter::new(
+            vec![Vec::new(); original_count],
+            vec![Vec::new(); recovery_count],
+            shard_bytes,
+        )
+        .unwrap();
+
+        for chunk in data.chunks(write_size) {
+            writer.write_all(chunk).unwrap();
+        }
+
+        assert_eq!(writer.total_bytes(), data.len() as u64);
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn empty() {
+        let (original, recovery) = encode_stream(2, 1, 64, &[], 1);
+        assert_eq!(
+            (original.clone(), recovery.clone()),
+            expected(2, 1, 64, &[])
+        );
+        assert_eq!(original[0], [0; 64]);
+        assert_eq!(original[1][..56], [0; 56]);
+        assert_eq!(original[1][56..], 0u64.to_le_bytes());
+    }
+
+    #[test]
+    fn lengths_around_stripe_boundary() {
+        // 3 original shards of 64 bytes is 192 bytes per stripe.
+        for len in [1, 183, 184, 185, 191, 192, 193, 376, 377, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+            for write_size in [1, 50, 192, 4096] {
+                assert_eq!(
+                    encode_stream(3, 2, 64, &data, write_size),
+                    expected(3, 2, 64, &data),
+                    "len {} write_size {}",
+                    len,
+                    write_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_configuration() {
+        assert!(matches!(
+            EncodingWriter::<Vec<u8>>::new(vec![Vec::new()], Vec::new(), 64),
+            Err(Error::UnsupportedShardCount {
+                original_count: 1,
+                recovery_count: 0
+            })
+        ));
+        assert!(matches!(
+            EncodingWriter::<Vec<u8>>::new(vec![Vec::new()], vec![Vec::new()], 10),
+            Err(Error::InvalidShardSize { shard_bytes: 10 })
+        ));
+    }
+
+    #[test]
+    fn decode_pass_through() {
+        // 3 original shards of 64 bytes is 192 bytes per stripe.
+        for len in [0, 1, 183, 184, 185, 191, 192, 193, 376, 377, 1000] {
+            let data = test_data(len);
+            let (original, recovery) = encode_stream(3, 2, 64, &data, 100);
+
+            let recovery: Vec<_> = recovery
+                .iter()
+                .map(|shard| TestReader::new(shard))
+                .collect();
+            let used: Vec<_> = recovery.iter().map(|reader| reader.used.clone()).collect();
+
+            assert_eq!(
+                decode_stream(
+                    original
+                        .iter()
+                        .map(|shard| Some(TestReader::new(shard)))
+                        .collect(),
+                    recovery.into_iter().map(Some).collect(),
+                    64
+                )
+                .unwrap(),
+                (data, 0)
+            );
+
+            assert!(used.iter().all(|used| !used.get()), "{}", len);
+        }
+    }
+
+    #[test]
+    fn decode_missing_original() {
+        for len in [0, 1, 183, 184, 185, 192, 376, 377, 1000] {
+            let data = test_data(len);
+            let (original, recovery) = encode_stream(3, 2, 64, &data, 100);
+            let stripe_count = (original[0].len() / 64) as u64;
+
+            assert_eq!(
+                decode_stream(
+                    vec![None, Some(TestReader::new(&original[1])), None],
+                    recovery
+                        .iter()
+                        .map(|shard| Some(TestReader::new(shard)))
+                        .collect(),
+                    64
+                )
+                .unwrap(),
+                (data, stripe_count),
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn decode_fail_mid_stream() {
+        let data = test_data(1000);
+        let (original, recovery) = encode_stream(3, 2, 64, &data, 100);
+        let stripe_count = (original[0].len() / 64) as u64;
+
+        // Original shard 1 fails in the middle of stripe 2,
+        // so recovery shard 0 must skip stripes 0 and 1.
+        assert_eq!(
+            decode_stream(
+                vec![
+                    Some(TestReader::new(&original[0])),
+                    Some(TestReader::failing_at(&original[1], 2 * 64 + 10)),
+                    Some(TestReader::new(&original[2])),
+                ],
+                recovery
+                    .iter()
+                    .map(|shard| Some(TestReader::new(shard)))
+                    .collect(),
+                64
+            )
+            .unwrap(),
+            (data, stripe_count - 2)
+        );
+    }
+
+    #[test]
+    fn decode_truncated_original() {
+        let data = test_data(1000);
+        let (original, recovery) = encode_stream(2, 2, 64, &data, 100);
+        let stripe_count = (original[0].len() / 64) as u64;
+
+        // All original shard streams end cleanly after stripe 1.
+        assert_eq!(
+            decode_stream(
+                original
+                    .iter()
+                    .map(|shard| Some(TestReader::new(&shard[..2 * 64])))
+                    .collect(),
+                recovery
+                    .iter()
+                    .map(|shard| Some(TestReader::new(shard)))
+                    .collect(),
+                64
+            )
+            .unwrap(),
+            (data, stripe_count - 2)
+        );
+    }
+
+    #[test]
+    fn decode_not_enough_shards() {
+        let data = test_data(1000);
+        let (original, recovery) = encode_stream(3, 1, 64, &data, 100);
+
+        let mut reader = DecodingReader::new(
+            vec![
+                Some(TestReader::new(&original[0])),
+                Some(TestReader::failing_at(&original[1], 128)),
+                None,
+            ],
+            vec![Some(TestReader::new(&recovery[0]))],
+            64,
+        )
+        .unwrap();
+
+        // Stripe 1 is read ahead, so failure at stripe 2 is seen
+        // once first stripe without its held back tail has been read.
+        let mut buf = [0; 192];
+        assert_eq!(reader.read(&mut buf).unwrap(), 184);
+        assert_eq!(buf[..184], data[..184]);
+
+        let err = reader.read(&mut buf).unwrap_err();
+        assert_eq!(
+            err.into_inner().unwrap().downcast_ref::<Error>(),
+            Some(&Error::NotEnoughShards {
+                original_count: 3,
+                original_received_count: 1,
+                recovery_received_count: 1,
+            })
+        );
+        assert!(reader.read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn decode_invalid_trailer() {
+        let data = test_data(100);
+        let (mut original, _) = encode_stream(2, 1, 64, &data, 100);
+        original[1][56..].copy_from_slice(&1000u64.to_le_bytes());
+
+        let err = decode_stream(
+            original
+                .iter()
+                .map(|shard| Some(TestReader::new(shard)))
+                .collect(),
+            vec![None],
+            64,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = decode_stream(
+            vec![Some(TestReader::new(&[])), Some(TestReader::new(&[]))],
+            vec![None],
+            64,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -14,7 +14,6 @@ use crate::{
 // IntOrRange - CRATE
 
 pub(crate) trait IntOrRange {
-    fn contains(&self, item: usize) -> bool;
     // incluside
     fn min(&self) -> usize;
     // exclusive
@@ -22,10 +21,6 @@ pub(crate) trait IntOrRange {
 }
 
 impl IntOrRange for usize {
-    fn contains(&self, item: usize) -> bool {
-        *self == item
-    }
-
     fn min(&self) -> usize {
         *self
     }
@@ -36,10 +31,6 @@ impl IntOrRange for usize {
 }
 
 impl IntOrRange for Range<usize> {
-    fn contains(&self, item: usize) -> bool {
-        self.contains(&item)
-    }
-
     fn min(&self) -> usize {
         self.start
     }
@@ -63,7 +54,7 @@ where
     }
     let got = sha.finalize();
 
-    if &got[..] != hex::decode(expected).unwrap() {
+    if got[..] != hex::decode(expected).unwrap() {
         print!("GOT     : ");
         for x in got {
             print!("{:02x}", x);
@@ -90,6 +81,7 @@ pub(crate) fn generate_original(
 // ======================================================================
 // RATE ENCODER/DECODER - TEST SINGLE-ROUND ROUNDTRIP
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn roundtrip<R: Rate<E>, E: Engine, T: IntOrRange>(
     encoder: &mut R::RateEncoder,
     decoder: &mut R::RateDecoder,
@@ -114,15 +106,15 @@ pub(crate) fn roundtrip<R: Rate<E>, E: Engine, T: IntOrRange>(
     let mut original_received = FixedBitSet::with_capacity(original_count);
 
     for x in decoder_original {
-        for i in x.min()..x.max() {
-            decoder.add_original_shard(i, &original[i]).unwrap();
+        for (i, original) in original.iter().enumerate().take(x.max()).skip(x.min()) {
+            decoder.add_original_shard(i, original).unwrap();
             original_received.set(i, true);
         }
     }
 
     for x in decoder_recovery {
-        for i in x.min()..x.max() {
-            decoder.add_recovery_shard(i, recovery[i]).unwrap();
+        for (i, recovery) in recovery.iter().enumerate().take(x.max()).skip(x.min()) {
+            decoder.add_recovery_shard(i, recovery).unwrap();
         }
     }
 
@@ -136,6 +128,7 @@ pub(crate) fn roundtrip<R: Rate<E>, E: Engine, T: IntOrRange>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn roundtrip_single<R: Rate<E>, E: Engine, T: IntOrRange>(
     engine: E,
     original_count: usize,
@@ -204,6 +197,291 @@ macro_rules! roundtrip_single {
     };
 }
 
+// ======================================================================
+// RATE DECODER - TEST REPAIR
+
+// Gives decoder the shards listed in `decoder_original` / `decoder_recovery`
+// and checks that `repair` restores all other shards.
+pub(crate) fn roundtrip_repair<R: Rate<E>, E: Engine>(
+    engine: E,
+    original_count: usize,
+    recovery_count: usize,
+    decoder_original: &[usize],
+    decoder_recovery: &[usize],
+    seed: u8,
+) {
+    let shard_bytes = 128;
+    let original = generate_original(original_count, shard_bytes, seed);
+
+    let mut encoder = R::encoder(
+        original_count,
+        recovery_count,
+        shard_bytes,
+        engine.clone(),
+        None,
+    )
+    .unwrap();
+
+    for original in &original {
+        encoder.add_original_shard(original).unwrap();
+    }
+
+    let result = encoder.encode().unwrap();
+    let recovery: Vec<_> = result.recovery_iter().map(|s| s.to_vec()).collect();
+    drop(result);
+
+    let mut decoder =
+        R::decoder(original_count, recovery_count, shard_bytes, engine, None).unwrap();
+
+    for i in decoder_original {
+        decoder.add_original_shard(*i, &original[*i]).unwrap();
+    }
+    for i in decoder_recovery {
+        decoder.add_recovery_shard(*i, &recovery[*i]).unwrap();
+    }
+
+    let result = decoder.repair().unwrap();
+
+    for (i, original) in original.iter().enumerate() {
+        if decoder_original.contains(&i) {
+            assert!(result.restored_original(i).is_none());
+        } else {
+            assert_eq!(result.restored_original(i).unwrap(), original);
+        }
+    }
+
+    for (i, recovery) in recovery.iter().enumerate() {
+        if decoder_recovery.contains(&i) {
+            assert!(result.restored_recovery(i).is_none());
+        } else {
+            assert_eq!(result.restored_recovery(i).unwrap(), recovery);
+        }
+    }
+}
+
+macro_rules! test_rate_decoder_repair {
+    ($Rate:ident) => {
+        #[test]
+        fn repair() {
+            for (original_count, recovery_count, decoder_original, decoder_recovery) in [
+                (3, 2, &[0, 1, 2][..], &[][..]),
+                (3, 2, &[0, 1, 2][..], &[1][..]),
+                (3, 2, &[1][..], &[0, 1][..]),
+                (10, 6, &[0, 2, 3, 5, 6, 7, 8][..], &[0, 3, 5][..]),
+                (6, 10, &[1, 4][..], &[0, 3, 7, 9][..]),
+                (
+                    100,
+                    20,
+                    &(0..90).collect::<Vec<_>>()[..],
+                    &[0, 3, 5, 7, 9, 11, 13, 15, 17, 19][..],
+                ),
+                (20, 100, &[][..], &(40..60).collect::<Vec<_>>()[..]),
+            ] {
+                crate::test_util::roundtrip_repair::<$Rate<_>, _>(
+                    crate::engine::Naive::new(),
+                    original_count,
+                    recovery_count,
+                    decoder_original,
+                    decoder_recovery,
+                    original_count as u8,
+                );
+                crate::test_util::roundtrip_repair::<$Rate<_>, _>(
+                    crate::engine::NoSimd::new(),
+                    original_count,
+                    recovery_count,
+                    decoder_original,
+                    decoder_recovery,
+                    recovery_count as u8,
+                );
+            }
+        }
+    };
+}
+
+// ======================================================================
+// RATE DECODER - TEST ERROR CORRECTION
+
+// Corrupts given shards in different symbol columns,
+// gives all other non-missing shards to decoder
+// and checks that `decode_with_errors` finds and fixes the corruption.
+pub(crate) fn roundtrip_with_errors<R: Rate<E>, E: Engine>(
+    engine: E,
+    original_count: usize,
+    recovery_count: usize,
+    missing: (&[usize], &[usize]),
+    corrupted: (&[usize], &[usize]),
+    seed: u8,
+) {
+    let shard_bytes = 128;
+    let original = generate_original(original_count, shard_bytes, seed);
+
+    let mut encoder = R::encoder(
+        original_count,
+        recovery_count,
+        shard_bytes,
+        engine.clone(),
+        None,
+    )
+    .unwrap();
+
+    for original in &original {
+        encoder.add_original_shard(original).unwrap();
+    }
+
+    let result = encoder.encode().unwrap();
+    let mut recovery: Vec<_> = result.recovery_iter().map(|s| s.to_vec()).collect();
+    drop(result);
+
+    let mut received_original = original.clone();
+    for (n, i) in corrupted.0.iter().enumerate() {
+        received_original[*i][n % shard_bytes] ^= 0x5A;
+        received_original[*i][(n * 7 + 33) % shard_bytes] ^= 0x01;
+    }
+    for (n, i) in corrupted.1.iter().enumerate() {
+        recovery[*i][(n * 3 + 1) % shard_bytes] ^= 0xA5;
+    }
+
+    let mut decoder =
+        R::decoder(original_count, recovery_count, shard_bytes, engine, None).unwrap();
+
+    for (i, shard) in received_original.iter().enumerate() {
+        if !missing.0.contains(&i) {
+            decoder.add_original_shard(i, shard).unwrap();
+        }
+    }
+    for (i, shard) in recovery.iter().enumerate() {
+        if !missing.1.contains(&i) {
+            decoder.add_recovery_shard(i, shard).unwrap();
+        }
+    }
+
+    let result = decoder.decode_with_errors().unwrap();
+
+    let mut expected_original = corrupted.0.to_vec();
+    let mut expected_recovery = corrupted.1.to_vec();
+    expected_original.sort_unstable();
+    expected_recovery.sort_unstable();
+    assert_eq!(result.corrupted_original(), expected_original);
+    assert_eq!(result.corrupted_recovery(), expected_recovery);
+
+    for i in missing.0.iter().chain(corrupted.0) {
+        assert_eq!(result.restored_original(*i).unwrap(), original[*i]);
+    }
+}
+
+macro_rules! test_rate_decoder_error_correction {
+    ($Rate:ident) => {
+        #[test]
+        fn decode_with_errors() {
+            for (original_count, recovery_count, missing, corrupted) in [
+                (3, 2, (&[][..], &[][..]), (&[1][..], &[][..])),
+                (3, 2, (&[][..], &[][..]), (&[][..], &[1][..])),
+                (10, 6, (&[2][..], &[4][..]), (&[0, 9][..], &[][..])),
+                (10, 6, (&[][..], &[][..]), (&[3][..], &[0, 5][..])),
+                (
+                    100,
+                    20,
+                    (&[50, 60][..], &[1][..]),
+                    (&[0, 1, 99][..], &[3, 19][..]),
+                ),
+                (
+                    20,
+                    100,
+                    (&[0, 1][..], &[][..]),
+                    (&[5, 6, 7][..], &[0, 50, 99][..]),
+                ),
+                (16, 16, (&[][..], &[][..]), (&[0, 15][..], &[0, 15][..])),
+            ] {
+                crate::test_util::roundtrip_with_errors::<$Rate<_>, _>(
+                    crate::engine::Naive::new(),
+                    original_count,
+                    recovery_count,
+                    missing,
+                    corrupted,
+                    original_count as u8,
+                );
+                crate::test_util::roundtrip_with_errors::<$Rate<_>, _>(
+                    crate::engine::NoSimd::new(),
+                    original_count,
+                    recovery_count,
+                    missing,
+                    corrupted,
+                    recovery_count as u8,
+                );
+            }
+        }
+
+        #[test]
+        fn decode_with_errors_too_many_corrupted_shards() {
+            use crate::rate::{RateDecoder, RateEncoder};
+
+            let original = crate::test_util::generate_original(3, 64, 1);
+            let mut encoder = $Rate::encoder(3, 2, 64, NoSimd::new(), None).unwrap();
+            for original in &original {
+                encoder.add_original_shard(original).unwrap();
+            }
+            let result = encoder.encode().unwrap();
+            let recovery: Vec<_> = result.recovery_iter().map(|s| s.to_vec()).collect();
+            drop(result);
+
+            let mut decoder = $Rate::decoder(3, 2, 64, NoSimd::new(), None).unwrap();
+            let mut corrupted = original[1].clone();
+            corrupted[0] ^= 1;
+
+            decoder.add_original_shard(0, &original[0]).unwrap();
+            decoder.add_original_shard(1, &corrupted).unwrap();
+            decoder.add_original_shard(2, &original[2]).unwrap();
+            decoder.add_recovery_shard(0, &recovery[0]).unwrap();
+
+            // One extra shard is enough to detect but not to correct.
+            assert_eq!(
+                decoder.decode_with_errors().err(),
+                Some(Error::TooManyCorruptedShards {
+                    original_count: 3,
+                    original_received_count: 3,
+                    recovery_received_count: 1,
+                })
+            );
+        }
+
+        #[test]
+        fn decode_with_errors_too_many_corrupted_shards_across_columns() {
+            use crate::rate::{RateDecoder, RateEncoder};
+
+            let original = crate::test_util::generate_original(2, 64, 1);
+            let mut encoder = $Rate::encoder(2, 2, 64, NoSimd::new(), None).unwrap();
+            for original in &original {
+                encoder.add_original_shard(original).unwrap();
+            }
+            let result = encoder.encode().unwrap();
+            let mut recovery: Vec<_> = result.recovery_iter().map(|s| s.to_vec()).collect();
+            drop(result);
+
+            // Each column has only one error, which is correctable,
+            // but together three of four shards are corrupted.
+            let mut original = original;
+            original[0][0] ^= 1;
+            original[1][1] ^= 1;
+            recovery[0][2] ^= 1;
+
+            let mut decoder = $Rate::decoder(2, 2, 64, NoSimd::new(), None).unwrap();
+            decoder.add_original_shard(0, &original[0]).unwrap();
+            decoder.add_original_shard(1, &original[1]).unwrap();
+            decoder.add_recovery_shard(0, &recovery[0]).unwrap();
+            decoder.add_recovery_shard(1, &recovery[1]).unwrap();
+
+            assert_eq!(
+                decoder.decode_with_errors().err(),
+                Some(Error::TooManyCorruptedShards {
+                    original_count: 2,
+                    original_received_count: 2,
+                    recovery_received_count: 2,
+                })
+            );
+        }
+    };
+}
+
 // ======================================================================
 // RATE ENCODER/DECODER - TEST TWO-ROUND ROUNDTRIP
 
@@ -375,6 +653,32 @@ macro_rules! test_rate_encoder_errors {
             );
         }
 
+        #[test]
+        fn different_shard_size_in_verify() {
+            let mut encoder = $Encoder::new(1, 1, 64, NoSimd::new(), None).unwrap();
+            encoder.add_original_shard([0; 64]).unwrap();
+            assert_eq!(
+                encoder.verify([(0, [0; 128])]),
+                Err(Error::DifferentShardSize {
+                    shard_bytes: 64,
+                    got: 128
+                }),
+            );
+        }
+
+        #[test]
+        fn invalid_recovery_shard_index_in_verify() {
+            let mut encoder = $Encoder::new(1, 1, 64, NoSimd::new(), None).unwrap();
+            encoder.add_original_shard([0; 64]).unwrap();
+            assert_eq!(
+                encoder.verify([(1, [0; 64])]),
+                Err(Error::InvalidRecoveryShardIndex {
+                    recovery_count: 1,
+                    index: 1,
+                }),
+            );
+        }
+
         #[test]
         fn invalid_shard_size_in_new() {
             assert_eq!(
@@ -444,6 +748,36 @@ macro_rules! test_rate_encoder_errors {
 
 macro_rules! test_rate_decoder_errors {
     ($Decoder:ident) => {
+        #[test]
+        fn checksum_mismatch_in_add_original_shard() {
+            let mut decoder = $Decoder::new(1, 1, 64, NoSimd::new(), None).unwrap();
+            assert_eq!(
+                decoder.add_original_shard_with_checksum(0, [0; 64], 0),
+                Err(Error::ChecksumMismatch {
+                    index: 0,
+                    kind: crate::ShardKind::Original,
+                }),
+            );
+            assert!(decoder
+                .add_original_shard_with_checksum(0, [0; 64], 0x03C8_EB67)
+                .is_ok());
+        }
+
+        #[test]
+        fn checksum_mismatch_in_add_recovery_shard() {
+            let mut decoder = $Decoder::new(1, 1, 64, NoSimd::new(), None).unwrap();
+            assert_eq!(
+                decoder.add_recovery_shard_with_checksum(0, [0; 64], 0),
+                Err(Error::ChecksumMismatch {
+                    index: 0,
+                    kind: crate::ShardKind::Recovery,
+                }),
+            );
+            assert!(decoder
+                .add_recovery_shard_with_checksum(0, [0; 64], 0x03C8_EB67)
+                .is_ok());
+        }
+
         #[test]
         fn different_shard_size_in_add_original_shard() {
             let mut decoder = $Decoder::new(1, 1, 64, NoSimd::new(), None).unwrap();
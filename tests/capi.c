@@ -0,0 +1,103 @@
+/* Exercises C ABI of reed-solomon-16, run by `tests/capi.rs`. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "reed_solomon_16.h"
+
+#define ORIGINAL_COUNT 5
+#define RECOVERY_COUNT 3
+#define SHARD_BYTES 128
+
+#define CHECK(expr, expected)                                                  \
+    do {                                                                       \
+        int err_ = (expr);                                                     \
+        if (err_ != (expected)) {                                              \
+            fprintf(stderr, "%s:%d: %s returned %d (%s)\n", __FILE__,          \
+                    __LINE__, #expr, err_, rs16_error_message(err_));          \
+            exit(1);                                                           \
+        }                                                                      \
+    } while (0)
+
+static uint8_t original[ORIGINAL_COUNT][SHARD_BYTES];
+static uint8_t recovery[RECOVERY_COUNT][SHARD_BYTES];
+static uint8_t restored[ORIGINAL_COUNT][SHARD_BYTES];
+
+int main(void) {
+    size_t i, j;
+
+    for (i = 0; i < ORIGINAL_COUNT; i++) {
+        for (j = 0; j < SHARD_BYTES; j++) {
+            original[i][j] = (uint8_t)(i * 31 + j * 7);
+        }
+    }
+
+    if (!rs16_supports(ORIGINAL_COUNT, RECOVERY_COUNT)) {
+        fprintf(stderr, "shard counts not supported\n");
+        return 1;
+    }
+
+    /* ENCODE */
+
+    RS16Encoder *encoder;
+    CHECK(rs16_encoder_new(ORIGINAL_COUNT, RECOVERY_COUNT, SHARD_BYTES, &encoder), RS16_OK);
+
+    for (i = 0; i < ORIGINAL_COUNT; i++) {
+        CHECK(rs16_encoder_add_original_shard(encoder, original[i], SHARD_BYTES), RS16_OK);
+    }
+    CHECK(rs16_encoder_add_original_shard(encoder, original[0], SHARD_BYTES),
+          RS16_ERROR_TOO_MANY_ORIGINAL_SHARDS);
+
+    uint8_t *recovery_ptrs[RECOVERY_COUNT];
+    for (i = 0; i < RECOVERY_COUNT; i++) {
+        recovery_ptrs[i] = recovery[i];
+    }
+    CHECK(rs16_encoder_encode(encoder, recovery_ptrs), RS16_OK);
+
+    rs16_encoder_free(encoder);
+
+    /* DECODE, LOSING ORIGINAL SHARDS 0, 2 AND 4 */
+
+    RS16Decoder *decoder;
+    CHECK(rs16_decoder_new(ORIGINAL_COUNT, RECOVERY_COUNT, SHARD_BYTES, &decoder), RS16_OK);
+
+    CHECK(rs16_decoder_add_original_shard(decoder, 1, original[1], SHARD_BYTES), RS16_OK);
+    CHECK(rs16_decoder_add_original_shard(decoder, 3, original[3], SHARD_BYTES), RS16_OK);
+
+    uint8_t *restored_ptrs[ORIGINAL_COUNT];
+    for (i = 0; i < ORIGINAL_COUNT; i++) {
+        restored_ptrs[i] = restored[i];
+    }
+    CHECK(rs16_decoder_decode(decoder, restored_ptrs), RS16_ERROR_NOT_ENOUGH_SHARDS);
+
+    CHECK(rs16_decoder_reset(decoder, ORIGINAL_COUNT, RECOVERY_COUNT, SHARD_BYTES), RS16_OK);
+    CHECK(rs16_decoder_add_original_shard(decoder, 1, original[1], SHARD_BYTES), RS16_OK);
+    CHECK(rs16_decoder_add_original_shard(decoder, 3, original[3], SHARD_BYTES), RS16_OK);
+    for (i = 0; i < RECOVERY_COUNT; i++) {
+        CHECK(rs16_decoder_add_recovery_shard(decoder, i, recovery[i], SHARD_BYTES), RS16_OK);
+    }
+    CHECK(rs16_decoder_decode(decoder, restored_ptrs), RS16_OK);
+
+    rs16_decoder_free(decoder);
+
+    for (i = 0; i < ORIGINAL_COUNT; i += 2) {
+        if (memcmp(restored[i], original[i], SHARD_BYTES) != 0) {
+            fprintf(stderr, "original shard %zu not restored\n", i);
+            return 1;
+        }
+    }
+
+    /* ERRORS */
+
+    CHECK(rs16_encoder_new(ORIGINAL_COUNT, RECOVERY_COUNT, 100, &encoder),
+          RS16_ERROR_INVALID_SHARD_SIZE);
+    if (encoder != NULL) {
+        fprintf(stderr, "encoder not NULL after error\n");
+        return 1;
+    }
+    rs16_encoder_free(encoder);
+
+    printf("ok\n");
+    return 0;
+}
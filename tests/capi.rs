@@ -0,0 +1,67 @@
+//! Tests for C ABI, requires `capi` feature.
+//!
+//! `tests/capi.c` is compiled with system C compiler (`$CC` or `cc`)
+//! and linked against the static library, which is built here with
+//! `cargo rustc --crate-type staticlib`.
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+const HEADER: &str = "include/reed_solomon_16.h";
+
+#[test]
+fn header_is_up_to_date() {
+    let generated =
+        fs::read_to_string(PathBuf::from(env!("OUT_DIR")).join("reed_solomon_16.h")).unwrap();
+    let committed = fs::read_to_string(HEADER).unwrap();
+
+    assert!(
+        generated == committed,
+        "{} is out of date, copy it from {}",
+        HEADER,
+        env!("OUT_DIR"),
+    );
+}
+
+#[test]
+fn c_program() {
+    // `target/<profile>/deps/capi-*` -> `target/<profile>`
+    let exe = env::current_exe().unwrap();
+    let profile_dir = exe.parent().unwrap().parent().unwrap();
+    let library = profile_dir.join("libreed_solomon_16.a");
+
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "capi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .arg("--target-dir")
+        .arg(profile_dir.parent().unwrap());
+    if profile_dir.ends_with("release") {
+        cargo.arg("--release");
+    }
+    assert!(cargo.status().unwrap().success(), "building library failed");
+    let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("capi-test");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-Iinclude"])
+        .arg("tests/capi.c")
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success(), "compiling tests/capi.c failed");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(output.stdout, b"ok\n");
+}
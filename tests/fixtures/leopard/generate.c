@@ -0,0 +1,125 @@
+/*
+ * Generates Leopard-RS fixtures for `leopard` module tests.
+ *
+ * Build against Leopard (https://github.com/catid/leopard) and run
+ * in this directory:
+ *
+ *     cc -O2 -I<leopard> generate.c <leopard-build>/libleopard.a -lstdc++ -o generate
+ *     ./generate
+ *
+ * Each case is written to `<original_count>_<recovery_count>_<shard_bytes>.bin`
+ * which contains original shards followed by recovery shards
+ * `work_data[0 .. recovery_count]` of `leo_encode`.
+ */
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "leopard.h"
+
+struct fixture {
+    unsigned original_count;
+    unsigned recovery_count;
+    uint64_t shard_bytes;
+};
+
+/* Must match `FIXTURES` in `src/leopard.rs`. */
+static const struct fixture FIXTURES[] = {
+    /* power-of-two recovery_count */
+    {129, 128, 64},
+    {1, 256, 64},
+    /* non-power-of-two recovery_count, last chunk of original shards partial */
+    {300, 10, 128},
+    {1000, 24, 64},
+    {3000, 1000, 192},
+    /* more recovery shards than original shards */
+    {64, 193, 128},
+};
+
+static void *allocate(uint64_t bytes) {
+    /* `aligned_alloc` requires size to be a multiple of alignment. */
+    void *p = aligned_alloc(64, (bytes + 63) / 64 * 64);
+    if (p == NULL) {
+        fprintf(stderr, "out of memory\n");
+        exit(1);
+    }
+    return p;
+}
+
+static uint32_t next(uint32_t *state) {
+    /* xorshift32 */
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+    return *state;
+}
+
+static void generate(const struct fixture *f) {
+    unsigned work_count = leo_encode_work_count(f->original_count, f->recovery_count);
+    void **original = allocate(f->original_count * sizeof(void *));
+    void **work = allocate(work_count * sizeof(void *));
+    uint32_t state = 0x12345678 ^ f->original_count ^ (f->recovery_count << 16);
+    char path[64];
+    FILE *out;
+    unsigned i;
+    uint64_t j;
+
+    for (i = 0; i < f->original_count; i++) {
+        original[i] = allocate(f->shard_bytes);
+        for (j = 0; j < f->shard_bytes; j++) {
+            ((uint8_t *)original[i])[j] = (uint8_t)next(&state);
+        }
+    }
+    for (i = 0; i < work_count; i++) {
+        work[i] = allocate(f->shard_bytes);
+    }
+
+    LeopardResult result =
+        leo_encode(f->shard_bytes, f->original_count, f->recovery_count, work_count,
+                   (const void *const *)original, work);
+    if (result != Leopard_Success) {
+        fprintf(stderr, "leo_encode: %s\n", leo_result_string(result));
+        exit(1);
+    }
+
+    snprintf(path, sizeof(path), "%u_%u_%u.bin", f->original_count, f->recovery_count,
+             (unsigned)f->shard_bytes);
+    out = fopen(path, "wb");
+    if (out == NULL) {
+        perror(path);
+        exit(1);
+    }
+    for (i = 0; i < f->original_count; i++) {
+        fwrite(original[i], 1, f->shard_bytes, out);
+    }
+    for (i = 0; i < f->recovery_count; i++) {
+        fwrite(work[i], 1, f->shard_bytes, out);
+    }
+    fclose(out);
+
+    for (i = 0; i < f->original_count; i++) {
+        free(original[i]);
+    }
+    for (i = 0; i < work_count; i++) {
+        free(work[i]);
+    }
+    free(original);
+    free(work);
+}
+
+int main(void) {
+    size_t i;
+
+    if (leo_init()) {
+        fprintf(stderr, "leo_init failed\n");
+        return 1;
+    }
+
+    for (i = 0; i < sizeof(FIXTURES) / sizeof(FIXTURES[0]); i++) {
+        generate(&FIXTURES[i]);
+    }
+
+    return 0;
+}
@@ -0,0 +1,19 @@
+#!/bin/sh
+# Generates par2cmdline fixtures for `par2` module tests.
+#
+# Requires `par2` of par2cmdline (https://github.com/Parchive/par2cmdline)
+# in `PATH`. Input files are random, so they are checked in
+# together with `set.par2` and `set.vol*.par2`.
+#
+# Must match `FIXTURE_SLICE_SIZE` and `FIXTURE_RECOVERY_COUNT` in `src/par2.rs`.
+
+set -eu
+
+cd "$(dirname "$0")"
+
+rm -f first.bin second.bin set.par2 set.vol*.par2
+
+head -c 20000 /dev/urandom > first.bin
+head -c 1001 /dev/urandom > second.bin
+
+par2 create -q -s1024 -c5 -f0 -n1 set.par2 first.bin second.bin